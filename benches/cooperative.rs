@@ -0,0 +1,78 @@
+//! Measures the cost of the `cooperative` feature and the fairness it buys
+//!
+//! cargo bench --bench cooperative --features async
+//! cargo bench --bench cooperative --features cooperative
+
+use je_di::{DIContainer, FromAsyncWorld, async_trait};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+
+const ITERATIONS: u32 = 10_000;
+
+struct World;
+
+macro_rules! leaf {
+    ($($name:ident),+) => {
+        $(
+            struct $name;
+
+            #[async_trait]
+            impl FromAsyncWorld for $name {
+                type World<'a> = World;
+                type Error = ();
+
+                async fn from_world<'a>(_: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+                    Ok(Self)
+                }
+            }
+        )+
+    };
+}
+
+leaf!(A, B, C, D, E, F, G, H, I);
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let container = DIContainer::new(World);
+        let polls = Arc::new(AtomicUsize::new(0));
+
+        let other_task = tokio::spawn({
+            let polls = polls.clone();
+            async move {
+                loop {
+                    polls.fetch_add(1, Ordering::Relaxed);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+        tokio::task::yield_now().await;
+        polls.store(0, Ordering::Relaxed);
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let _: (A, B, C, D, E, F, G, H, I) = container.extract_async().await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        other_task.abort();
+
+        println!("cooperative: {}", cfg!(feature = "cooperative"));
+        println!(
+            "{ITERATIONS} extractions of a 9-tuple in {elapsed:?} ({:?} per extraction)",
+            elapsed / ITERATIONS
+        );
+        println!(
+            "concurrent task polled {} times during the extractions",
+            polls.load(Ordering::Relaxed)
+        );
+    });
+}
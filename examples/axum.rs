@@ -1,10 +1,14 @@
 use axum::{
-    Router,
+    Extension, Router,
     http::{StatusCode, header::AUTHORIZATION},
+    middleware::from_fn_with_state,
     response::IntoResponse,
     routing::get,
 };
-use je_di::{axum::Dependency, axum_dependency, axum_world};
+use je_di::{
+    axum::{Dependency, inject},
+    axum_dependency, axum_world,
+};
 use tokio::net::TcpListener;
 
 #[tokio::main]
@@ -12,6 +16,13 @@ async fn main() {
     let db_connection = DBConnection;
     let router = Router::new()
         .route("/user", get(get_user))
+        .route(
+            "/profile",
+            get(get_profile).route_layer(from_fn_with_state(
+                db_connection.clone(),
+                inject::<ValidatedUser, DBConnection>,
+            )),
+        )
         .with_state(db_connection);
     let listener = TcpListener::bind("[::]:3000").await.unwrap();
 
@@ -24,6 +35,11 @@ async fn get_user(
     user_id.to_string()
 }
 
+// ValidatedUser resolved by the inject middleware of the route
+async fn get_profile(Extension(ValidatedUser(user_id)): Extension<ValidatedUser>) -> String {
+    format!("profile of {user_id}")
+}
+
 struct AuthHeader(String);
 
 #[derive(Clone)]
@@ -36,6 +52,7 @@ impl DBConnection {
     }
 }
 
+#[derive(Clone)]
 struct ValidatedUser(u64);
 
 axum_world! {
@@ -0,0 +1,78 @@
+use je_di::{DIContainer, FromWorld};
+use serde::Deserialize;
+
+// defaults, usually read from a file with `config::File::with_name("config")`
+const CONFIG: &str = r#"
+service_url = "http://localhost:8080"
+
+[db]
+url = "postgres://localhost/app"
+pool_size = 4
+"#;
+
+#[derive(Deserialize)]
+struct World {
+    service_url: String,
+    db: DbConfig,
+}
+
+#[derive(Deserialize)]
+struct DbConfig {
+    url: String,
+    pool_size: u32,
+}
+
+struct Service {
+    url: String,
+}
+
+impl FromWorld for Service {
+    type World<'a> = World;
+    type Error = String;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: world.service_url.clone(),
+        })
+    }
+}
+
+struct Database {
+    url: String,
+    pool_size: u32,
+}
+
+impl FromWorld for Database {
+    type World<'a> = World;
+    type Error = String;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: world.db.url.clone(),
+            pool_size: world.db.pool_size,
+        })
+    }
+}
+
+// try `APP_DB__POOL_SIZE=16 cargo run --example config --features config`
+fn main() {
+    let config = config::Config::builder()
+        .add_source(config::File::from_str(CONFIG, config::FileFormat::Toml))
+        .add_source(
+            config::Environment::with_prefix("APP")
+                .prefix_separator("_")
+                .separator("__")
+                .try_parsing(true),
+        )
+        .build()
+        .expect("invalid configuration sources");
+
+    let container: DIContainer<World> =
+        DIContainer::from_config_crate(&config).unwrap_or_else(|e| panic!("{e}"));
+
+    let (service, database): (Service, Database) = container.extract().unwrap();
+    println!(
+        "service: {}, db: {} (pool size {})",
+        service.url, database.url, database.pool_size
+    );
+}
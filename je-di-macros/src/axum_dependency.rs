@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Attribute, Data, DeriveInput, Fields, Type, spanned::Spanned};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "AxumDependency can only be derived for structs",
+        ));
+    };
+
+    let state = required_attribute(&input.attrs, "state", &input)?;
+    let error = required_attribute(&input.attrs, "error", &input)?;
+    let name = &input.ident;
+
+    let bindings = (0..data.fields.len())
+        .map(|i| format_ident!("__je_di_field_{}", i))
+        .collect::<Vec<_>>();
+
+    let resolutions = data.fields.iter().zip(&bindings).map(|(field, binding)| {
+        let ty = &field.ty;
+        quote_spanned! {ty.span()=>
+            let #binding = ::je_di::__private::resolve_async::<#ty>(world).await?;
+        }
+    });
+
+    let checks = data.fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote_spanned! {ty.span()=>
+            __je_di_field_must_share_the_state_of_the_dependency::<#ty>();
+        }
+    });
+
+    let construct = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote!(Self { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn __je_di_field_must_share_the_state_of_the_dependency<T>()
+            where
+                T: for<'a> ::je_di::FromAsyncWorld<
+                    World<'a> = ::je_di::axum::AxumRequestPartsWorld<'a, #state>,
+                >,
+            {
+            }
+
+            #[allow(dead_code)]
+            fn __je_di_check_fields() {
+                #(#checks)*
+            }
+
+            #[::je_di::async_trait]
+            impl ::je_di::FromAsyncWorld for #name {
+                type World<'a> = ::je_di::axum::AxumRequestPartsWorld<'a, #state>;
+                type Error = #error;
+
+                async fn from_world<'a>(
+                    world: &'a Self::World<'a>,
+                ) -> ::core::result::Result<Self, Self::Error> {
+                    #(#resolutions)*
+                    ::core::result::Result::Ok(#construct)
+                }
+            }
+        };
+    })
+}
+
+fn required_attribute(attrs: &[Attribute], name: &str, input: &DeriveInput) -> syn::Result<Type> {
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .ok_or_else(|| {
+            syn::Error::new(
+                input.ident.span(),
+                format!("AxumDependency requires a #[{name}(Type)] attribute"),
+            )
+        })?;
+
+    attr.parse_args()
+}
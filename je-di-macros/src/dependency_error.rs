@@ -0,0 +1,131 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, Type, Variant, spanned::Spanned};
+
+struct WrappingVariant<'a> {
+    ident: &'a Ident,
+    field: Option<&'a Ident>,
+    ty: &'a Type,
+    from: bool,
+    source: bool,
+    response: bool,
+}
+
+impl WrappingVariant<'_> {
+    fn pattern(&self, binding: &Ident) -> TokenStream {
+        let ident = self.ident;
+        match self.field {
+            Some(field) => quote!(Self::#ident { #field: #binding }),
+            None => quote!(Self::#ident(#binding)),
+        }
+    }
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "DependencyError can only be derived for enums",
+        ));
+    };
+
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if let Some(variant) = parse_variant(variant)? {
+            variants.push(variant);
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let binding = Ident::new("error", proc_macro2::Span::call_site());
+
+    let from_impls = variants.iter().filter(|v| v.from).map(|variant| {
+        let ty = variant.ty;
+        let pattern = variant.pattern(&binding);
+        quote! {
+            impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                fn from(#binding: #ty) -> Self {
+                    #pattern
+                }
+            }
+        }
+    });
+
+    let sources = variants
+        .iter()
+        .filter(|v| v.source)
+        .map(|v| v.pattern(&binding))
+        .collect::<Vec<_>>();
+    let error_impl = (!sources.is_empty()).then(|| {
+        quote! {
+            impl #impl_generics ::std::error::Error for #name #ty_generics #where_clause {
+                fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    match self {
+                        #(#sources => ::core::option::Option::Some(#binding),)*
+                        #[allow(unreachable_patterns)]
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+
+    let responses = variants
+        .iter()
+        .filter(|v| v.response)
+        .map(|v| v.pattern(&binding))
+        .collect::<Vec<_>>();
+    let response_impl = (!responses.is_empty()).then(|| {
+        quote! {
+            impl #impl_generics ::je_di::__private::axum::response::IntoResponse
+                for #name #ty_generics #where_clause
+            {
+                fn into_response(self) -> ::je_di::__private::axum::response::Response {
+                    match self {
+                        #(#responses => ::je_di::__private::axum::response::IntoResponse::into_response(#binding),)*
+                        #[allow(unreachable_patterns)]
+                        _ => ::je_di::__private::axum::response::IntoResponse::into_response(
+                            ::je_di::__private::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                        ),
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#from_impls)*
+        #error_impl
+        #response_impl
+    })
+}
+
+fn parse_variant(variant: &Variant) -> syn::Result<Option<WrappingVariant<'_>>> {
+    let has = |name: &str| variant.attrs.iter().any(|attr| attr.path().is_ident(name));
+    let (from, source, response) = (has("from"), has("source"), has("response"));
+
+    if !(from || source || response) {
+        return Ok(None);
+    }
+
+    let field = match &variant.fields {
+        Fields::Named(fields) if fields.named.len() == 1 => &fields.named[0],
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+        _ => {
+            return Err(syn::Error::new(
+                variant.span(),
+                "#[from], #[source] and #[response] variants must have exactly one field",
+            ));
+        }
+    };
+
+    Ok(Some(WrappingVariant {
+        ident: &variant.ident,
+        field: field.ident.as_ref(),
+        ty: &field.ty,
+        from,
+        source,
+        response,
+    }))
+}
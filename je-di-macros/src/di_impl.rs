@@ -0,0 +1,190 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Expr, ExprCall, FnArg, Ident, ImplItem, ImplItemType, ItemImpl, Pat, Token,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+};
+
+pub struct DiImpl {
+    sync: bool,
+    asynchronous: bool,
+    item: ItemImpl,
+}
+
+impl Parse for DiImpl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut sync = false;
+        let mut asynchronous = false;
+
+        if !input.peek(Token![impl]) && !input.peek(Token![#]) {
+            loop {
+                if input.parse::<Option<Token![async]>>()?.is_some() {
+                    asynchronous = true;
+                } else {
+                    let mode: Ident = input.parse()?;
+                    if mode != "sync" {
+                        return Err(syn::Error::new(
+                            mode.span(),
+                            "expected `sync` or `async`",
+                        ));
+                    }
+                    sync = true;
+                }
+
+                if input.parse::<Option<Token![,]>>()?.is_none() {
+                    break;
+                }
+            }
+            input.parse::<Token![;]>()?;
+        } else {
+            sync = true;
+            asynchronous = true;
+        }
+
+        Ok(Self {
+            sync,
+            asynchronous,
+            item: input.parse()?,
+        })
+    }
+}
+
+pub fn expand(input: DiImpl) -> syn::Result<TokenStream> {
+    let item = &input.item;
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new(
+            path.span(),
+            "di_impl! takes an inherent impl, the traits are generated",
+        ));
+    }
+
+    let mut world = None;
+    let mut error = None;
+    let mut from_world = None;
+
+    for impl_item in &item.items {
+        match impl_item {
+            ImplItem::Type(ty) if ty.ident == "World" => world = Some(ty),
+            ImplItem::Type(ty) if ty.ident == "Error" => error = Some(ty),
+            ImplItem::Fn(function) if function.sig.ident == "from_world" => {
+                from_world = Some(function)
+            }
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "expected `type World<'a>`, `type Error` or `fn from_world`",
+                ));
+            }
+        }
+    }
+
+    let missing = |what: &str| syn::Error::new(item.self_ty.span(), format!("missing {what}"));
+    let world: &ImplItemType = world.ok_or_else(|| missing("`type World<'a>`"))?;
+    let error: &ImplItemType = error.ok_or_else(|| missing("`type Error`"))?;
+    let from_world = from_world.ok_or_else(|| missing("`fn from_world`"))?;
+
+    let world_arg = match from_world.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) if from_world.sig.inputs.len() == 1 => &arg.pat,
+        _ => {
+            return Err(syn::Error::new(
+                from_world.sig.inputs.span(),
+                "`from_world` takes the World as its only argument",
+            ));
+        }
+    };
+    if !matches!(**world_arg, Pat::Ident(_) | Pat::Wild(_)) {
+        return Err(syn::Error::new(
+            world_arg.span(),
+            "expected an identifier for the World argument",
+        ));
+    }
+
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let body = &from_world.block;
+
+    let mut output = TokenStream::new();
+
+    if input.sync {
+        let mut sync_body = body.clone();
+        StripAwait.visit_block_mut(&mut sync_body);
+        ResolveFromWorld(quote!(::je_di::__private::resolve)).visit_block_mut(&mut sync_body);
+
+        output.extend(quote! {
+            impl #impl_generics ::je_di::FromWorld for #self_ty #where_clause {
+                #world
+                #error
+
+                fn from_world(
+                    #world_arg: &Self::World<'_>,
+                ) -> ::core::result::Result<Self, Self::Error> #sync_body
+            }
+        });
+    }
+
+    if input.asynchronous {
+        let mut body = body.clone();
+        ResolveFromWorld(quote!(::je_di::__private::resolve_async)).visit_block_mut(&mut body);
+
+        output.extend(quote! {
+            #[::je_di::async_trait]
+            impl #impl_generics ::je_di::FromAsyncWorld for #self_ty #where_clause {
+                #world
+                #error
+
+                async fn from_world<'a>(
+                    #world_arg: &'a Self::World<'a>,
+                ) -> ::core::result::Result<Self, Self::Error> #body
+            }
+        });
+    }
+
+    Ok(output)
+}
+
+/// Replaces `expr.await` with `expr`, leaving nested async blocks untouched
+struct StripAwait;
+
+impl VisitMut for StripAwait {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Await(awaited) => {
+                *expr = (*awaited.base).clone();
+                self.visit_expr_mut(expr);
+            }
+            Expr::Async(_) => {}
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+}
+
+/// Replaces `Type::from_world(..)` calls with `resolve::<Type>(..)`, `resolve` being the path of
+/// the sync or async resolution: the nodes are logged, traced and observed like any other
+struct ResolveFromWorld(TokenStream);
+
+impl VisitMut for ResolveFromWorld {
+    fn visit_expr_call_mut(&mut self, call: &mut ExprCall) {
+        visit_mut::visit_expr_call_mut(self, call);
+
+        let Expr::Path(function) = &*call.func else {
+            return;
+        };
+        if function.qself.is_some() || function.path.segments.len() < 2 {
+            return;
+        }
+
+        let mut ty = function.path.clone();
+        let Some(last) = ty.segments.pop() else {
+            return;
+        };
+        if last.value().ident != "from_world" {
+            return;
+        }
+        ty.segments.pop_punct();
+
+        let resolve = &self.0;
+        *call.func = syn::parse_quote!(#resolve::<#ty>);
+    }
+}
@@ -0,0 +1,327 @@
+//! Derive macros for je-di
+//!
+//! These are re-exported by `je-di` behind the `derive` feature, depend on it rather than on this
+//! crate directly.
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, ItemStruct, parse_macro_input};
+
+mod axum_dependency;
+mod capabilities;
+mod dependency_error;
+mod di_impl;
+mod merge;
+mod sub_world;
+mod world;
+mod world_builder;
+
+/// # Derives the error wiring for a dependency tree
+///
+/// `FromAsyncDependency` requires
+/// `Self::Error: From<<Self::Dependency as FromAsyncWorld>::Error>`, this derive generates those
+/// conversions for an error enum.
+///
+/// Every annotated variant must hold exactly one field:
+///
+/// - `#[from]` generates `From<Field>` for the enum
+/// - `#[source]` generates [`std::error::Error`] for the enum, returning the wrapped error from
+///   `source()`, the enum must implement `Debug` and `Display`
+/// - `#[response]` generates `IntoResponse` for the enum (requires the `axum` feature), marked
+///   variants delegate to the wrapped value, every other variant responds with
+///   `500 Internal Server Error`
+///
+/// # Usage
+/// ```ignore
+/// use je_di::DependencyError;
+///
+/// #[derive(Debug, DependencyError)]
+/// enum AppError {
+///     #[from]
+///     #[source]
+///     Io(std::io::Error),
+///     #[from]
+///     #[response]
+///     Rejected(StatusCode),
+///     Other,
+/// }
+///
+/// impl std::fmt::Display for AppError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "application error")
+///     }
+/// }
+/// ```
+#[proc_macro_derive(DependencyError, attributes(from, source, response))]
+pub fn derive_dependency_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    dependency_error::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Derives an axum dependency from its fields
+///
+/// Generates a `FromAsyncWorld` implementation with `AxumRequestPartsWorld<State>` as World,
+/// resolving every field, in declaration order, as a dependency of the same World. Requires the
+/// `axum` feature.
+///
+/// - `#[state(Type)]` sets the axum State, every field must share it, a field resolved from a
+///   different State is a compile error pointing at the field
+/// - `#[error(Type)]` sets the rejection, the error of every field must convert into it through
+///   `From`
+///
+/// # Usage
+/// ```ignore
+/// use je_di::AxumDependency;
+///
+/// #[derive(AxumDependency)]
+/// #[state(DBConnection)]
+/// #[error(StatusCode)]
+/// struct Session {
+///     header: AuthHeader,
+///     db: DBConnection,
+/// }
+///
+/// async fn handler(Dependency(session): Dependency<Session>) { /* ... */ }
+/// ```
+#[proc_macro_derive(AxumDependency, attributes(state, error))]
+pub fn derive_axum_dependency(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    axum_dependency::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Derives the capabilities of a World
+///
+/// Generates `je_di::capability::Provides<Field>` for the type of every field, returning a
+/// reference to the field, and implements the capability traits listed in
+/// `#[capabilities(...)]`. Capability traits are declared with `je_di::capability!`, each
+/// requires `Provides` for its type: listing a capability the World has no field for is a
+/// compile error.
+///
+/// - `#[capabilities(skip)]` on a field excludes it
+/// - two fields of the same type are a compile error, skip all but one of them
+///
+/// # Usage
+/// ```ignore
+/// use je_di::{Capabilities, capability};
+///
+/// capability! {
+///     pub trait HasDb {
+///         fn db(&self) -> &Database;
+///     }
+/// }
+///
+/// #[derive(Capabilities)]
+/// #[capabilities(HasDb, HasHttp)]
+/// struct AppWorld {
+///     database: Database,
+///     http: HttpClient,
+///     #[capabilities(skip)]
+///     port: u16,
+/// }
+/// ```
+#[proc_macro_derive(Capabilities, attributes(capabilities))]
+pub fn derive_capabilities(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    capabilities::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Derives the sub-World accessors of a World
+///
+/// Generates `FromRef<World>` for the type of every field, cloning the field, so that every
+/// field can be used as a smaller World: `DIContainer::project::<Field>()` builds a container
+/// over it and `Projected<T, World>` resolves the dependencies declared over it from the large
+/// World. Fields must be `Clone`, share the expensive ones behind an `Arc`.
+///
+/// - `#[subworld(skip)]` excludes a field
+/// - two fields of the same type are a compile error, a type has a single projection, skip all
+///   but one of them
+///
+/// # Usage
+/// ```ignore
+/// use je_di::SubWorld;
+///
+/// #[derive(SubWorld)]
+/// struct AppWorld {
+///     database: DatabaseConfig,
+///     mailer: MailerConfig,
+///     #[subworld(skip)]
+///     secrets: Secrets,
+/// }
+///
+/// let database: Projected<Database, AppWorld> = container.extract()?;
+/// let mailer: Mailer = container.project::<MailerConfig>().extract()?;
+/// ```
+#[proc_macro_derive(SubWorld, attributes(subworld))]
+pub fn derive_sub_world(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    sub_world::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Derives the layered merge of a World
+///
+/// Generates `je_di::merge::Merge` for a struct, merging every field of the later layer into the
+/// field of the earlier one, in declaration order. Every field must implement `Merge`: `Option`
+/// fields are overridden by the later layer when it is `Some`, nested structs deriving `Merge`
+/// are merged field by field.
+///
+/// - `#[merge(replace)]` overrides the field with the value of the later layer
+///   unconditionally, for fields that don't implement `Merge`
+///
+/// # Usage
+/// ```ignore
+/// use je_di::Merge;
+///
+/// #[derive(Merge)]
+/// struct Settings {
+///     port: Option<u16>,
+///     database: DatabaseSettings,
+///     #[merge(replace)]
+///     features: Vec<String>,
+/// }
+/// ```
+#[proc_macro_derive(Merge, attributes(merge))]
+pub fn derive_merge(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    merge::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Derives a typestate builder for a World
+///
+/// Generates a `{World}Builder` with a setter per field, named after the field and taking
+/// `impl Into<FieldType>`, and a `{World}::builder()` constructor. `build()` and
+/// `build_container()`, moving the World into a new `DIContainer`, are only available once
+/// every required field is set: a missing field is a compile error, `build` not being found on
+/// the builder. Setting a required field twice is a compile error as well.
+///
+/// - fields are required by default
+/// - `#[builder(default)]` fields fall back to `Default::default()`
+/// - `#[builder(default = expr)]` fields fall back to `expr`
+/// - `Option<T>` fields are optional, their setter takes `impl Into<T>` and they fall back to
+///   `None`
+///
+/// The doc comments of the fields are copied onto their setters. The builder, its constructor
+/// and its setters share the visibility of the struct.
+///
+/// # Usage
+/// ```ignore
+/// use je_di::WorldBuilder;
+///
+/// #[derive(WorldBuilder)]
+/// struct AppWorld {
+///     database_url: String,
+///     #[builder(default = 8080)]
+///     port: u16,
+///     #[builder(default)]
+///     verbose: bool,
+///     api_key: Option<String>,
+/// }
+///
+/// let container = AppWorld::builder()
+///     .database_url("postgres://localhost")
+///     .api_key("secret")
+///     .build_container();
+/// ```
+#[proc_macro_derive(WorldBuilder, attributes(builder))]
+pub fn derive_world_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    world_builder::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Generates the sync and async implementations of a dependency from one body
+///
+/// Takes an inherent impl holding `type World<'a>`, `type Error` and `fn from_world`, and
+/// generates `FromWorld` and `FromAsyncWorld` implementations sharing the body. The sync body is
+/// the async body with every `.await` removed (except in nested async blocks), the functions
+/// called before an `.await` must have sync counterparts of the same name. Calls to
+/// `Type::from_world(world)` resolve `Type` through `FromWorld` in the sync implementation and
+/// through `FromAsyncWorld` in the async one, like the nodes of any other dependency tree.
+///
+/// The implementations to generate can be selected with a leading `sync;` or `async;`, both
+/// are generated by default (or with `sync, async;`). The async implementation requires the
+/// `async` feature.
+///
+/// # Usage
+/// ```ignore
+/// use je_di::di_impl;
+///
+/// di_impl! {
+///     impl Repository {
+///         type World<'a> = World;
+///         type Error = String;
+///
+///         fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+///             let connection = Connection::from_world(world).await?;
+///             Ok(Self { connection })
+///         }
+///     }
+/// }
+///
+/// di_impl! {
+///     async;
+///     impl Cache {
+///         /* ... */
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn di_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as di_impl::DiImpl);
+
+    di_impl::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// # Marks a struct as a World
+///
+/// Generates, next to the unchanged struct:
+///
+/// - a `DIContainer<World>` type alias, named after the struct with its `World` suffix replaced
+///   by `Container` (`AppWorld` gives `AppContainer`, `Settings` gives `SettingsContainer`), or
+///   named with `#[world(container = Name)]`
+/// - a `container(self)` method moving the World into a new container, disabled with
+///   `#[world(method = false)]`
+///
+/// The alias and the method share the visibility of the struct. The struct is emitted as is,
+/// derives and their helper attributes applied to it are unaffected.
+///
+/// # Usage
+/// ```ignore
+/// use je_di::world;
+///
+/// #[world]
+/// pub struct AppWorld {
+///     database_url: String,
+/// }
+///
+/// let container: AppContainer = AppWorld { database_url }.container();
+///
+/// #[world(container = Workers, method = false)]
+/// struct WorkerWorld;
+/// ```
+#[proc_macro_attribute]
+pub fn world(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as world::WorldArgs);
+    let input = parse_macro_input!(input as ItemStruct);
+
+    world::expand(args, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
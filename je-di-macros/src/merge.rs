@@ -0,0 +1,64 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Index, Member, spanned::Spanned};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Merge can only be derived for structs",
+        ));
+    };
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let mut merges = Vec::new();
+    for (index, field) in fields.into_iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+
+        merges.push(if is_replaced(field)? {
+            quote!(self.#member = other.#member;)
+        } else {
+            quote!(::je_di::merge::Merge::merge(&mut self.#member, other.#member);)
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::je_di::merge::Merge for #name #ty_generics #where_clause {
+            fn merge(&mut self, other: Self) {
+                #(#merges)*
+            }
+        }
+    })
+}
+
+fn is_replaced(field: &Field) -> syn::Result<bool> {
+    let mut replaced = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("merge"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("replace") {
+                replaced = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `replace`"))
+            }
+        })?;
+    }
+
+    Ok(replaced)
+}
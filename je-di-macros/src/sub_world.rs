@@ -0,0 +1,79 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{Data, DeriveInput, Field, Fields, Index, Member, spanned::Spanned};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "SubWorld can only be derived for structs",
+        ));
+    };
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let mut seen: Vec<(String, &Field)> = Vec::new();
+    let mut impls = Vec::new();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    for (index, field) in fields.into_iter().enumerate() {
+        if is_skipped(field)? {
+            continue;
+        }
+
+        let ty = &field.ty;
+        let key = ty.to_token_stream().to_string();
+        if let Some((_, first)) = seen.iter().find(|(seen, _)| *seen == key) {
+            let mut error = syn::Error::new(
+                field.span(),
+                "two fields of the same type can't both be sub-Worlds, mark one #[subworld(skip)]",
+            );
+            error.combine(syn::Error::new(first.span(), "first field of this type"));
+            return Err(error);
+        }
+        seen.push((key, field));
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+
+        impls.push(quote! {
+            impl #impl_generics ::je_di::projection::FromRef<#name #ty_generics> for #ty
+            #where_clause
+            {
+                fn from_ref(world: &#name #ty_generics) -> Self {
+                    ::core::clone::Clone::clone(&world.#member)
+                }
+            }
+        });
+    }
+
+    Ok(quote!(#(#impls)*))
+}
+
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skipped = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("subworld"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skipped = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip`"))
+            }
+        })?;
+    }
+
+    Ok(skipped)
+}
@@ -0,0 +1,109 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    GenericParam, Ident, ItemStruct, LitBool, Token,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+#[derive(Default)]
+pub struct WorldArgs {
+    container: Option<Ident>,
+    method: Option<bool>,
+}
+
+impl Parse for WorldArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = WorldArgs::default();
+
+        for arg in Punctuated::<WorldArg, Token![,]>::parse_terminated(input)? {
+            match arg {
+                WorldArg::Container(ident) => args.container = Some(ident),
+                WorldArg::Method(method) => args.method = Some(method),
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+enum WorldArg {
+    Container(Ident),
+    Method(bool),
+}
+
+impl Parse for WorldArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        if name == "container" {
+            Ok(WorldArg::Container(input.parse()?))
+        } else if name == "method" {
+            Ok(WorldArg::Method(input.parse::<LitBool>()?.value))
+        } else {
+            Err(syn::Error::new(
+                name.span(),
+                "expected `container = Name` or `method = bool`",
+            ))
+        }
+    }
+}
+
+pub fn expand(args: WorldArgs, item: ItemStruct) -> syn::Result<TokenStream> {
+    let name = &item.ident;
+    let vis = &item.vis;
+
+    let alias = args.container.unwrap_or_else(|| {
+        let name = name.to_string();
+        match name.strip_suffix("World") {
+            Some(prefix) if !prefix.is_empty() => format_ident!("{}Container", prefix),
+            _ => format_ident!("{}Container", name),
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let alias_params = item.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            quote!(const #ident: #ty)
+        }
+    });
+    let alias_params = if item.generics.params.is_empty() {
+        quote!()
+    } else {
+        quote!(<#(#alias_params),*>)
+    };
+
+    let method = args.method.unwrap_or(true).then(|| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Moves the World into a new container
+                #vis fn container(self) -> ::je_di::DIContainer<Self> {
+                    ::je_di::DIContainer::new(self)
+                }
+            }
+        }
+    });
+
+    let doc = format!("Container over [`{name}`]");
+
+    Ok(quote! {
+        #item
+
+        #[doc = #doc]
+        #vis type #alias #alias_params = ::je_di::DIContainer<#name #ty_generics>;
+
+        #method
+    })
+}
@@ -0,0 +1,299 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Field, Fields, GenericArgument, GenericParam, Generics,
+    Ident, PathArguments, Type, parse_quote, spanned::Spanned,
+};
+
+enum Kind {
+    Required,
+    Default(Option<Expr>),
+    Optional(Type),
+}
+
+struct BuilderField<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    docs: Vec<&'a Attribute>,
+    kind: Kind,
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "WorldBuilder can only be derived for structs",
+        ));
+    };
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(BuilderField::parse)
+            .collect::<syn::Result<Vec<_>>>()?,
+        Fields::Unnamed(fields) => {
+            return Err(syn::Error::new(
+                fields.span(),
+                "WorldBuilder requires named fields, the setters are named after them",
+            ));
+        }
+        Fields::Unit => Vec::new(),
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let builder = format_ident!("{}Builder", name);
+    let set = quote!(::je_di::world_builder::Set);
+    let unset = quote!(::je_di::world_builder::Unset);
+
+    let required = fields
+        .iter()
+        .filter(|field| matches!(field.kind, Kind::Required))
+        .count();
+    let states = (0..required)
+        .map(|index| format_ident!("__State{}", index))
+        .collect::<Vec<_>>();
+
+    let args = generic_args(&input.generics);
+    let where_clause = &input.generics.where_clause;
+
+    let mut declared = input.generics.clone();
+    for state in &states {
+        declared
+            .params
+            .push(parse_quote!(#state = ::je_di::world_builder::Unset));
+    }
+    let declared_params = &declared.params;
+
+    let field_idents = fields.iter().map(|field| field.ident).collect::<Vec<_>>();
+    let field_types = fields.iter().map(|field| field.ty).collect::<Vec<_>>();
+
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let unset_states = states.iter().map(|_| &unset);
+    let all_set = states.iter().map(|_| &set);
+
+    let mut setters = Vec::new();
+    let mut state_index = 0;
+    for field in &fields {
+        let ident = field.ident;
+        let docs = &field.docs;
+
+        match &field.kind {
+            Kind::Required => {
+                let before = states.iter().enumerate().map(|(index, state)| {
+                    if index == state_index {
+                        unset.clone()
+                    } else {
+                        quote!(#state)
+                    }
+                });
+                let after = states.iter().enumerate().map(|(index, state)| {
+                    if index == state_index {
+                        set.clone()
+                    } else {
+                        quote!(#state)
+                    }
+                });
+                let mut generics = input.generics.clone();
+                for (index, state) in states.iter().enumerate() {
+                    if index != state_index {
+                        generics.params.push(parse_quote!(#state));
+                    }
+                }
+                let (setter_generics, _, _) = generics.split_for_impl();
+                let ty = field.ty;
+                let others = field_idents
+                    .iter()
+                    .filter(|other| **other != ident)
+                    .collect::<Vec<_>>();
+
+                setters.push(quote! {
+                    impl #setter_generics #builder<#(#args,)* #(#before),*> #where_clause {
+                        #(#docs)*
+                        #vis fn #ident(
+                            self,
+                            value: impl ::core::convert::Into<#ty>,
+                        ) -> #builder<#(#args,)* #(#after),*> {
+                            #builder {
+                                #ident: ::core::option::Option::Some(value.into()),
+                                #(#others: self.#others,)*
+                                _state: ::core::marker::PhantomData,
+                            }
+                        }
+                    }
+                });
+                state_index += 1;
+            }
+            Kind::Default(_) | Kind::Optional(_) => {
+                let (value_ty, value) = match &field.kind {
+                    Kind::Optional(inner) => {
+                        (inner, quote!(::core::option::Option::Some(value.into())))
+                    }
+                    _ => (field.ty, quote!(value.into())),
+                };
+
+                let mut generics = input.generics.clone();
+                for state in &states {
+                    generics.params.push(parse_quote!(#state));
+                }
+                let (setter_generics, _, _) = generics.split_for_impl();
+
+                setters.push(quote! {
+                    impl #setter_generics #builder<#(#args,)* #(#states),*> #where_clause {
+                        #(#docs)*
+                        #vis fn #ident(mut self, value: impl ::core::convert::Into<#value_ty>) -> Self {
+                            self.#ident = ::core::option::Option::Some(#value);
+                            self
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    let built = fields.iter().map(|field| {
+        let ident = field.ident;
+        match &field.kind {
+            Kind::Required => quote! {
+                #ident: match self.#ident {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                }
+            },
+            Kind::Default(None) => quote! {
+                #ident: self.#ident.unwrap_or_default()
+            },
+            Kind::Default(Some(default)) => quote! {
+                #ident: self.#ident.unwrap_or_else(|| #default)
+            },
+            Kind::Optional(_) => quote! {
+                #ident: self.#ident.flatten()
+            },
+        }
+    });
+
+    let builder_doc = format!(
+        "Builder of [`{name}`], see [`{name}::builder`], generated by `#[derive(WorldBuilder)]`"
+    );
+
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #[must_use]
+        #vis struct #builder<#declared_params> #where_clause {
+            #(#field_idents: ::core::option::Option<#field_types>,)*
+            _state: ::core::marker::PhantomData<fn() -> (#(#states,)*)>,
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Starts building the World, every required field must be set before `build`
+            #vis fn builder() -> #builder<#(#args,)* #(#unset_states),*> {
+                #builder {
+                    #(#field_idents: ::core::option::Option::None,)*
+                    _state: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #(#setters)*
+
+        impl #impl_generics #builder<#(#args,)* #(#all_set),*> #where_clause {
+            /// Builds the World
+            #vis fn build(self) -> #name #ty_generics {
+                #name {
+                    #(#built,)*
+                }
+            }
+
+            /// Builds the World and moves it into a new container
+            #vis fn build_container(self) -> ::je_di::DIContainer<#name #ty_generics> {
+                ::je_di::DIContainer::new(self.build())
+            }
+        }
+    })
+}
+
+impl<'a> BuilderField<'a> {
+    fn parse(field: &'a Field) -> syn::Result<Self> {
+        let mut kind = None;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("builder"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    let default = if meta.input.peek(syn::Token![=]) {
+                        Some(meta.value()?.parse()?)
+                    } else {
+                        None
+                    };
+                    kind = Some(Kind::Default(default));
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `default` or `default = expr`"))
+                }
+            })?;
+        }
+
+        let kind = match kind {
+            Some(kind) => kind,
+            None => match option_inner(&field.ty) {
+                Some(inner) => Kind::Optional(inner.clone()),
+                None => Kind::Required,
+            },
+        };
+
+        Ok(Self {
+            ident: field.ident.as_ref().expect("named field"),
+            ty: &field.ty,
+            docs: field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .collect(),
+            kind,
+        })
+    }
+}
+
+/// Returns `T` for a field of type `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Returns the generic parameters of the struct as arguments, without their bounds
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,96 @@
+//! # Allocator-aware dependencies
+//!
+//! Request handling on an arena (a bump allocator reset after every request) wants the
+//! collections built by the dependencies allocated in the arena too. [`FromWorldIn<A>`] is the
+//! allocator-aware [`FromWorld`](crate::FromWorld): the allocator is threaded into construction,
+//! and [`DIContainer::extract_in`] resolves a dependency with an allocator borrowed for the
+//! request, the dependency can then hold `Vec<T, &A>`, `Box<T, &A>`, ... borrowing it.
+//!
+//! This module requires the nightly `allocator_api` Rust feature, enabled with the
+//! `allocator_api` feature of the crate, which doesn't build on a stable compiler.
+//!
+//! # Scope
+//!
+//! Only the dependencies implementing [`FromWorldIn`] are constructed in the allocator: it is a
+//! separate trait, the [`FromWorld`](crate::FromWorld) dependencies, the blanket implementations
+//! and the integrations of the crate keep allocating through the global allocator. A
+//! [`FromWorldIn`] implementation resolving regular dependencies gets them allocated globally,
+//! only the allocations it makes itself with the allocator land in the arena.
+//!
+//! # Usage
+//! ```
+//! # #![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+//! # #[cfg(feature = "allocator_api")] {
+//! use je_di::{DIContainer, allocator::FromWorldIn};
+//! use std::{
+//!     alloc::{AllocError, Allocator, Global, Layout},
+//!     cell::Cell,
+//!     ptr::NonNull,
+//! };
+//!
+//! /// Counts the allocations made through it
+//! #[derive(Default)]
+//! struct Arena {
+//!     allocations: Cell<usize>,
+//! }
+//!
+//! unsafe impl Allocator for Arena {
+//!     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+//!         self.allocations.set(self.allocations.get() + 1);
+//!         Global.allocate(layout)
+//!     }
+//!
+//!     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+//!         unsafe { Global.deallocate(ptr, layout) }
+//!     }
+//! }
+//!
+//! struct World {
+//!     user_ids: [u32; 3],
+//! }
+//!
+//! struct UserIds<'arena>(Vec<u32, &'arena Arena>);
+//!
+//! impl<'arena> FromWorldIn<&'arena Arena> for UserIds<'arena> {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world_in(world: &Self::World<'_>, arena: &'arena Arena) -> Result<Self, Self::Error> {
+//!         let mut ids = Vec::with_capacity_in(world.user_ids.len(), arena);
+//!         ids.extend_from_slice(&world.user_ids);
+//!         Ok(Self(ids))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { user_ids: [1, 2, 3] });
+//! let arena = Arena::default();
+//!
+//! let ids: UserIds = container.extract_in(&arena).unwrap();
+//! assert_eq!(ids.0, [1, 2, 3]);
+//! assert_eq!(arena.allocations.get(), 1);
+//! # }
+//! ```
+
+use crate::DIContainer;
+use core::alloc::Allocator;
+
+/// # Defines a dependency constructed with an allocator
+///
+/// See the [module documentation](crate::allocator)
+pub trait FromWorldIn<A: Allocator>: Sized {
+    type World<'a>;
+    type Error;
+
+    fn from_world_in(world: &Self::World<'_>, alloc: A) -> Result<Self, Self::Error>;
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, constructed with the allocations it makes in `alloc`
+    pub fn extract_in<'alloc, T, A>(&self, alloc: &'alloc A) -> Result<T, T::Error>
+    where
+        A: Allocator,
+        T: for<'a> FromWorldIn<&'alloc A, World<'a> = World>,
+    {
+        T::from_world_in(&self.world, alloc)
+    }
+}
@@ -0,0 +1,175 @@
+//! # Type-level transforms
+//!
+//! [`AndThen<D, F>`] resolves `D` and applies the transform `F` to it, [`AndThenTry<D, F>`] does
+//! the same with a fallible transform. For pure transforms of a single dependency, this is a
+//! lighter alternative to [`FromDependency`](crate::FromDependency): no World, no error, no new
+//! dependency type, the composed dependency is a type alias:
+//!
+//! ```ignore
+//! type Port = AndThen<RawPort, ParsePort>;
+//! ```
+//!
+//! The composed dependency shares the World of `D`, and its error for [`AndThen`].
+//! [`AndThenTry`] fails with the error of the transform, which must be `From<D::Error>`.
+//!
+//! # Function pointers and closures
+//!
+//! The transform is a type parameter, so it must be a type that can be called without a value.
+//! A function pointer type such as `fn(A) -> B` only names a signature: every function with
+//! that signature has this type, the type alone doesn't say which one to call. Closures and
+//! function items do have a type of their own, but it can't be written down. The transform is
+//! therefore a marker type implementing [`Transform`] (or [`TryTransform`]), whose associated
+//! function is the transform, like [`MapError`](crate::bridge::MapError) hooks.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromWorld,
+//!     and_then::{AndThen, AndThenTry, Transform, TryTransform},
+//! };
+//!
+//! struct World {
+//!     host: &'static str,
+//!     port: &'static str,
+//! }
+//!
+//! struct Settings {
+//!     host: &'static str,
+//!     port: &'static str,
+//! }
+//!
+//! impl FromWorld for Settings {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { host: world.host, port: world.port })
+//!     }
+//! }
+//!
+//! struct ToHost;
+//!
+//! impl Transform<Settings> for ToHost {
+//!     type Output = String;
+//!
+//!     fn transform(settings: Settings) -> String {
+//!         settings.host.to_uppercase()
+//!     }
+//! }
+//!
+//! struct ParsePort;
+//!
+//! impl TryTransform<Settings> for ParsePort {
+//!     type Output = u16;
+//!     type Error = String;
+//!
+//!     fn try_transform(settings: Settings) -> Result<u16, String> {
+//!         settings.port.parse().map_err(|error| format!("invalid port: {error}"))
+//!     }
+//! }
+//!
+//! type Host = AndThen<Settings, ToHost>;
+//! type Port = AndThenTry<Settings, ParsePort>;
+//!
+//! let container = DIContainer::new(World { host: "localhost", port: "8080" });
+//! let (host, port): (Host, Port) = container.extract().unwrap();
+//! assert_eq!((host.as_str(), *port), ("LOCALHOST", 8080));
+//!
+//! let container = DIContainer::new(World { host: "localhost", port: "http" });
+//! assert!(container.extract::<Port>().is_err());
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use core::{marker::PhantomData, ops::Deref};
+
+/// # Infallible transform of an `Input` dependency
+pub trait Transform<Input> {
+    type Output;
+
+    fn transform(input: Input) -> Self::Output;
+}
+
+/// # Fallible transform of an `Input` dependency
+pub trait TryTransform<Input> {
+    type Output;
+    type Error;
+
+    fn try_transform(input: Input) -> Result<Self::Output, Self::Error>;
+}
+
+/// # `D` transformed by `F`
+///
+/// See the [module documentation](crate::and_then)
+pub struct AndThen<D, F: Transform<D>> {
+    value: F::Output,
+    _marker: PhantomData<fn() -> (D, F)>,
+}
+
+impl<D, F: Transform<D>> AndThen<D, F> {
+    pub fn into_inner(self) -> F::Output {
+        self.value
+    }
+}
+
+impl<D, F: Transform<D>> Deref for AndThen<D, F> {
+    type Target = F::Output;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<D, F> FromWorld for AndThen<D, F>
+where
+    D: FromWorld,
+    F: Transform<D>,
+{
+    type World<'a> = D::World<'a>;
+    type Error = D::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: F::transform(resolve::<D>(world)?),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # `D` transformed by the fallible `F`
+///
+/// See the [module documentation](crate::and_then)
+pub struct AndThenTry<D, F: TryTransform<D>> {
+    value: F::Output,
+    _marker: PhantomData<fn() -> (D, F)>,
+}
+
+impl<D, F: TryTransform<D>> AndThenTry<D, F> {
+    pub fn into_inner(self) -> F::Output {
+        self.value
+    }
+}
+
+impl<D, F: TryTransform<D>> Deref for AndThenTry<D, F> {
+    type Target = F::Output;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<D, F> FromWorld for AndThenTry<D, F>
+where
+    D: FromWorld,
+    F: TryTransform<D>,
+    F::Error: From<D::Error>,
+{
+    type World<'a> = D::World<'a>;
+    type Error = F::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: F::try_transform(resolve::<D>(world)?)?,
+            _marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! # Wiring assertions
+//!
+//! Library authors shipping dependency implementations for a World, or applications assembling
+//! one, check that the World is fully wired with
+//! [`assert_world_provides!`](crate::assert_world_provides):
+//!
+//! ```ignore
+//! assert_world_provides!(AppWorld: UserRepository, Mailer, Clock);
+//! ```
+//!
+//! The assertion fails to compile when any of the listed types can't be resolved from the World,
+//! i.e. doesn't implement [`FromWorld`](crate::FromWorld) with `type World<'a> = AppWorld`. With
+//! the `async` prefix the types are checked against
+//! [`FromAsyncWorld`](crate::FromAsyncWorld) instead. Nothing is resolved and nothing runs: the
+//! assertion expands to a function that is never called, only type checked.
+//!
+//! # Error messages
+//!
+//! A type resolved from another World fails with the mismatch of the Worlds, naming both:
+//!
+//! ```text
+//! error[E0271]: type mismatch resolving `<Mailer as FromWorld>::World<'a> == AppWorld`
+//!   |
+//!   | assert_world_provides!(AppWorld: UserRepository, Mailer, Clock);
+//!   |                                                  ^^^^^^ type mismatch resolving ...
+//!   |
+//! note: expected this to be `AppWorld`
+//! ```
+//!
+//! A type that isn't a dependency at all fails with the missing implementation:
+//!
+//! ```text
+//! error[E0277]: the trait bound `Clock: FromWorld` is not satisfied
+//!   |
+//!   | assert_world_provides!(AppWorld: UserRepository, Mailer, Clock);
+//!   |                                                          ^^^^^ unsatisfied trait bound
+//! ```
+//!
+//! Both point at the offending type in the list.
+//!
+//! # Integration tests
+//!
+//! The assertion is an item, it can be placed at the top of any module. In an integration test,
+//! e.g. `tests/wiring.rs`, it checks the public wiring of the crate as a downstream user sees it,
+//! without a test function: the test target failing to build is the failure. For a library
+//! generic over the World, assert it for each World it is meant to be used with.
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromWorld, assert_world_provides};
+//!
+//! struct AppWorld {
+//!     url: &'static str,
+//! }
+//!
+//! struct Database(&'static str);
+//!
+//! impl FromWorld for Database {
+//!     type World<'a> = AppWorld;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.url))
+//!     }
+//! }
+//!
+//! assert_world_provides!(AppWorld: Database, (Database, Database));
+//! ```
+//!
+//! A dependency of another World fails the assertion:
+//!
+//! ```compile_fail,E0271
+//! # use je_di::{FromWorld, assert_world_provides};
+//! # struct AppWorld;
+//! struct WorkerWorld;
+//!
+//! struct Queue;
+//!
+//! impl FromWorld for Queue {
+//!     type World<'a> = WorkerWorld;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! assert_world_provides!(AppWorld: Queue);
+//! ```
+
+/// # Asserts at compile time that a World provides dependencies
+///
+/// ```ignore
+/// assert_world_provides!(World: DepA, DepB, DepC);
+/// assert_world_provides!(async World: AsyncDepA, AsyncDepB);
+/// ```
+///
+/// See the [module documentation](crate::assert)
+#[macro_export]
+macro_rules! assert_world_provides {
+    (async $world:ty: $($ty:ty),+ $(,)?) => {
+        const _: () = {
+            fn provided<T: for<'a> $crate::FromAsyncWorld<World<'a> = $world>>() {}
+
+            #[allow(dead_code)]
+            fn assert_world_provides() {
+                $(provided::<$ty>();)+
+            }
+        };
+    };
+
+    ($world:ty: $($ty:ty),+ $(,)?) => {
+        const _: () = {
+            fn provided<T: for<'a> $crate::FromWorld<World<'a> = $world>>() {}
+
+            #[allow(dead_code)]
+            fn assert_world_provides() {
+                $(provided::<$ty>();)+
+            }
+        };
+    };
+}
@@ -94,12 +94,152 @@
 //! //     }
 //! // }
 //! ```
+//!
+//! ## Injecting into the request extensions
+//!
+//! Handlers needing a dependency without taking it as an argument, or layers further down the
+//! stack, read it from the request extensions: the [`inject`] middleware resolves it once and
+//! inserts it, handlers then take it as an [`Extension`](axum::Extension). A failed resolution
+//! short-circuits the request with the rejection of the dependency.
+//!
+//! The middleware is scoped like any layer, per route with `route_layer`:
+//!
+//! ```
+//! use axum::{
+//!     Extension, Router,
+//!     body::Body,
+//!     http::{Request, StatusCode, request::Parts},
+//!     middleware::from_fn_with_state,
+//!     routing::get,
+//! };
+//! use je_di::{axum::inject, axum_world};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use tower::ServiceExt;
+//!
+//! #[derive(Clone)]
+//! struct DBConnection;
+//!
+//! #[derive(Clone)]
+//! struct ValidatedUser(u64);
+//!
+//! axum_world! {
+//!     async fn from_world(parts: &Parts, _state: &DBConnection) -> Result<ValidatedUser, StatusCode> {
+//!         let user_id = parts.headers.get("x-user-id").ok_or(StatusCode::UNAUTHORIZED)?;
+//!         let user_id = user_id.to_str().ok().and_then(|id| id.parse().ok());
+//!         user_id.map(ValidatedUser).ok_or(StatusCode::BAD_REQUEST)
+//!     }
+//! }
+//!
+//! static PROFILES_SERVED: AtomicUsize = AtomicUsize::new(0);
+//!
+//! async fn get_profile(Extension(ValidatedUser(user_id)): Extension<ValidatedUser>) -> String {
+//!     PROFILES_SERVED.fetch_add(1, Ordering::SeqCst);
+//!     format!("profile of {user_id}")
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let router = Router::new()
+//!     .route(
+//!         "/profile",
+//!         get(get_profile).route_layer(from_fn_with_state(
+//!             DBConnection,
+//!             inject::<ValidatedUser, DBConnection>,
+//!         )),
+//!     )
+//!     .route("/health", get(|| async { "ok" }))
+//!     .with_state(DBConnection);
+//!
+//! let request = Request::get("/profile").header("x-user-id", "42").body(Body::empty());
+//! let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+//! let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "profile of 42");
+//!
+//! // the rejection is the response, the handler isn't called
+//! let request = Request::get("/profile").body(Body::empty()).unwrap();
+//! let response = router.clone().oneshot(request).await.unwrap();
+//! assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+//! assert_eq!(PROFILES_SERVED.load(Ordering::SeqCst), 1);
+//!
+//! // the other routes don't resolve it
+//! let request = Request::get("/health").body(Body::empty()).unwrap();
+//! let response = router.oneshot(request).await.unwrap();
+//! assert_eq!(response.status(), StatusCode::OK);
+//! # });
+//! ```
+//!
+//! ## Deriving dependencies
+//!
+//! With the `derive` feature, `#[derive(AxumDependency)]` implements a dependency made of other
+//! axum dependencies of the same State, resolving each field in declaration order:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use axum::{
+//!     Router,
+//!     body::Body,
+//!     http::{Request, StatusCode, header::AUTHORIZATION, request::Parts},
+//!     routing::get,
+//! };
+//! use je_di::{AxumDependency, axum::Dependency, axum_world};
+//! use tower::ServiceExt;
+//!
+//! #[derive(Clone)]
+//! struct DBConnection(&'static str);
+//!
+//! axum_world! {
+//!     async fn from_world(_parts: &Parts, state: &DBConnection) -> Result<DBConnection, StatusCode> {
+//!         Ok(state.clone())
+//!     }
+//! }
+//!
+//! struct AuthHeader(String);
+//!
+//! axum_world! {
+//!     async fn from_world(parts: &Parts, _state: &DBConnection) -> Result<AuthHeader, StatusCode> {
+//!         let header = parts.headers.get(AUTHORIZATION).ok_or(StatusCode::UNAUTHORIZED)?;
+//!         Ok(AuthHeader(header.to_str().map_err(|_| StatusCode::BAD_REQUEST)?.to_string()))
+//!     }
+//! }
+//!
+//! #[derive(AxumDependency)]
+//! #[state(DBConnection)]
+//! #[error(StatusCode)]
+//! struct Session {
+//!     header: AuthHeader,
+//!     db: DBConnection,
+//! }
+//!
+//! async fn whoami(Dependency(session): Dependency<Session>) -> String {
+//!     format!("{} on {}", session.header.0, session.db.0)
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let router = Router::new()
+//!     .route("/whoami", get(whoami))
+//!     .with_state(DBConnection("primary"));
+//!
+//! let request = Request::get("/whoami").header(AUTHORIZATION, "alice").body(Body::empty());
+//! let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+//! let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "alice on primary");
+//!
+//! // a field failing to resolve rejects the request
+//! let request = Request::get("/whoami").body(Body::empty()).unwrap();
+//! let response = router.oneshot(request).await.unwrap();
+//! assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+//! # });
+//! # }
+//! ```
 
-use crate::async_dependency::FromAsyncWorld;
+use crate::{
+    async_dependency::{BorrowFromAsyncWorld, FromAsyncWorld},
+    resolve::resolve_async,
+};
 use axum::{
-    extract::{FromRequest, FromRequestParts, Request},
+    extract::{FromRequest, FromRequestParts, MatchedPath, Request},
     http::request::Parts,
-    response::IntoResponse,
+    middleware::Next,
+    response::{IntoResponse, Response},
 };
 
 /// Alias for a dependency that implements FromRequestParts via FromAsyncWorld
@@ -108,6 +248,11 @@ pub type AxumRequestPartsWorld<'a, State> = (&'a Parts, &'a State);
 /// Alias for a dependency that implements FromRequest via FromAsyncWorld
 pub type AxumRequestWorld<'a, State> = (Request, &'a State);
 
+/// Alias for a route aware dependency that implements FromRequestParts via FromAsyncWorld
+///
+/// The [`MatchedPath`] is `None` when no route matched (fallback handlers)
+pub type AxumRouteWorld<'a, State> = (&'a Parts, Option<&'a MatchedPath>, &'a State);
+
 /// # Axum dependency extractor
 ///
 /// implements [`FromRequest`]/[`FromRequestParts`]
@@ -149,6 +294,214 @@ where
     }
 }
 
+/// # Axum dependency borrowing from the request
+///
+/// Holds a `T` implementing [`BorrowFromAsyncWorld`] with [`AxumRequestPartsWorld`] as World,
+/// `T` may borrow from the [`Parts`] and the State for `'a`.
+///
+/// # Limitations
+///
+/// [`FromRequestParts::from_request_parts`] lends the [`Parts`] for the duration of the call
+/// only, the extracted value can't borrow from them: `DependencyRef` can't be a handler
+/// argument. Take the [`Parts`] (and the State) as handler arguments instead and extract the
+/// dependency in the handler body, where it can borrow them for the rest of the handler.
+///
+/// # Usage
+/// ```
+/// use axum::{
+///     Router,
+///     body::Body,
+///     extract::State,
+///     http::{HeaderValue, Request, StatusCode, header::AUTHORIZATION, request::Parts},
+///     routing::get,
+/// };
+/// use je_di::{axum::DependencyRef, axum_world};
+/// use tower::ServiceExt;
+///
+/// #[derive(Clone)]
+/// struct DBConnection;
+///
+/// // view of the AUTHORIZATION header, borrowed from the request
+/// struct HeaderView<'a>(&'a HeaderValue);
+///
+/// axum_world! {
+///     async fn borrow_from_world<'a>(
+///         parts: &'a Parts,
+///         _state: &'a DBConnection
+///     ) -> Result<HeaderView<'a>, StatusCode> {
+///         parts.headers.get(AUTHORIZATION).map(HeaderView).ok_or(StatusCode::UNAUTHORIZED)
+///     }
+/// }
+///
+/// async fn handler(parts: Parts, State(state): State<DBConnection>) -> Result<String, StatusCode> {
+///     let header = DependencyRef::<HeaderView>::extract(&parts, &state).await?;
+///
+///     // the view borrows the header of the request, nothing was copied
+///     assert!(std::ptr::eq(header.0, &parts.headers[AUTHORIZATION]));
+///     Ok(header.0.to_str().unwrap_or_default().to_string())
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let router = Router::new().route("/", get(handler)).with_state(DBConnection);
+///
+/// let request = Request::get("/").header(AUTHORIZATION, "Bearer token").body(Body::empty());
+/// let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+/// assert_eq!(response.status(), StatusCode::OK);
+/// let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+/// assert_eq!(body, "Bearer token");
+///
+/// let request = Request::get("/").body(Body::empty()).unwrap();
+/// let response = router.oneshot(request).await.unwrap();
+/// assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+/// # });
+/// ```
+pub struct DependencyRef<'a, T> {
+    value: T,
+    _lifetime: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T> DependencyRef<'a, T> {
+    // not an `async fn`: the future must be declared `Send`, otherwise proving it inside axum
+    // handlers fails with "implementation of `BorrowFromAsyncWorld` is not general enough"
+    #[allow(clippy::manual_async_fn)]
+    pub fn extract<State>(
+        parts: &'a Parts,
+        state: &'a State,
+    ) -> impl Future<Output = Result<Self, T::Error>> + Send + 'a
+    where
+        T: BorrowFromAsyncWorld<'a, World = AxumRequestPartsWorld<'a, State>> + Send + 'a,
+        State: Sync,
+    {
+        async move {
+            Ok(Self {
+                value: T::borrow_from_world((parts, state)).await?,
+                _lifetime: std::marker::PhantomData,
+            })
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for DependencyRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// # Route aware axum dependency extractor
+///
+/// implements [`FromRequestParts`] where:
+///
+/// - `T` implements [`FromAsyncWorld`] where [`FromAsyncWorld::World`] = [`AxumRouteWorld`]
+/// - `T::Error` implements [`IntoResponse`]
+///
+/// Enables route aware construction, like per endpoint rate limiters
+///
+/// # Usage
+/// ```
+/// use axum::{
+///     Router,
+///     body::Body,
+///     extract::MatchedPath,
+///     http::{Request, StatusCode, request::Parts},
+///     routing::get,
+/// };
+/// use je_di::{axum::RouteDependency, axum_world};
+/// use tower::ServiceExt;
+///
+/// #[derive(Clone)]
+/// struct Limits;
+///
+/// // the rate limit of the matched route
+/// struct RateLimit(u32);
+///
+/// axum_world! {
+///     async fn from_world(
+///         _parts: &Parts,
+///         route: Option<&MatchedPath>,
+///         _state: &Limits
+///     ) -> Result<RateLimit, StatusCode> {
+///         match route.map(MatchedPath::as_str) {
+///             Some("/users/{id}") => Ok(RateLimit(10)),
+///             Some(_) => Ok(RateLimit(100)),
+///             None => Err(StatusCode::NOT_FOUND),
+///         }
+///     }
+/// }
+///
+/// async fn limit(RouteDependency(RateLimit(limit)): RouteDependency<RateLimit>) -> String {
+///     limit.to_string()
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let router = Router::new()
+///     .route("/users/{id}", get(limit))
+///     .route("/health", get(limit))
+///     .with_state(Limits);
+///
+/// for (path, limit) in [("/users/42", "10"), ("/health", "100")] {
+///     let request = Request::get(path).body(Body::empty()).unwrap();
+///     let response = router.clone().oneshot(request).await.unwrap();
+///     assert_eq!(response.status(), StatusCode::OK);
+///
+///     let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+///     assert_eq!(body, limit);
+/// }
+/// # });
+/// ```
+pub struct RouteDependency<T>(pub T);
+
+impl<State, T> FromRequestParts<State> for RouteDependency<T>
+where
+    T: for<'a> FromAsyncWorld<World<'a> = AxumRouteWorld<'a, State>>,
+    T::Error: IntoResponse,
+    State: Sync,
+{
+    type Rejection = T::Error;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &State,
+    ) -> Result<Self, Self::Rejection> {
+        let parts = &*parts;
+        let matched_path = parts.extensions.get::<MatchedPath>();
+
+        Ok(RouteDependency(
+            resolve_async::<T>(&(parts, matched_path, state)).await?,
+        ))
+    }
+}
+
+/// # Middleware inserting `T` into the request extensions
+///
+/// Resolves `T` from the [`AxumRequestPartsWorld`] of the request, for
+/// [`from_fn_with_state`](axum::middleware::from_fn_with_state), responds with the rejection when
+/// it fails. See the [module documentation](crate::axum#injecting-into-the-request-extensions)
+pub async fn inject<T, State>(
+    axum::extract::State(state): axum::extract::State<State>,
+    request: Request,
+    next: Next,
+) -> Response
+where
+    T: for<'a> FromAsyncWorld<World<'a> = AxumRequestPartsWorld<'a, State>> + Clone + Send + Sync,
+    T::Error: IntoResponse,
+    State: Sync,
+{
+    let (mut parts, body) = request.into_parts();
+    let value = match resolve_async::<T>(&(&parts, &state)).await {
+        Ok(value) => value,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    parts.extensions.insert(value);
+    next.run(Request::from_parts(parts, body)).await
+}
+
 /// # Axum integration entry point
 ///
 /// Define a FromAsyncWorld implementation that uses (Parts/Request, State) as World to enable seamless
@@ -166,6 +519,27 @@ where
 ///         // implementaiton returning Result<Self, RejectionType>
 ///     }
 /// }
+///
+/// // borrowing variant, extracted with DependencyRef
+/// axum_world! {
+///     async fn borrow_from_world<'a>(
+///         parts: &'a Parts,
+///         state: &'a StateType
+///     ) -> Result<Type<'a>, RejectionType> {
+///         // implementaiton returning Result<Type<'a>, RejectionType>
+///     }
+/// }
+///
+/// // route aware variant, extracted with RouteDependency
+/// axum_world! {
+///     async fn from_world(
+///         parts: &Parts,
+///         route: Option<&MatchedPath>,
+///         state: &StateType
+///     ) -> Result<Type, RejectionType> {
+///         // implementaiton returning Result<Self, RejectionType>
+///     }
+/// }
 /// ```
 #[macro_export]
 macro_rules! axum_world {
@@ -210,6 +584,51 @@ macro_rules! axum_world {
             }
         }
     };
+
+    (
+        async fn borrow_from_world<$lt:lifetime>(
+            $parts:ident: &$parts_lt:lifetime Parts,
+            $state_ident:ident: &$state_lt:lifetime $state:ty
+        ) -> Result<$ty:ty, $error:ty> { $($expr:tt)* }
+    ) => {
+        #[$crate::async_trait]
+        impl<$lt> $crate::async_dependency::BorrowFromAsyncWorld<$lt> for $ty {
+            type World = $crate::axum::AxumRequestPartsWorld<$lt, $state>;
+            type Error = $error;
+
+            async fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+                #[allow(unused)]
+                let $parts = world.0;
+                #[allow(unused)]
+                let $state_ident = world.1;
+                $($expr)*
+            }
+        }
+    };
+
+    (
+        async fn from_world(
+            $parts:ident: &Parts,
+            $route:ident: Option<&MatchedPath>,
+            $state_ident:ident: &$state:ty
+        ) -> Result<$ty:ty, $error:ty> { $($expr:tt)* }
+    ) => {
+        #[$crate::async_trait]
+        impl $crate::async_dependency::FromAsyncWorld for $ty {
+            type World<'a> = $crate::axum::AxumRouteWorld<'a, $state>;
+            type Error = $error;
+
+            async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+                #[allow(unused)]
+                let $parts = world.0;
+                #[allow(unused)]
+                let $route = world.1;
+                #[allow(unused)]
+                let $state_ident = world.2;
+                $($expr)*
+            }
+        }
+    };
 }
 /// # Defines an axum aware dependency
 ///
@@ -0,0 +1,73 @@
+//! # Batch resolution
+//!
+//! For batch jobs over many Worlds, e.g. one World per tenant, [`extract_each`] resolves the same
+//! `T` from every World of a slice.
+//!
+//! The results are returned in the order of the Worlds, a failed resolution doesn't stop the
+//! batch.
+//!
+//! # Concurrency
+//!
+//! [`extract_each_async`] (behind the `batch` feature) resolves up to `concurrency` Worlds at a
+//! time on the current task, a concurrency of 1 resolves them sequentially. The results are still
+//! returned in the order of the Worlds, not in completion order.
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromWorld, batch::extract_each};
+//!
+//! struct Tenant {
+//!     name: &'static str,
+//! }
+//!
+//! struct Schema(String);
+//!
+//! impl FromWorld for Schema {
+//!     type World<'a> = Tenant;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         match world.name {
+//!             "" => Err("unnamed tenant".to_string()),
+//!             name => Ok(Self(format!("tenant_{name}"))),
+//!         }
+//!     }
+//! }
+//!
+//! let tenants = [Tenant { name: "acme" }, Tenant { name: "" }, Tenant { name: "initech" }];
+//!
+//! let schemas = extract_each::<Schema, _>(&tenants);
+//! assert_eq!(schemas[0].as_ref().unwrap().0, "tenant_acme");
+//! assert!(schemas[1].is_err());
+//! assert_eq!(schemas[2].as_ref().unwrap().0, "tenant_initech");
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use alloc::vec::Vec;
+
+/// Resolves `T` from every World, in order
+pub fn extract_each<T, World>(worlds: &[World]) -> Vec<Result<T, T::Error>>
+where
+    T: for<'a> FromWorld<World<'a> = World>,
+{
+    worlds.iter().map(resolve::<T>).collect()
+}
+
+/// Resolves `T` from every World with at most `concurrency` resolutions in flight, in order
+#[cfg(feature = "batch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "batch")))]
+pub async fn extract_each_async<T, World>(
+    worlds: &[World],
+    concurrency: core::num::NonZeroUsize,
+) -> Vec<Result<T, T::Error>>
+where
+    T: for<'a> crate::FromAsyncWorld<World<'a> = World>,
+{
+    use futures::StreamExt;
+
+    futures::stream::iter(worlds)
+        .map(crate::resolve::resolve_async::<T>)
+        .buffered(concurrency.get())
+        .collect()
+        .await
+}
@@ -0,0 +1,110 @@
+//! # Async initialization, sync usage
+//!
+//! Some dependencies need an `async fn` to be built, opening a connection, but are used
+//! synchronously afterwards. [`BlockingInit<T>`] resolves the [`FromAsyncWorld`] dependency `T`
+//! from a sync [`FromWorld`] resolution, blocking on its future through the tokio runtime
+//! [`Handle`] of the World: a sync container can hold async-built dependencies.
+//!
+//! The World exposes the handle through [`AsRef<Handle>`].
+//!
+//! # Runtime requirement
+//!
+//! [`Handle::block_on`] blocks the calling thread until the resolution completes. It panics when
+//! called from an async context, including a task spawned on the runtime: extract
+//! `BlockingInit` from plain threads, or from [`spawn_blocking`](tokio::task::spawn_blocking).
+//! The resolution runs on the calling thread, IO and timers are driven by the runtime of the
+//! handle, which must have the drivers `T` uses enabled.
+//!
+//! # Startup only
+//!
+//! Blocking a thread on IO doesn't scale: resolve `BlockingInit` dependencies once at startup,
+//! e.g. into a [`SingletonContainer`](crate::singleton::SingletonContainer), not per request on
+//! hot paths, where `T` should be resolved with
+//! [`extract_async`](crate::DIContainer::extract_async) instead.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromAsyncWorld, async_trait, blocking::BlockingInit};
+//! use tokio::runtime::{Handle, Runtime};
+//!
+//! struct World {
+//!     runtime: Handle,
+//!     url: &'static str,
+//! }
+//!
+//! impl AsRef<Handle> for World {
+//!     fn as_ref(&self) -> &Handle {
+//!         &self.runtime
+//!     }
+//! }
+//!
+//! struct Connection {
+//!     url: &'static str,
+//! }
+//!
+//! impl Connection {
+//!     fn query(&self) -> String {
+//!         format!("queried {}", self.url)
+//!     }
+//! }
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Connection {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         tokio::task::yield_now().await;
+//!         Ok(Self { url: world.url })
+//!     }
+//! }
+//!
+//! let runtime = Runtime::new().unwrap();
+//! let container = DIContainer::new(World {
+//!     runtime: runtime.handle().clone(),
+//!     url: "postgres://localhost",
+//! });
+//!
+//! let connection: BlockingInit<Connection> = container.extract().unwrap();
+//! assert_eq!(connection.query(), "queried postgres://localhost");
+//! ```
+
+use crate::{FromAsyncWorld, FromWorld, resolve::resolve_async};
+use std::ops::Deref;
+use tokio::runtime::Handle;
+
+/// # Async dependency `T` resolved synchronously, see the [module documentation](crate::blocking)
+pub struct BlockingInit<T> {
+    value: T,
+}
+
+impl<T> BlockingInit<T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for BlockingInit<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, W> FromWorld for BlockingInit<T>
+where
+    T: for<'a> FromAsyncWorld<World<'a> = W>,
+    W: AsRef<Handle>,
+{
+    type World<'a> = W;
+    type Error = T::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let handle = world.as_ref();
+
+        Ok(Self {
+            value: handle.block_on(resolve_async::<T>(world))?,
+        })
+    }
+}
@@ -0,0 +1,136 @@
+//! # Boxed errors
+//!
+//! Applications that only report errors don't need every extraction to carry the precise error
+//! type of its root. [`BoxContainer<World>`] extracts any dependency with a [`BoxError`] as
+//! error, boxing whatever error the dependency returned: extractions of dependencies with
+//! different error types can share a `?` in the same function.
+//!
+//! # Bound
+//!
+//! Boxing requires `T::Error: Into<BoxError>`, which holds for every
+//! `Error + Send + Sync + 'static` error, as well as for `String` and `&str`. Errors borrowing
+//! from the World, or holding an `Rc`, can't be boxed.
+//!
+//! The original error can be recovered with [`downcast`](BoxError::downcast) on the box.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, boxed::BoxError};
+//!
+//! struct World {
+//!     port: &'static str,
+//! }
+//!
+//! struct Port(u16);
+//!
+//! impl FromWorld for Port {
+//!     type World<'a> = World;
+//!     type Error = std::num::ParseIntError;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         world.port.parse().map(Self)
+//!     }
+//! }
+//!
+//! struct Host(&'static str);
+//!
+//! impl FromWorld for Host {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("localhost"))
+//!     }
+//! }
+//!
+//! fn address(world: World) -> Result<String, BoxError> {
+//!     let container = DIContainer::new(world).into_boxed();
+//!
+//!     let host: Host = container.extract()?;
+//!     let port: Port = container.extract()?;
+//!     Ok(format!("{}:{}", host.0, port.0))
+//! }
+//!
+//! assert_eq!(address(World { port: "8080" }).unwrap(), "localhost:8080");
+//!
+//! let error = address(World { port: "http" }).unwrap_err();
+//! assert!(error.downcast_ref::<std::num::ParseIntError>().is_some());
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::error::Error;
+
+/// Boxed error of any dependency
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// # Container boxing the errors of its extractions
+pub struct BoxContainer<World> {
+    container: DIContainer<World>,
+}
+
+impl<World> BoxContainer<World> {
+    pub fn new(world: World) -> Self {
+        Self {
+            container: DIContainer::new(world),
+        }
+    }
+
+    /// The underlying container, its extractions return the precise errors
+    pub fn container(&self) -> &DIContainer<World> {
+        &self.container
+    }
+
+    pub fn into_container(self) -> DIContainer<World> {
+        self.container
+    }
+
+    /// Extracts `T`, boxing its error
+    pub fn extract<T>(&self) -> Result<T, BoxError>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+        T::Error: Into<BoxError>,
+    {
+        resolve::<T>(&self.container.world).map_err(Into::into)
+    }
+
+    /// Extracts the async `T`, boxing its error
+    ///
+    /// # Usage
+    /// ```
+    /// # use je_di::{DIContainer, FromAsyncWorld, async_trait};
+    /// # struct World;
+    /// # struct Database;
+    /// # #[async_trait]
+    /// # impl FromAsyncWorld for Database {
+    /// #     type World<'a> = World;
+    /// #     type Error = std::io::Error;
+    /// #     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+    /// #         Err(std::io::Error::other("connection refused"))
+    /// #     }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let container = DIContainer::new(World).into_boxed();
+    ///
+    /// let error = container.extract_async::<Database>().await.err().unwrap();
+    /// assert_eq!(error.to_string(), "connection refused");
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn extract_async<T>(&self) -> Result<T, BoxError>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World>,
+        T::Error: Into<BoxError>,
+    {
+        crate::resolve::resolve_async::<T>(&self.container.world)
+            .await
+            .map_err(Into::into)
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Wraps the container into a [`BoxContainer`]
+    pub fn into_boxed(self) -> BoxContainer<World> {
+        BoxContainer { container: self }
+    }
+}
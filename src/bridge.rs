@@ -0,0 +1,138 @@
+//! # Sync to async bridge
+//!
+//! [`SyncBridge<T, Error>`] resolves a sync [`FromWorld`] dependency `T` as a
+//! [`FromAsyncWorld`] one, converting its error into the async `Error` through a
+//! [`MapError`] hook: an async dependency tree can then depend on sync dependencies whose error
+//! types differ, adding context to their errors on the way.
+//!
+//! The conversion defaults to [`ViaFrom`], which requires `Error: From<T::Error>`. Any type
+//! implementing [`MapError`] can be used instead, e.g. to wrap the error with the name of the
+//! dependency, or when the `From` implementation can't be written because neither error type is
+//! local to the crate.
+//!
+//! # Coherence
+//!
+//! The bridge is a wrapper rather than a blanket `impl<T: FromWorld> FromAsyncWorld for T`:
+//! such an impl would overlap with the blanket implementation of [`FromAsyncWorld`] for every
+//! [`FromAsyncDependency`](crate::FromAsyncDependency), and with every type implementing both
+//! traits by hand (or through `di_impl!`). Types implementing both traits don't need the bridge,
+//! it is meant for sync dependencies with no async implementation.
+//!
+//! `T` is resolved on the executor, the bridge suits cheap sync dependencies (configuration,
+//! values read from the World), not blocking ones.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromAsyncDependency, FromWorld, async_trait,
+//!     bridge::{MapError, SyncBridge},
+//! };
+//!
+//! struct World {
+//!     port: &'static str,
+//! }
+//!
+//! struct Port(u16);
+//!
+//! impl FromWorld for Port {
+//!     type World<'a> = World;
+//!     type Error = std::num::ParseIntError;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         world.port.parse().map(Self)
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! struct ServerError(String);
+//!
+//! struct WithContext;
+//!
+//! impl MapError<std::num::ParseIntError, ServerError> for WithContext {
+//!     fn map_err(error: std::num::ParseIntError) -> ServerError {
+//!         ServerError(format!("invalid port: {error}"))
+//!     }
+//! }
+//!
+//! struct Server(u16);
+//!
+//! #[async_trait]
+//! impl FromAsyncDependency for Server {
+//!     type World<'a> = World;
+//!     type Error = ServerError;
+//!     type Dependency = SyncBridge<Port, ServerError, WithContext>;
+//!
+//!     async fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         port: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self(port.0))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let container = DIContainer::new(World { port: "8080" });
+//! let server: Server = container.extract_async().await.unwrap();
+//! assert_eq!(server.0, 8080);
+//!
+//! let container = DIContainer::new(World { port: "http" });
+//! let error = container.extract_async::<Server>().await.err().unwrap();
+//! assert_eq!(error.0, "invalid port: invalid digit found in string");
+//! # });
+//! ```
+
+use crate::{FromAsyncWorld, FromWorld, async_trait, resolve::resolve};
+use std::{marker::PhantomData, ops::Deref};
+
+/// # Conversion of the error of a bridged sync dependency
+pub trait MapError<Source, Target> {
+    fn map_err(error: Source) -> Target;
+}
+
+/// # Converts the error through [`From`]
+pub struct ViaFrom;
+
+impl<Source, Target: From<Source>> MapError<Source, Target> for ViaFrom {
+    fn map_err(error: Source) -> Target {
+        Target::from(error)
+    }
+}
+
+/// # Sync dependency `T` resolved as an async one, see the [module documentation](crate::bridge)
+pub struct SyncBridge<T, Error, M = ViaFrom> {
+    value: T,
+    _marker: PhantomData<fn() -> (Error, M)>,
+}
+
+impl<T, Error, M> SyncBridge<T, Error, M> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, Error, M> Deref for SyncBridge<T, Error, M> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[async_trait]
+impl<T, W, E, M> FromAsyncWorld for SyncBridge<T, E, M>
+where
+    T: for<'a> FromWorld<World<'a> = W> + Send + 'static,
+    W: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+    M: MapError<T::Error, E> + 'static,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: resolve::<T>(world).map_err(M::map_err)?,
+            _marker: PhantomData,
+        })
+    }
+}
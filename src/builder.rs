@@ -0,0 +1,125 @@
+//! # Builder based registration
+//!
+//! [`DIContainerBuilder`] records which dependencies are provided and which roots the
+//! application is going to extract, [`DIContainerBuilder::validate`] then reports the wiring
+//! gaps before the first extraction.
+//!
+//! # Limitations
+//!
+//! Rust has no way to enumerate trait implementations, the builder only knows what it is told:
+//!
+//! - the required dependencies are gathered from [`DescribeDependencies`], a hand written
+//!   description that doesn't match the actual [`FromWorld`] implementation can't be detected
+//! - a missing [`FromWorld`] implementation is still a compile error, `validate` reports
+//!   missing `provide` registrations, not missing implementations
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, DescribeDependencies, FromDependency, FromWorld};
+//!
+//! struct World {
+//!     username: String,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Printer(String);
+//!
+//! impl FromWorld for Printer {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.username.clone()))
+//!     }
+//! }
+//!
+//! impl DescribeDependencies for Printer {}
+//!
+//! struct Looper(Printer);
+//!
+//! impl FromDependency for Looper {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Printer;
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         printer: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self(printer.clone()))
+//!     }
+//! }
+//!
+//! let world = || World { username: "ferris".to_string() };
+//!
+//! // `Looper` is extracted but never provided
+//! let builder = DIContainer::builder(world())
+//!     .provide::<Printer>()
+//!     .root::<Looper>();
+//! assert_eq!(builder.validate(), [std::any::type_name::<Looper>()]);
+//!
+//! let builder = DIContainer::builder(world())
+//!     .provide::<Printer>()
+//!     .provide::<Looper>()
+//!     .root::<Looper>();
+//! assert!(builder.validate().is_empty());
+//!
+//! let container = builder.build();
+//! assert_eq!(container.extract::<Looper>().unwrap().0.0, "ferris");
+//! ```
+
+use crate::{DIContainer, DependencyGraph, DescribeDependencies, FromWorld};
+use std::{any::TypeId, collections::HashSet};
+
+pub struct DIContainerBuilder<World> {
+    world: World,
+    provided: HashSet<TypeId>,
+    roots: DependencyGraph,
+}
+
+impl<World> DIContainerBuilder<World> {
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            provided: HashSet::new(),
+            roots: DependencyGraph::new(),
+        }
+    }
+
+    /// Registers `T` as provided
+    pub fn provide<T>(mut self) -> Self
+    where
+        T: for<'a> FromWorld<World<'a> = World> + 'static,
+    {
+        self.provided.insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Declares `T` as a root, every node of its dependency tree is required to be provided
+    pub fn root<T>(mut self) -> Self
+    where
+        T: for<'a> FromWorld<World<'a> = World> + DescribeDependencies,
+    {
+        T::describe(&mut self.roots);
+        self
+    }
+
+    /// Returns the type names of the required dependencies that were not provided
+    pub fn validate(&self) -> Vec<&'static str> {
+        self.roots
+            .nodes()
+            .filter(|(type_id, _)| !self.provided.contains(type_id))
+            .map(|(_, type_name)| type_name)
+            .collect()
+    }
+
+    pub fn build(self) -> DIContainer<World> {
+        DIContainer::new(self.world)
+    }
+}
+
+impl<World> DIContainer<World> {
+    pub fn builder(world: World) -> DIContainerBuilder<World> {
+        DIContainerBuilder::new(world)
+    }
+}
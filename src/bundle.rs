@@ -0,0 +1,212 @@
+//! # Resolved bundles
+//!
+//! [`DIContainer::extract_bundle`] resolves a dependency tree like
+//! [`DIContainer::extract`](crate::DIContainer::extract), and keeps the [`Debug`] rendering of
+//! every intermediate node in a [`ResolvedBundle`] next to the root, to inspect what a complex
+//! graph was actually built from.
+//!
+//! Like [`DescribeDependencies`](crate::DescribeDependencies), the shape of the tree is given by
+//! the types themselves through [`BundleDependencies`]: every [`FromDependency`] type implements
+//! it automatically, leaf types (implementing [`FromWorld`] directly) need an empty
+//! implementation:
+//!
+//! ```ignore
+//! impl BundleDependencies for Config {}
+//! ```
+//!
+//! # `Debug` bound
+//!
+//! Every node of the tree, the root included, must implement [`Debug`]: a single node without it
+//! makes the whole tree unavailable to `extract_bundle`. The elements of a tuple dependency are
+//! recorded as separate nodes, the tuple itself is not.
+//!
+//! # Memory cost
+//!
+//! The intermediate nodes are rendered with `{:?}` as they are resolved and the strings are kept
+//! for as long as the bundle lives, on top of the root: large intermediates (buffers, caches,
+//! collections) are copied in full into their rendering. Nodes are keyed by type name, a type
+//! resolved more than once in the tree is kept once, with its last rendering. This is a
+//! debugging tool, keep [`DIContainer::extract`](crate::DIContainer::extract) on the hot path.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromDependency, FromWorld, bundle::BundleDependencies};
+//!
+//! struct World;
+//!
+//! #[derive(Debug)]
+//! struct Config {
+//!     url: &'static str,
+//! }
+//!
+//! impl FromWorld for Config {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { url: "https://example.com" })
+//!     }
+//! }
+//!
+//! impl BundleDependencies for Config {}
+//!
+//! #[derive(Debug)]
+//! struct Client {
+//!     url: &'static str,
+//! }
+//!
+//! impl FromDependency for Client {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Config;
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, config: &Config) -> Result<Self, Self::Error> {
+//!         Ok(Self { url: config.url })
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! struct Service;
+//!
+//! impl FromDependency for Service {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = (Client, Config);
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, _: &(Client, Config)) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let bundle = container.extract_bundle::<Service>().unwrap();
+//! assert_eq!(bundle.intermediates().len(), 2);
+//!
+//! assert_eq!(bundle.get::<Client>(), Some(r#"Client { url: "https://example.com" }"#));
+//! assert_eq!(bundle.get::<Config>(), Some(r#"Config { url: "https://example.com" }"#));
+//! ```
+
+use crate::{DIContainer, FromDependency, FromWorld, resolve::resolve};
+use alloc::{collections::BTreeMap, format, string::String};
+use core::fmt::Debug;
+
+/// # Root of a dependency tree along with the rendering of its intermediate nodes
+#[derive(Debug, Clone)]
+pub struct ResolvedBundle<T> {
+    root: T,
+    intermediates: BTreeMap<&'static str, String>,
+}
+
+impl<T> ResolvedBundle<T> {
+    pub fn root(&self) -> &T {
+        &self.root
+    }
+
+    pub fn into_root(self) -> T {
+        self.root
+    }
+
+    /// [`Debug`] rendering of the intermediate nodes, by type name
+    pub fn intermediates(&self) -> &BTreeMap<&'static str, String> {
+        &self.intermediates
+    }
+
+    /// [`Debug`] rendering of the intermediate node of type `U`, if it is part of the tree
+    pub fn get<U>(&self) -> Option<&str> {
+        self.intermediates
+            .get(core::any::type_name::<U>())
+            .map(String::as_str)
+    }
+
+    pub fn into_parts(self) -> (T, BTreeMap<&'static str, String>) {
+        (self.root, self.intermediates)
+    }
+}
+
+/// # Resolves a node, recording the rendering of its dependencies
+///
+/// The default implementation resolves a leaf, with no dependency to record
+pub trait BundleDependencies: FromWorld + Debug {
+    fn resolve_bundled(
+        world: &Self::World<'_>,
+        intermediates: &mut BTreeMap<&'static str, String>,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let _ = intermediates;
+        resolve::<Self>(world)
+    }
+
+    /// Records `self` as an intermediate node
+    fn record(&self, intermediates: &mut BTreeMap<&'static str, String>) {
+        intermediates.insert(core::any::type_name::<Self>(), format!("{self:?}"));
+    }
+}
+
+impl<T> BundleDependencies for T
+where
+    T: FromDependency + Debug,
+    T::Dependency: BundleDependencies,
+{
+    fn resolve_bundled(
+        world: &Self::World<'_>,
+        intermediates: &mut BTreeMap<&'static str, String>,
+    ) -> Result<Self, Self::Error> {
+        let dependency = T::Dependency::resolve_bundled(world, intermediates)?;
+        dependency.record(intermediates);
+
+        T::from_dependency(world, &dependency)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($first_n:tt:$first_name:ident, $($n:tt:$name:ident),+) => {
+        impl<$first_name, $($name),+> BundleDependencies for ($first_name, $($name),+)
+        where
+            $first_name: BundleDependencies,
+            $($name: BundleDependencies + for<'a> FromWorld<World<'a> = $first_name::World<'a>, Error = $first_name::Error>),+
+        {
+            fn resolve_bundled(
+                world: &Self::World<'_>,
+                intermediates: &mut BTreeMap<&'static str, String>,
+            ) -> Result<Self, Self::Error> {
+                Ok((
+                    $first_name::resolve_bundled(world, intermediates)?,
+                    $($name::resolve_bundled(world, intermediates)?),+
+                ))
+            }
+
+            fn record(&self, intermediates: &mut BTreeMap<&'static str, String>) {
+                self.$first_n.record(intermediates);
+                $(self.$n.record(intermediates);)+
+            }
+        }
+    };
+}
+
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7, 8:Dep8);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2);
+impl_tuple!(0:Dep0, 1:Dep1);
+
+impl<World> DIContainer<World> {
+    /// Extracts `T` along with the [`Debug`] rendering of every intermediate node of its tree
+    pub fn extract_bundle<T>(&self) -> Result<ResolvedBundle<T>, T::Error>
+    where
+        T: BundleDependencies + for<'a> FromWorld<World<'a> = World>,
+    {
+        let mut intermediates = BTreeMap::new();
+        let root = T::resolve_bundled(&self.world, &mut intermediates)?;
+
+        Ok(ResolvedBundle {
+            root,
+            intermediates,
+        })
+    }
+}
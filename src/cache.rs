@@ -0,0 +1,183 @@
+//! # Keyed caching
+//!
+//! [`Cached<T, K>`] builds `T` once per cache key and shares it across extractions, the key is
+//! computed from the World by [`CacheKey`], e.g. one client per host.
+//!
+//! The cached values are stored in a [`KeyedCache<K, T>`] World field, exposed through
+//! [`AsRef`], so the cache lives as long as the World it belongs to.
+//!
+//! # Eviction
+//!
+//! Entries are never evicted automatically, use [`KeyedCache::remove`] or [`KeyedCache::clear`].
+//!
+//! # Thread safety
+//!
+//! The cache is guarded by a mutex which is not held while `T` is built: two extractions missing
+//! the same key concurrently both build `T`, the first to finish is cached and returned to both.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, cache::{CacheKey, Cached, KeyedCache}};
+//!
+//! struct World {
+//!     host: String,
+//!     clients: KeyedCache<Host, Client>,
+//! }
+//!
+//! impl AsRef<KeyedCache<Host, Client>> for World {
+//!     fn as_ref(&self) -> &KeyedCache<Host, Client> {
+//!         &self.clients
+//!     }
+//! }
+//!
+//! #[derive(Clone, PartialEq, Eq, Hash)]
+//! struct Host(String);
+//!
+//! impl CacheKey<World> for Host {
+//!     fn cache_key(world: &World) -> Self {
+//!         Host(world.host.clone())
+//!     }
+//! }
+//!
+//! struct Client {
+//!     host: String,
+//! }
+//!
+//! impl FromWorld for Client {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { host: world.host.clone() })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     host: "example.com".to_string(),
+//!     clients: KeyedCache::new(),
+//! });
+//!
+//! let first: Cached<Client, Host> = container.extract().unwrap();
+//! let second: Cached<Client, Host> = container.extract().unwrap();
+//! assert_eq!(first.host, "example.com");
+//! assert!(std::sync::Arc::ptr_eq(&first.into_arc(), &second.into_arc()));
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// # Computes the cache key of a [`Cached`] dependency from the World
+pub trait CacheKey<World>: Hash + Eq {
+    fn cache_key(world: &World) -> Self;
+}
+
+/// # Cache of `T` instances by key, held by the World
+pub struct KeyedCache<K, T> {
+    entries: Mutex<HashMap<K, Arc<T>>>,
+}
+
+impl<K, T> Default for KeyedCache<K, T> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Hash + Eq, T> KeyedCache<K, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<Arc<T>> {
+        self.lock().get(key).cloned()
+    }
+
+    /// Caches `value` unless `key` is already present, returns the cached value
+    pub fn insert(&self, key: K, value: T) -> Arc<T> {
+        self.lock()
+            .entry(key)
+            .or_insert_with(|| Arc::new(value))
+            .clone()
+    }
+
+    pub fn remove(&self, key: &K) -> Option<Arc<T>> {
+        self.lock().remove(key)
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, Arc<T>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// # `T` cached per [`CacheKey`] `K`
+pub struct Cached<T, K> {
+    value: Arc<T>,
+    _marker: PhantomData<fn() -> K>,
+}
+
+impl<T, K> Cached<T, K> {
+    pub fn into_arc(self) -> Arc<T> {
+        self.value
+    }
+}
+
+impl<T, K> Clone for Cached<T, K> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, K> Deref for Cached<T, K> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, K> FromWorld for Cached<T, K>
+where
+    T: FromWorld,
+    K: for<'a> CacheKey<T::World<'a>>,
+    for<'a> T::World<'a>: AsRef<KeyedCache<K, T>>,
+{
+    type World<'a> = T::World<'a>;
+    type Error = T::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let key = K::cache_key(world);
+        let cache = world.as_ref();
+
+        let value = match cache.get(&key) {
+            Some(value) => value,
+            None => cache.insert(key, resolve::<T>(world)?),
+        };
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
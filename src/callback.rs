@@ -0,0 +1,137 @@
+//! # Callback injection
+//!
+//! [`BoxedFn<Args, Ret>`] is a cloneable function, a World field holding a strategy (pricing rule,
+//! id generator, clock) that dependencies need. [`Callback<Args, Ret, World>`] resolves by
+//! cloning it out of the World through [`AsRef<BoxedFn<Args, Ret>>`], the World can hold
+//! several callbacks as long as their signatures differ, otherwise wrap them in newtypes
+//! implementing [`FromWorld`](crate::FromWorld) themselves.
+//!
+//! Multiple arguments are passed as a tuple.
+//!
+//! # Async
+//!
+//! The function is always `Send + Sync`, so callbacks can be resolved by the async traits and
+//! called from any task. It is a plain sync function: to inject an async strategy, return a boxed
+//! future from it.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, callback::{BoxedFn, Callback}};
+//!
+//! struct World {
+//!     discount: BoxedFn<u64, u64>,
+//! }
+//!
+//! impl AsRef<BoxedFn<u64, u64>> for World {
+//!     fn as_ref(&self) -> &BoxedFn<u64, u64> {
+//!         &self.discount
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     discount: BoxedFn::new(|price| price * 9 / 10),
+//! });
+//!
+//! let discount: Callback<u64, u64, World> = container.extract().unwrap();
+//! assert_eq!(discount.call(100), 90);
+//! ```
+
+use crate::FromWorld;
+use alloc::sync::Arc;
+use core::{convert::Infallible, fmt, marker::PhantomData};
+
+/// # Cloneable, shareable function from `Args` to `Ret`
+pub struct BoxedFn<Args, Ret> {
+    function: Arc<dyn Fn(Args) -> Ret + Send + Sync>,
+}
+
+impl<Args, Ret> BoxedFn<Args, Ret> {
+    pub fn new(function: impl Fn(Args) -> Ret + Send + Sync + 'static) -> Self {
+        Self {
+            function: Arc::new(function),
+        }
+    }
+
+    pub fn call(&self, args: Args) -> Ret {
+        (self.function)(args)
+    }
+}
+
+impl<Args, Ret> Clone for BoxedFn<Args, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            function: self.function.clone(),
+        }
+    }
+}
+
+impl<Args, Ret> fmt::Debug for BoxedFn<Args, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedFn").finish_non_exhaustive()
+    }
+}
+
+impl<Args, Ret, F> From<F> for BoxedFn<Args, Ret>
+where
+    F: Fn(Args) -> Ret + Send + Sync + 'static,
+{
+    fn from(function: F) -> Self {
+        Self::new(function)
+    }
+}
+
+/// # [`BoxedFn`] cloned out of the World
+pub struct Callback<Args, Ret, World, Error = Infallible> {
+    pub function: BoxedFn<Args, Ret>,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<Args, Ret, World, Error> Callback<Args, Ret, World, Error> {
+    pub fn into_inner(self) -> BoxedFn<Args, Ret> {
+        self.function
+    }
+}
+
+impl<Args, Ret, World, Error> core::ops::Deref for Callback<Args, Ret, World, Error> {
+    type Target = BoxedFn<Args, Ret>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.function
+    }
+}
+
+impl<Args, Ret, W, E> FromWorld for Callback<Args, Ret, W, E>
+where
+    W: AsRef<BoxedFn<Args, Ret>>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            function: world.as_ref().clone(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[crate::async_trait]
+impl<Args, Ret, W, E> crate::FromAsyncWorld for Callback<Args, Ret, W, E>
+where
+    Args: 'static,
+    Ret: 'static,
+    W: AsRef<BoxedFn<Args, Ret>> + Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            function: world.as_ref().clone(),
+            _marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! # Capabilities
+//!
+//! A dependency needing a database and nothing else from its World can be written once for every
+//! World providing a database: its implementation is generic over the World, bounded by
+//! capability traits such as `HasDb` or `HasHttp`. Resolving it from a World missing a capability
+//! is a compile error naming the missing trait.
+//!
+//! [`Provides<T>`] is the standard capability, a World providing a `T`.
+//! [`capability!`](macro@crate::capability) declares named capability traits over it, with an
+//! accessor method, and with the `derive` feature `#[derive(Capabilities)]` implements
+//! [`Provides`] for the fields of a World and the capabilities listed in
+//! `#[capabilities(HasDb, HasHttp)]`.
+//!
+//! # Composing with the World GAT
+//!
+//! The capability bounds are put on a World type parameter of the implementation, which
+//! [`FromWorld::World`](crate::FromWorld::World) is set to: `impl<W: HasDb> FromWorld for
+//! Repository<W>` with `type World<'a> = W`. The World doesn't depend on `'a`, so
+//! `Repository<W>: for<'a> FromWorld<World<'a> = W>` holds and the container of any World with
+//! the capability extracts it. The dependency carries `W` in its type, usually as a
+//! `PhantomData<fn() -> W>`, as an implementation can't introduce a type parameter its type
+//! doesn't use.
+//!
+//! Worlds borrowing for `'a`, e.g. [`Scoped`](crate::scope::Scoped), bound the World for every
+//! lifetime instead: `where for<'a> Scoped<'a, W, S>: HasDb`.
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use je_di::{Capabilities, DIContainer, FromWorld, capability};
+//! use std::marker::PhantomData;
+//!
+//! #[derive(Clone)]
+//! struct Database(&'static str);
+//!
+//! #[derive(Clone)]
+//! struct HttpClient(&'static str);
+//!
+//! capability! {
+//!     trait HasDb {
+//!         fn db(&self) -> &Database;
+//!     }
+//!
+//!     trait HasHttp {
+//!         fn http(&self) -> &HttpClient;
+//!     }
+//! }
+//!
+//! #[derive(Capabilities)]
+//! #[capabilities(HasDb, HasHttp)]
+//! struct ApiWorld {
+//!     database: Database,
+//!     http: HttpClient,
+//! }
+//!
+//! #[derive(Capabilities)]
+//! #[capabilities(HasDb)]
+//! struct WorkerWorld {
+//!     database: Database,
+//!     #[capabilities(skip)]
+//!     concurrency: usize,
+//! }
+//!
+//! struct UserRepository<W> {
+//!     database: Database,
+//!     _world: PhantomData<fn() -> W>,
+//! }
+//!
+//! impl<W: HasDb> FromWorld for UserRepository<W> {
+//!     type World<'a> = W;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { database: world.db().clone(), _world: PhantomData })
+//!     }
+//! }
+//!
+//! let api = DIContainer::new(ApiWorld {
+//!     database: Database("api-db"),
+//!     http: HttpClient("https://example.com"),
+//! });
+//! let worker = DIContainer::new(WorkerWorld { database: Database("worker-db"), concurrency: 4 });
+//!
+//! let repository: UserRepository<_> = api.extract().unwrap();
+//! assert_eq!(repository.database.0, "api-db");
+//!
+//! let repository: UserRepository<_> = worker.extract().unwrap();
+//! assert_eq!(repository.database.0, "worker-db");
+//! # }
+//! ```
+//!
+//! A dependency requiring a capability the World lacks doesn't compile:
+//!
+//! ```compile_fail
+//! # use je_di::{DIContainer, FromWorld, capability::Provides};
+//! # use std::marker::PhantomData;
+//! # struct HttpClient;
+//! je_di::capability! {
+//!     trait HasHttp {
+//!         fn http(&self) -> &HttpClient;
+//!     }
+//! }
+//!
+//! struct Crawler<W>(PhantomData<fn() -> W>);
+//!
+//! impl<W: HasHttp> FromWorld for Crawler<W> {
+//!     type World<'a> = W;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(PhantomData))
+//!     }
+//! }
+//!
+//! struct WorkerWorld;
+//!
+//! let crawler: Crawler<_> = DIContainer::new(WorkerWorld).extract().unwrap();
+//! ```
+
+/// # The World provides a `T`, the standard capability
+pub trait Provides<T: ?Sized> {
+    fn provide(&self) -> &T;
+}
+
+/// # Declares named capability traits
+///
+/// Each trait requires [`Provides`] for the returned type, and implements its accessor through
+/// it: implementing the trait for a World providing the type takes an empty impl, generated by
+/// `#[derive(Capabilities)]`.
+///
+/// ```ignore
+/// capability! {
+///     /// The World holds a database connection
+///     pub trait HasDb {
+///         fn db(&self) -> &Database;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! capability {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis trait $name:ident {
+            fn $method:ident(&self) -> &$ty:ty;
+        }
+    )+) => {
+        $(
+            $(#[$attr])*
+            $vis trait $name: $crate::capability::Provides<$ty> {
+                fn $method(&self) -> &$ty {
+                    <Self as $crate::capability::Provides<$ty>>::provide(self)
+                }
+            }
+        )+
+    };
+}
@@ -0,0 +1,126 @@
+//! # Fallback chains
+//!
+//! [`DIContainer::extract_chain`] tries providers in order until one resolves: a primary store
+//! falling back to a replica, then to a local cache. Unlike racing (`race` feature) the providers
+//! are resolved sequentially and the chain stops at the first success, the next providers are
+//! never resolved.
+//!
+//! The chain is a tuple of 2 to 5 providers sharing a World and an error type, if every provider
+//! fails the error of the last one is returned.
+//!
+//! # Output unification
+//!
+//! The providers are different types, the chain returns the first success converted into a
+//! common `Output` through [`Into`]: either an enum with a variant per provider, or a trait
+//! object, e.g. with `impl From<Primary> for Box<dyn Storage>`.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//!
+//! struct World {
+//!     primary_up: bool,
+//! }
+//!
+//! trait Storage {
+//!     fn name(&self) -> &'static str;
+//! }
+//!
+//! struct Primary;
+//! struct Replica;
+//!
+//! impl Storage for Primary {
+//!     fn name(&self) -> &'static str {
+//!         "primary"
+//!     }
+//! }
+//!
+//! impl Storage for Replica {
+//!     fn name(&self) -> &'static str {
+//!         "replica"
+//!     }
+//! }
+//!
+//! impl FromWorld for Primary {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         world.primary_up.then_some(Self).ok_or_else(|| "primary is down".to_string())
+//!     }
+//! }
+//!
+//! impl FromWorld for Replica {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl From<Primary> for Box<dyn Storage> {
+//!     fn from(primary: Primary) -> Self {
+//!         Box::new(primary)
+//!     }
+//! }
+//!
+//! impl From<Replica> for Box<dyn Storage> {
+//!     fn from(replica: Replica) -> Self {
+//!         Box::new(replica)
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { primary_up: false });
+//!
+//! let storage: Box<dyn Storage> = container.extract_chain::<(Primary, Replica), _>().unwrap();
+//! assert_eq!(storage.name(), "replica");
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+
+/// # Providers tried in order, see the [module documentation](crate::chain)
+///
+/// Implemented for tuples of 2 to 5 providers
+pub trait FallbackChain<World, Output> {
+    type Error;
+
+    /// Returns the first provider resolving, or the error of the last one
+    fn resolve_chain(world: &World) -> Result<Output, Self::Error>;
+}
+
+macro_rules! impl_chain {
+    ($first:ident, $($name:ident),+) => {
+        impl<World, Output, $first, $($name),+> FallbackChain<World, Output> for ($first, $($name),+)
+        where
+            $first: for<'a> FromWorld<World<'a> = World> + Into<Output>,
+            $($name: for<'a> FromWorld<World<'a> = World, Error = $first::Error> + Into<Output>),+
+        {
+            type Error = $first::Error;
+
+            fn resolve_chain(world: &World) -> Result<Output, Self::Error> {
+                let result = resolve::<$first>(world).map(Into::into);
+                $(
+                    let result = result.or_else(|_| resolve::<$name>(world).map(Into::into));
+                )+
+
+                result
+            }
+        }
+    };
+}
+
+impl_chain!(Dep0, Dep1, Dep2, Dep3, Dep4);
+impl_chain!(Dep0, Dep1, Dep2, Dep3);
+impl_chain!(Dep0, Dep1, Dep2);
+impl_chain!(Dep0, Dep1);
+
+impl<World> DIContainer<World> {
+    /// Extracts the first provider of the chain `C` that resolves, converted into `Output`
+    pub fn extract_chain<C, Output>(&self) -> Result<Output, C::Error>
+    where
+        C: FallbackChain<World, Output>,
+    {
+        C::resolve_chain(&self.world)
+    }
+}
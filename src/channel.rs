@@ -0,0 +1,216 @@
+//! # Channel injection
+//!
+//! The World holds the sending half of a channel, [`ChannelSender<S, World>`] resolves by cloning
+//! it out of the World through [`AsRef<S>`]. Any cloneable sender works: std, tokio (behind the
+//! `tokio` feature) or an [`EventBus`].
+//!
+//! [`EventBus<Event>`] fans an event out to multiple senders, std and tokio senders can be mixed
+//! on the same bus.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, channel::{ChannelSender, EventBus}};
+//! use std::sync::mpsc;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Event {
+//!     UserCreated(u32),
+//! }
+//!
+//! struct World {
+//!     events: mpsc::Sender<Event>,
+//!     bus: EventBus<Event>,
+//! }
+//!
+//! impl AsRef<mpsc::Sender<Event>> for World {
+//!     fn as_ref(&self) -> &mpsc::Sender<Event> {
+//!         &self.events
+//!     }
+//! }
+//!
+//! impl AsRef<EventBus<Event>> for World {
+//!     fn as_ref(&self) -> &EventBus<Event> {
+//!         &self.bus
+//!     }
+//! }
+//!
+//! let (events, received) = mpsc::channel();
+//! let (audit, audited) = mpsc::channel();
+//! let (metrics, measured) = mpsc::channel();
+//!
+//! let container = DIContainer::new(World {
+//!     events,
+//!     bus: EventBus::new().with(audit).with(metrics),
+//! });
+//!
+//! let sender: ChannelSender<mpsc::Sender<Event>, World> = container.extract().unwrap();
+//! sender.send(Event::UserCreated(1)).unwrap();
+//! assert_eq!(received.recv(), Ok(Event::UserCreated(1)));
+//!
+//! let bus: ChannelSender<EventBus<Event>, World> = container.extract().unwrap();
+//! assert_eq!(bus.publish(Event::UserCreated(2)), 2);
+//! assert_eq!(audited.recv(), Ok(Event::UserCreated(2)));
+//! assert_eq!(measured.recv(), Ok(Event::UserCreated(2)));
+//! ```
+
+use crate::FromWorld;
+use std::{convert::Infallible, marker::PhantomData, sync::Arc, sync::mpsc};
+
+/// # Sender cloned out of the World
+pub struct ChannelSender<S, World, Error = Infallible> {
+    pub sender: S,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<S, World, Error> ChannelSender<S, World, Error> {
+    pub fn into_inner(self) -> S {
+        self.sender
+    }
+}
+
+impl<S, World, Error> std::ops::Deref for ChannelSender<S, World, Error> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sender
+    }
+}
+
+impl<S, W, E> FromWorld for ChannelSender<S, W, E>
+where
+    S: Clone,
+    W: AsRef<S>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sender: world.as_ref().clone(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[crate::async_trait]
+impl<S, W, E> crate::FromAsyncWorld for ChannelSender<S, W, E>
+where
+    S: Clone + Send + 'static,
+    W: AsRef<S> + Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sender: world.as_ref().clone(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # Sending half of a channel usable by an [`EventBus`]
+pub trait EventSender<Event>: Send + Sync {
+    /// Sends without blocking, returns the event back if the channel is closed or full
+    fn send_event(&self, event: Event) -> Result<(), Event>;
+}
+
+impl<Event: Send> EventSender<Event> for mpsc::Sender<Event> {
+    fn send_event(&self, event: Event) -> Result<(), Event> {
+        self.send(event).map_err(|error| error.0)
+    }
+}
+
+impl<Event: Send> EventSender<Event> for mpsc::SyncSender<Event> {
+    fn send_event(&self, event: Event) -> Result<(), Event> {
+        self.try_send(event).map_err(|error| match error {
+            mpsc::TrySendError::Full(event) | mpsc::TrySendError::Disconnected(event) => event,
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<Event: Send> EventSender<Event> for tokio::sync::mpsc::Sender<Event> {
+    fn send_event(&self, event: Event) -> Result<(), Event> {
+        self.try_send(event).map_err(|error| match error {
+            tokio::sync::mpsc::error::TrySendError::Full(event)
+            | tokio::sync::mpsc::error::TrySendError::Closed(event) => event,
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<Event: Send> EventSender<Event> for tokio::sync::mpsc::UnboundedSender<Event> {
+    fn send_event(&self, event: Event) -> Result<(), Event> {
+        self.send(event).map_err(|error| error.0)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<Event: Send> EventSender<Event> for tokio::sync::broadcast::Sender<Event> {
+    fn send_event(&self, event: Event) -> Result<(), Event> {
+        self.send(event).map(|_| ()).map_err(|error| error.0)
+    }
+}
+
+/// # Fans events out to multiple senders
+///
+/// Cloning the bus shares its senders
+pub struct EventBus<Event> {
+    senders: Vec<Arc<dyn EventSender<Event>>>,
+}
+
+impl<Event> Clone for EventBus<Event> {
+    fn clone(&self) -> Self {
+        Self {
+            senders: self.senders.clone(),
+        }
+    }
+}
+
+impl<Event> Default for EventBus<Event> {
+    fn default() -> Self {
+        Self {
+            senders: Vec::new(),
+        }
+    }
+}
+
+impl<Event> EventBus<Event> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, sender: impl EventSender<Event> + 'static) -> Self {
+        self.add(sender);
+        self
+    }
+
+    pub fn add(&mut self, sender: impl EventSender<Event> + 'static) {
+        self.senders.push(Arc::new(sender));
+    }
+
+    pub fn len(&self) -> usize {
+        self.senders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+impl<Event: Clone> EventBus<Event> {
+    /// Sends `event` to every sender, returns the number of senders which accepted it
+    pub fn publish(&self, event: Event) -> usize {
+        self.senders
+            .iter()
+            .filter(|sender| sender.send_event(event.clone()).is_ok())
+            .count()
+    }
+}
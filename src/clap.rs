@@ -0,0 +1,86 @@
+//! # `clap` integration
+//!
+//! For CLI apps the World can be the arguments themselves: a [`clap::Parser`] struct, whose
+//! fields the dependencies read like any other World. [`DIContainer::from_args`] parses the
+//! arguments of the process into the World, [`DIContainer::try_from_args_from`] parses any
+//! iterator of arguments, e.g. in tests.
+//!
+//! # Errors
+//!
+//! [`DIContainer::from_args`] reports parse failures like [`clap::Parser::parse`]: the error is
+//! printed with the usage and the process exits, `--help` and `--version` print their output and
+//! exit successfully. [`DIContainer::try_from_args_from`] returns the [`clap::Error`] instead,
+//! call [`clap::Error::exit`] to report it the same way.
+//!
+//! # Combining with other config layers
+//!
+//! The arguments are one layer among others, usually the most specific one:
+//!
+//! - environment variables and defaults can be layered below the arguments by clap itself, with
+//!   `#[arg(env = "APP_PORT", default_value_t = 8080)]` (the `env` feature of clap)
+//! - for layers clap doesn't know about, e.g. a configuration file, parse the arguments first
+//!   and build a World holding both, the arguments optionally pointing at the file:
+//!
+//! ```ignore
+//! let args = Args::parse();
+//! let config = config::Config::builder()
+//!     .add_source(config::File::with_name(&args.config))
+//!     .build()?;
+//!
+//! let container = DIContainer::new(World { args, settings: config.try_deserialize()? });
+//! ```
+//!
+//! Dependencies then choose the layer they read from, or merge them, an argument overriding the
+//! file when it was given: `world.args.port.unwrap_or(world.settings.port)`.
+//!
+//! # Usage
+//! ```
+//! use clap::Parser;
+//! use je_di::{DIContainer, FromWorld};
+//!
+//! #[derive(Parser)]
+//! struct Args {
+//!     #[arg(long, default_value_t = 8080)]
+//!     port: u16,
+//!     #[arg(long)]
+//!     verbose: bool,
+//! }
+//!
+//! struct Server {
+//!     address: String,
+//! }
+//!
+//! impl FromWorld for Server {
+//!     type World<'a> = Args;
+//!     type Error = String;
+//!
+//!     fn from_world(args: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { address: format!("0.0.0.0:{}", args.port) })
+//!     }
+//! }
+//!
+//! let container = DIContainer::<Args>::try_from_args_from(["app", "--port", "3000"]).unwrap();
+//! assert_eq!(container.extract::<Server>().unwrap().address, "0.0.0.0:3000");
+//!
+//! let error = DIContainer::<Args>::try_from_args_from(["app", "--port", "http"]).err().unwrap();
+//! assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+//! ```
+
+use crate::DIContainer;
+use std::ffi::OsString;
+
+impl<World: clap::Parser> DIContainer<World> {
+    /// Parses the arguments of the process into the World, exits on failure
+    pub fn from_args() -> Self {
+        Self::new(World::parse())
+    }
+
+    /// Parses `args` into the World, the first argument being the binary name
+    pub fn try_from_args_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        World::try_parse_from(args).map(Self::new)
+    }
+}
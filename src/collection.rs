@@ -0,0 +1,212 @@
+//! # Fixed-size collections
+//!
+//! For small, known-size groups of dependencies, e.g. one plugin per entry of the configuration,
+//! [`Collected<C, World>`] resolves every element of the collection `C` from the element Worlds
+//! the World exposes through [`AsRef<[Item]>`](AsRef), in order, without allocating:
+//!
+//! - `SmallVec<[T; N]>` behind the `smallvec` feature, spilling to the heap past `N` elements
+//! - `ArrayVec<T, N>` behind the `arrayvec` feature, failing with [`CollectError::Capacity`]
+//!   before resolving anything when there are more than `N` element Worlds
+//!
+//! `N` is the const generic capacity of the collection, inferred from the requested type. The
+//! `smallvec` feature enables the `const_generics` feature of `smallvec`, so any `N` is
+//! accepted.
+//!
+//! # Errors
+//!
+//! By default the resolution fails fast on the first failed element. Setting the `FAIL_FAST`
+//! flag to `false` resolves every element and reports all the failures, each with the index of
+//! its World, in [`CollectError::Resolve`].
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "arrayvec")] {
+//! use arrayvec::ArrayVec;
+//! use je_di::{DIContainer, FromWorld, collection::{CollectError, Collected}};
+//!
+//! struct PluginConfig {
+//!     name: &'static str,
+//! }
+//!
+//! struct World {
+//!     plugins: Vec<PluginConfig>,
+//! }
+//!
+//! impl AsRef<[PluginConfig]> for World {
+//!     fn as_ref(&self) -> &[PluginConfig] {
+//!         &self.plugins
+//!     }
+//! }
+//!
+//! struct Plugin(&'static str);
+//!
+//! impl FromWorld for Plugin {
+//!     type World<'a> = PluginConfig;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         match world.name {
+//!             "" => Err("unnamed plugin".to_string()),
+//!             name => Ok(Self(name)),
+//!         }
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     plugins: vec![PluginConfig { name: "auth" }, PluginConfig { name: "metrics" }],
+//! });
+//! let plugins: Collected<ArrayVec<Plugin, 4>, World> = container.extract().unwrap();
+//! assert_eq!(plugins.len(), 2);
+//!
+//! let too_small = container.extract::<Collected<ArrayVec<Plugin, 1>, World>>();
+//! assert!(matches!(too_small, Err(CollectError::Capacity { capacity: 1, len: 2 })));
+//!
+//! let container = DIContainer::new(World {
+//!     plugins: vec![PluginConfig { name: "" }, PluginConfig { name: "" }],
+//! });
+//! let Err(CollectError::Resolve(errors)) =
+//!     container.extract::<Collected<ArrayVec<Plugin, 4>, World, false>>()
+//! else {
+//!     unreachable!()
+//! };
+//! assert_eq!(errors.iter().map(|(index, _)| *index).collect::<Vec<_>>(), [0, 1]);
+//! # }
+//! ```
+
+#[cfg(any(feature = "smallvec", feature = "arrayvec"))]
+use crate::{FromWorld, resolve::resolve};
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+/// # Collection `C` resolved from the element Worlds of `World`
+///
+/// Fails fast on the first failed element unless `FAIL_FAST` is `false`
+pub struct Collected<C, World, const FAIL_FAST: bool = true> {
+    pub value: C,
+    _marker: PhantomData<fn() -> World>,
+}
+
+impl<C, World, const FAIL_FAST: bool> Collected<C, World, FAIL_FAST> {
+    pub fn into_inner(self) -> C {
+        self.value
+    }
+}
+
+impl<C, World, const FAIL_FAST: bool> Deref for Collected<C, World, FAIL_FAST> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// # Error of a [`Collected`] resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectError<E> {
+    /// The failed elements with the index of their World, only the first when failing fast
+    Resolve(Vec<(usize, E)>),
+    /// More element Worlds than the capacity of the collection
+    Capacity { capacity: usize, len: usize },
+}
+
+impl<E: fmt::Display> fmt::Display for CollectError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectError::Resolve(errors) => {
+                write!(f, "{} element(s) failed to resolve", errors.len())?;
+                for (index, error) in errors {
+                    write!(f, "\n  [{index}]: {error}")?;
+                }
+                Ok(())
+            }
+            CollectError::Capacity { capacity, len } => {
+                write!(f, "{len} elements exceed the capacity of {capacity}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for CollectError<E> {}
+
+/// Resolves every element, pushing the resolved ones
+#[cfg(any(feature = "smallvec", feature = "arrayvec"))]
+fn collect<T, Item>(
+    items: &[Item],
+    fail_fast: bool,
+    mut push: impl FnMut(T),
+) -> Result<(), CollectError<T::Error>>
+where
+    T: for<'a> FromWorld<World<'a> = Item>,
+{
+    let mut errors = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        match resolve::<T>(item) {
+            Ok(element) => push(element),
+            Err(error) => {
+                errors.push((index, error));
+                if fail_fast {
+                    break;
+                }
+            }
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(CollectError::Resolve(errors)),
+    }
+}
+
+#[cfg(feature = "smallvec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smallvec")))]
+impl<T, Item, W, const N: usize, const FAIL_FAST: bool> FromWorld
+    for Collected<smallvec::SmallVec<[T; N]>, W, FAIL_FAST>
+where
+    T: for<'a> FromWorld<World<'a> = Item>,
+    W: AsRef<[Item]>,
+{
+    type World<'a> = W;
+    type Error = CollectError<T::Error>;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let mut value = smallvec::SmallVec::new();
+        collect::<T, Item>(world.as_ref(), FAIL_FAST, |element| value.push(element))?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrayvec")))]
+impl<T, Item, W, const N: usize, const FAIL_FAST: bool> FromWorld
+    for Collected<arrayvec::ArrayVec<T, N>, W, FAIL_FAST>
+where
+    T: for<'a> FromWorld<World<'a> = Item>,
+    W: AsRef<[Item]>,
+{
+    type World<'a> = W;
+    type Error = CollectError<T::Error>;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let items = world.as_ref();
+        if items.len() > N {
+            return Err(CollectError::Capacity {
+                capacity: N,
+                len: items.len(),
+            });
+        }
+
+        let mut value = arrayvec::ArrayVec::new();
+        collect::<T, Item>(items, FAIL_FAST, |element| value.push(element))?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
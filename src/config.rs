@@ -0,0 +1,53 @@
+//! # `config` crate integration
+//!
+//! Populates the World from a built [`config::Config`], aggregating files, environment
+//! variables and defaults.
+//!
+//! See `examples/config.rs` for a TOML file layered with environment overrides.
+//!
+//! # Usage
+//! ```ignore
+//! let config = config::Config::builder()
+//!     .add_source(config::File::with_name("config"))
+//!     .add_source(config::Environment::with_prefix("APP").separator("__"))
+//!     .build()?;
+//!
+//! let container: DIContainer<World> = DIContainer::from_config_crate(&config)?;
+//! ```
+
+use crate::DIContainer;
+use serde::de::DeserializeOwned;
+
+/// # Error deserializing the World from a [`config::Config`]
+#[derive(Debug)]
+pub struct WorldConfigError {
+    /// type name of the World
+    pub world: &'static str,
+    pub source: config::ConfigError,
+}
+
+impl std::fmt::Display for WorldConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to deserialize {} from config: {}", self.world, self.source)
+    }
+}
+
+impl std::error::Error for WorldConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl<World: DeserializeOwned> DIContainer<World> {
+    /// Deserializes the World from `config`
+    pub fn from_config_crate(config: &config::Config) -> Result<Self, WorldConfigError> {
+        config
+            .clone()
+            .try_deserialize()
+            .map(Self::new)
+            .map_err(|source| WorldConfigError {
+                world: std::any::type_name::<World>(),
+                source,
+            })
+    }
+}
@@ -0,0 +1,259 @@
+//! # Pooled connections
+//!
+//! A connection checked out of a pool of the World when resolved, and returned to the pool when
+//! dropped: one connection per request, held for as long as the handler holds the dependency.
+//! The World exposes the pool through [`AsRef`], the pool crates are integrated behind features:
+//!
+//! - `bb8`: [`PooledConnection<'a, M, World>`], borrowing a [`bb8::Pool<M>`]
+//! - `deadpool`: [`DeadpoolConnection<M, World>`], from a [`deadpool::managed::Pool<M>`]
+//!
+//! For a pool of ready made items without a connection manager, see [`Pool`](crate::pool::Pool).
+//!
+//! # Lifetime of the checkout
+//!
+//! A bb8 connection borrows its pool, [`PooledConnection`] is therefore a
+//! [`BorrowFromAsyncWorld<'a>`] dependency, resolved with
+//! [`DIContainer::extract_borrowing_async`](crate::DIContainer::extract_borrowing_async): `'a` is
+//! the borrow of the container, the connection can't outlive the container it was checked out of,
+//! and the container can't be moved nor dropped while a connection is checked out.
+//!
+//! A deadpool object holds a handle to its pool instead, [`DeadpoolConnection`] is a plain
+//! [`FromAsyncWorld`] dependency, free of the container borrow, that can be part of an owned
+//! dependency tree or sent to a spawned task.
+//!
+//! # Async acquire
+//!
+//! Both acquire asynchronously: when the pool is exhausted, the resolution waits for a connection
+//! to be returned, up to the connection timeout of the pool, and fails with the error of the pool,
+//! converted into the Error parameter through [`From`].
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "bb8")] {
+//! use je_di::{DIContainer, connection::PooledConnection};
+//!
+//! struct Connection(u32);
+//!
+//! struct Manager;
+//!
+//! impl bb8::ManageConnection for Manager {
+//!     type Connection = Connection;
+//!     type Error = String;
+//!
+//!     async fn connect(&self) -> Result<Connection, String> {
+//!         Ok(Connection(1))
+//!     }
+//!
+//!     async fn is_valid(&self, _connection: &mut Connection) -> Result<(), String> {
+//!         Ok(())
+//!     }
+//!
+//!     fn has_broken(&self, _connection: &mut Connection) -> bool {
+//!         false
+//!     }
+//! }
+//!
+//! struct World {
+//!     pool: bb8::Pool<Manager>,
+//! }
+//!
+//! impl AsRef<bb8::Pool<Manager>> for World {
+//!     fn as_ref(&self) -> &bb8::Pool<Manager> {
+//!         &self.pool
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let pool = bb8::Pool::builder().max_size(1).build(Manager).await.unwrap();
+//! let container = DIContainer::new(World { pool: pool.clone() });
+//!
+//! {
+//!     let connection: PooledConnection<Manager, World> =
+//!         container.extract_borrowing_async().await.unwrap();
+//!     assert_eq!(connection.0, 1);
+//!     assert_eq!(pool.state().idle_connections, 0);
+//! }
+//!
+//! // returned on drop
+//! assert_eq!(pool.state().idle_connections, 1);
+//! # });
+//! # }
+//! ```
+//!
+//! With deadpool, the connection is a regular async dependency:
+//!
+//! ```
+//! # #[cfg(feature = "deadpool")] {
+//! use deadpool::managed::{Manager, Metrics, Pool, RecycleResult};
+//! use je_di::{DIContainer, connection::DeadpoolConnection};
+//!
+//! struct Connection(u32);
+//!
+//! struct ConnectionManager;
+//!
+//! impl Manager for ConnectionManager {
+//!     type Type = Connection;
+//!     type Error = String;
+//!
+//!     async fn create(&self) -> Result<Connection, String> {
+//!         Ok(Connection(1))
+//!     }
+//!
+//!     async fn recycle(&self, _connection: &mut Connection, _: &Metrics) -> RecycleResult<String> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! struct World {
+//!     pool: Pool<ConnectionManager>,
+//! }
+//!
+//! impl AsRef<Pool<ConnectionManager>> for World {
+//!     fn as_ref(&self) -> &Pool<ConnectionManager> {
+//!         &self.pool
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let pool = Pool::builder(ConnectionManager).max_size(1).build().unwrap();
+//! let container = DIContainer::new(World { pool: pool.clone() });
+//!
+//! let connection: DeadpoolConnection<ConnectionManager, World> =
+//!     container.extract_async().await.unwrap();
+//! assert_eq!(connection.0, 1);
+//! assert_eq!(pool.status().available, 0);
+//!
+//! drop(connection);
+//! assert_eq!(pool.status().available, 1);
+//! # });
+//! # }
+//! ```
+//!
+//! [`FromAsyncWorld`]: crate::FromAsyncWorld
+//! [`BorrowFromAsyncWorld<'a>`]: crate::BorrowFromAsyncWorld
+
+#[cfg(feature = "bb8")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bb8")))]
+pub use self::bb8_connection::PooledConnection;
+
+#[cfg(feature = "deadpool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "deadpool")))]
+pub use self::deadpool_connection::DeadpoolConnection;
+
+#[cfg(feature = "bb8")]
+mod bb8_connection {
+    use crate::{BorrowFromAsyncWorld, async_trait};
+    use bb8::{ManageConnection, Pool, RunError};
+    use std::{
+        marker::PhantomData,
+        ops::{Deref, DerefMut},
+    };
+
+    /// # Connection checked out of a [`bb8::Pool`] of the World, for the borrow of the container
+    ///
+    /// See the [module documentation](crate::connection)
+    pub struct PooledConnection<'a, M, World, Error = RunError<<M as ManageConnection>::Error>>
+    where
+        M: ManageConnection,
+    {
+        connection: bb8::PooledConnection<'a, M>,
+        _marker: PhantomData<fn() -> (World, Error)>,
+    }
+
+    impl<'a, M: ManageConnection, World, Error> PooledConnection<'a, M, World, Error> {
+        pub fn into_inner(self) -> bb8::PooledConnection<'a, M> {
+            self.connection
+        }
+    }
+
+    impl<M: ManageConnection, World, Error> Deref for PooledConnection<'_, M, World, Error> {
+        type Target = M::Connection;
+
+        fn deref(&self) -> &Self::Target {
+            &self.connection
+        }
+    }
+
+    impl<M: ManageConnection, World, Error> DerefMut for PooledConnection<'_, M, World, Error> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.connection
+        }
+    }
+
+    #[async_trait]
+    impl<'a, M, World, Error> BorrowFromAsyncWorld<'a> for PooledConnection<'a, M, World, Error>
+    where
+        M: ManageConnection,
+        World: AsRef<Pool<M>> + Sync + 'a,
+        Error: From<RunError<M::Error>> + Send + Sync,
+    {
+        type World = &'a World;
+        type Error = Error;
+
+        async fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+            Ok(Self {
+                connection: world.as_ref().get().await?,
+                _marker: PhantomData,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "deadpool")]
+mod deadpool_connection {
+    use crate::{FromAsyncWorld, async_trait};
+    use deadpool::managed::{Manager, Object, Pool, PoolError};
+    use std::{
+        marker::PhantomData,
+        ops::{Deref, DerefMut},
+    };
+
+    /// # Connection checked out of a [`deadpool::managed::Pool`] of the World
+    ///
+    /// See the [module documentation](crate::connection)
+    pub struct DeadpoolConnection<M, World, Error = PoolError<<M as Manager>::Error>>
+    where
+        M: Manager,
+    {
+        connection: Object<M>,
+        _marker: PhantomData<fn() -> (World, Error)>,
+    }
+
+    impl<M: Manager, World, Error> DeadpoolConnection<M, World, Error> {
+        pub fn into_inner(self) -> Object<M> {
+            self.connection
+        }
+    }
+
+    impl<M: Manager, World, Error> Deref for DeadpoolConnection<M, World, Error> {
+        type Target = M::Type;
+
+        fn deref(&self) -> &Self::Target {
+            &self.connection
+        }
+    }
+
+    impl<M: Manager, World, Error> DerefMut for DeadpoolConnection<M, World, Error> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.connection
+        }
+    }
+
+    #[async_trait]
+    impl<M, World, Error> FromAsyncWorld for DeadpoolConnection<M, World, Error>
+    where
+        M: Manager + 'static,
+        World: AsRef<Pool<M>> + Send + Sync + 'static,
+        Error: From<PoolError<M::Error>> + Send + Sync + 'static,
+    {
+        type World<'a> = World;
+        type Error = Error;
+
+        async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+            Ok(Self {
+                connection: world.as_ref().get().await?,
+                _marker: PhantomData,
+            })
+        }
+    }
+}
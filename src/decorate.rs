@@ -0,0 +1,134 @@
+//! # Decorators
+//!
+//! [`DIContainer::decorate`] registers a function applied to every extracted `T`, e.g. to wrap a
+//! service in a logging proxy, and turns the container into a [`DecoratedContainer`].
+//!
+//! # Composition
+//!
+//! - decorators are registered per type, the decorators of the same type are applied in
+//!   registration order
+//! - decorators apply to the extracted type, not to the graph below it: a dependency resolved as
+//!   part of another type is not decorated, extract it through the container or decorate it in
+//!   the implementation of its dependent
+//! - decorators run on every extraction, values shared by [`Cached`](crate::cache::Cached) or a
+//!   [`Pool`](crate::pool::Pool) are decorated per extraction, the decorated value itself is never
+//!   stored. Decorate the shared value before it is cached instead, in its `FromWorld`
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//!
+//! struct World;
+//!
+//! struct Service {
+//!     calls: Vec<&'static str>,
+//! }
+//!
+//! impl FromWorld for Service {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { calls: vec!["built"] })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World)
+//!     .decorate(|mut service: Service| {
+//!         service.calls.push("logged");
+//!         service
+//!     })
+//!     .decorate(|mut service: Service| {
+//!         service.calls.push("metered");
+//!         service
+//!     });
+//!
+//! let service: Service = container.extract().unwrap();
+//! assert_eq!(service.calls, ["built", "logged", "metered"]);
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+type Decorator<T> = Box<dyn Fn(T) -> T + Send + Sync>;
+
+/// # Container applying registered decorators to extracted values
+pub struct DecoratedContainer<World> {
+    container: DIContainer<World>,
+    decorators: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl<World> DecoratedContainer<World> {
+    /// The underlying container, extractions through it are not decorated
+    pub fn container(&self) -> &DIContainer<World> {
+        &self.container
+    }
+
+    /// Registers a decorator of `T`, applied after the ones already registered
+    pub fn decorate<T: 'static>(
+        mut self,
+        decorator: impl Fn(T) -> T + Send + Sync + 'static,
+    ) -> Self {
+        let decorator: Decorator<T> = match self.decorators.remove(&TypeId::of::<T>()) {
+            Some(previous) => {
+                let previous = previous
+                    .downcast::<Decorator<T>>()
+                    .unwrap_or_else(|_| unreachable!("decorators are keyed by their TypeId"));
+                Box::new(move |value| decorator(previous(value)))
+            }
+            None => Box::new(decorator),
+        };
+
+        self.decorators
+            .insert(TypeId::of::<T>(), Box::new(decorator));
+        self
+    }
+
+    pub fn extract<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + 'static,
+    {
+        resolve::<T>(&self.container.world).map(|value| self.apply(value))
+    }
+
+    fn apply<T: 'static>(&self, value: T) -> T {
+        match self
+            .decorators
+            .get(&TypeId::of::<T>())
+            .and_then(|decorator| decorator.downcast_ref::<Decorator<T>>())
+        {
+            Some(decorator) => decorator(value),
+            None => value,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World> DecoratedContainer<World> {
+    pub async fn extract_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World>,
+    {
+        crate::resolve::resolve_async::<T>(&self.container.world)
+            .await
+            .map(|value| self.apply(value))
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Registers a decorator of `T`, see [`DecoratedContainer::decorate`]
+    pub fn decorate<T: 'static>(
+        self,
+        decorator: impl Fn(T) -> T + Send + Sync + 'static,
+    ) -> DecoratedContainer<World> {
+        DecoratedContainer {
+            container: self,
+            decorators: HashMap::new(),
+        }
+        .decorate(decorator)
+    }
+}
@@ -0,0 +1,547 @@
+//! # Declared dependencies
+//!
+//! [`dependency!`](macro@crate::dependency) implements [`FromWorld`](crate::FromWorld), or
+//! [`FromAsyncWorld`](crate::FromAsyncWorld) with the `async` prefix, for a type built from a
+//! single dependency whose error converts into the error of the type:
+//!
+//! ```ignore
+//! dependency! { Repository, dep = Database, world = World, error = AppError }
+//! dependency! { async Repository, dep = Database, world = World, error = AppError }
+//! ```
+//!
+//! The type is built with `From<Dep>`, or with `build = f` where `f` is a
+//! `Fn(&World, Dep) -> Result<Type, Error>`.
+//!
+//! # Shared World and error
+//!
+//! [`impl_from_world!`](crate::impl_from_world) implements `FromWorld` for several types sharing
+//! the same World and error, each with a closure-like body taking the World:
+//!
+//! ```ignore
+//! impl_from_world! {
+//!     world = World, error = AppError;
+//!     Printer { |w| Ok(Printer { username: w.username.clone() }) },
+//!     Retries { |w| Ok(Retries(w.retries)) },
+//! }
+//! ```
+//!
+//! `w` is the name bound to the `&World` in the body, any identifier can be used. The body is an
+//! expression evaluating to `Result<Type, Error>`, it isn't a closure: it can use `?` and, in the
+//! `async` variant implementing `FromAsyncWorld`, `.await`:
+//!
+//! ```ignore
+//! impl_from_world! {
+//!     async world = World, error = AppError;
+//!     Session { |w| Ok(Session(w.store.load().await?)) },
+//! }
+//! ```
+//!
+//! # Error conversion
+//!
+//! The error of the dependency is converted through [`ErrorFrom`], implemented for every `From`
+//! conversion. When the conversion is missing the compile error names it, instead of an
+//! unsatisfied bound of a blanket implementation:
+//!
+//! ```text
+//! error[E0277]: `AppError` can't be built from the error `DatabaseError` of a dependency
+//!   |
+//!   = note: add `impl From<DatabaseError> for AppError`
+//! ```
+//!
+//! # Deriving the error
+//!
+//! With the `derive` feature, `#[derive(DependencyError)]` writes the conversions of an error
+//! enum wrapping the errors of the dependencies, `#[from]`, and its `Error::source`,
+//! `#[source]`:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use je_di::DependencyError;
+//! use std::{error::Error, fmt};
+//!
+//! #[derive(Debug, DependencyError)]
+//! enum AppError {
+//!     #[from]
+//!     #[source]
+//!     Io(std::io::Error),
+//!     #[from]
+//!     Parse { error: std::num::ParseIntError },
+//!     Missing,
+//! }
+//!
+//! impl fmt::Display for AppError {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         f.write_str("application error")
+//!     }
+//! }
+//!
+//! let io = AppError::from(std::io::Error::other("disk full"));
+//! assert_eq!(io.source().unwrap().to_string(), "disk full");
+//!
+//! let parse = AppError::from("x".parse::<u8>().unwrap_err());
+//! assert!(matches!(parse, AppError::Parse { .. }));
+//! assert!(parse.source().is_none());
+//! assert!(AppError::Missing.source().is_none());
+//! # }
+//! ```
+//!
+//! The conversions are those [`FromAsyncDependency`](crate::FromAsyncDependency) requires from
+//! the errors of the dependencies, and `#[response]`, with the `axum` feature, responds with the
+//! wrapped value:
+//!
+//! ```
+//! # #[cfg(all(feature = "derive", feature = "axum"))] {
+//! use axum::{http::StatusCode, response::IntoResponse};
+//! use je_di::{DIContainer, DependencyError, FromAsyncDependency, FromAsyncWorld, async_trait};
+//! use std::num::ParseIntError;
+//!
+//! #[derive(Debug, DependencyError)]
+//! enum AppError {
+//!     #[from]
+//!     Parse(ParseIntError),
+//!     #[from]
+//!     #[response]
+//!     Rejected(StatusCode),
+//! }
+//!
+//! // the generated conversions
+//! fn converts<E: From<ParseIntError> + From<StatusCode>>() {}
+//! converts::<AppError>();
+//!
+//! struct World {
+//!     port: &'static str,
+//! }
+//!
+//! struct Port(u16);
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Port {
+//!     type World<'a> = World;
+//!     type Error = ParseIntError;
+//!
+//!     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.port.parse()?))
+//!     }
+//! }
+//!
+//! struct Server(u16);
+//!
+//! #[async_trait]
+//! impl FromAsyncDependency for Server {
+//!     type World<'a> = World;
+//!     type Error = AppError;
+//!     type Dependency = Port;
+//!
+//!     async fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         port: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         match port.0 {
+//!             0 => Err(StatusCode::SERVICE_UNAVAILABLE.into()),
+//!             port => Ok(Self(port)),
+//!         }
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let container = DIContainer::new(World { port: "8080" });
+//! assert_eq!(container.extract_async::<Server>().await.unwrap().0, 8080);
+//!
+//! // the error of the dependency is converted into its variant
+//! let container = DIContainer::new(World { port: "http" });
+//! let error = container.extract_async::<Server>().await.err();
+//! assert!(matches!(error, Some(AppError::Parse(_))));
+//!
+//! let container = DIContainer::new(World { port: "0" });
+//! let error = container.extract_async::<Server>().await.err().unwrap();
+//! assert_eq!(error.into_response().status(), StatusCode::SERVICE_UNAVAILABLE);
+//! # });
+//! # }
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, dependency};
+//!
+//! struct World {
+//!     url: &'static str,
+//! }
+//!
+//! struct Database(&'static str);
+//!
+//! #[derive(Debug)]
+//! struct DatabaseError;
+//!
+//! impl FromWorld for Database {
+//!     type World<'a> = World;
+//!     type Error = DatabaseError;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.url))
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! enum AppError {
+//!     Database(DatabaseError),
+//! }
+//!
+//! impl From<DatabaseError> for AppError {
+//!     fn from(error: DatabaseError) -> Self {
+//!         Self::Database(error)
+//!     }
+//! }
+//!
+//! struct Repository(Database);
+//!
+//! impl From<Database> for Repository {
+//!     fn from(database: Database) -> Self {
+//!         Self(database)
+//!     }
+//! }
+//!
+//! dependency! { Repository, dep = Database, world = World, error = AppError }
+//!
+//! struct Url(String);
+//!
+//! dependency! {
+//!     Url,
+//!     dep = Database,
+//!     world = World,
+//!     error = AppError,
+//!     build = |_world: &World, database: Database| Ok(Url(database.0.to_string())),
+//! }
+//!
+//! let container = DIContainer::new(World { url: "postgres://localhost" });
+//! assert_eq!(container.extract::<Repository>().unwrap().0.0, "postgres://localhost");
+//! assert_eq!(container.extract::<Url>().unwrap().0, "postgres://localhost");
+//! ```
+//!
+//! Several types sharing a World and an error:
+//!
+//! ```
+//! use je_di::{DIContainer, impl_from_world};
+//!
+//! struct World {
+//!     username: String,
+//!     retries: u32,
+//! }
+//!
+//! struct Printer {
+//!     username: String,
+//! }
+//!
+//! struct Retries(u32);
+//!
+//! impl_from_world! {
+//!     world = World, error = String;
+//!     Printer { |w| Ok(Printer { username: w.username.clone() }) },
+//!     Retries { |world| match world.retries {
+//!         0 => Err("retries must be positive".to_string()),
+//!         retries => Ok(Retries(retries)),
+//!     } },
+//! }
+//!
+//! let container = DIContainer::new(World { username: "alice".to_string(), retries: 3 });
+//! let (printer, retries): (Printer, Retries) = container.extract().unwrap();
+//! assert_eq!(printer.username, "alice");
+//! assert_eq!(retries.0, 3);
+//! ```
+//!
+//! Without the conversion it doesn't compile:
+//!
+//! ```compile_fail,E0277
+//! # use je_di::{FromWorld, dependency};
+//! # struct World;
+//! # struct Database;
+//! # struct DatabaseError;
+//! # impl FromWorld for Database {
+//! #     type World<'a> = World;
+//! #     type Error = DatabaseError;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//! #         Ok(Self)
+//! #     }
+//! # }
+//! struct AppError;
+//!
+//! struct Repository(Database);
+//!
+//! impl From<Database> for Repository {
+//!     fn from(database: Database) -> Self {
+//!         Self(database)
+//!     }
+//! }
+//!
+//! dependency! { Repository, dep = Database, world = World, error = AppError }
+//! ```
+//!
+//! # Conditional dependencies
+//!
+//! A dependency resolvable only from Worlds satisfying a bound, e.g. `World: HasConfig`, is
+//! generic over its World: the World is a type parameter of the implementation, bounded in its
+//! `where` clause, and `type World<'a>` is set to it. The parameter must appear in the type of
+//! the dependency, usually as a `PhantomData<fn() -> W>`.
+//! [`conditional_from_world!`](crate::conditional_from_world) writes the implementation,
+//! taking the World from the type of the `from_world` argument:
+//!
+//! ```ignore
+//! conditional_from_world! {
+//!     impl<W> FromWorld for Greeting<W>
+//!     where
+//!         W: HasLocale,
+//!     {
+//!         type Error = String;
+//!
+//!         fn from_world(world: &W) -> Result<Self, Self::Error> {
+//!             Ok(Greeting::new(world.locale()))
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The bound can't be written on the World GAT itself:
+//!
+//! - `where Self::World<'a>: HasLocale` doesn't compile, `'a` isn't in scope of the impl, and
+//!   `where for<'a> Self::World<'a>: HasLocale` makes the impl depend on its own associated type,
+//!   an overflow error (E0275) evaluating it
+//! - with a concrete World, `type World<'a> = AppWorld`, a bound `AppWorld: HasLocale` is a
+//!   trivial bound: a compile error when it doesn't hold, instead of a dependency that isn't
+//!   resolvable
+//! - `for<'a> FromWorld<World<'a> = W>` holds because `W` doesn't depend on `'a`, Worlds
+//!   borrowing for `'a` bound every lifetime instead, `where for<'a> Scoped<'a, W, S>: HasLocale`
+//!
+//! Named bounds shared by several dependencies are [capabilities](mod@crate::capability).
+
+/// # Error built from the error of a dependency
+///
+/// Implemented for every `From` conversion, only used to name the missing conversion in compile
+/// errors of [`dependency!`](macro@crate::dependency)
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be built from the error `{Source}` of a dependency",
+    label = "missing error conversion",
+    note = "add `impl From<{Source}> for {Self}`"
+)]
+pub trait ErrorFrom<Source> {
+    fn error_from(source: Source) -> Self;
+}
+
+impl<Source, E: From<Source>> ErrorFrom<Source> for E {
+    fn error_from(source: Source) -> Self {
+        E::from(source)
+    }
+}
+
+/// # Implements the resolution of a type built from a single dependency
+///
+/// See the [module documentation](mod@crate::dependency)
+#[macro_export]
+macro_rules! dependency {
+    (@build $world:ident, $dependency:ident, $ty:ty, $dep:ty) => {
+        ::core::result::Result::Ok(<$ty as ::core::convert::From<$dep>>::from($dependency))
+    };
+
+    (@build $world:ident, $dependency:ident, $ty:ty, $dep:ty, $build:expr) => {
+        ($build)($world, $dependency)
+    };
+
+    (
+        async $ty:ty,
+        dep = $dep:ty,
+        world = $world:ty,
+        error = $error:ty
+        $(, build = $build:expr)?
+        $(,)?
+    ) => {
+        #[$crate::async_trait]
+        impl $crate::FromAsyncWorld for $ty {
+            type World<'a> = $world;
+            type Error = $error;
+
+            async fn from_world<'a>(
+                world: &'a Self::World<'a>,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                let dependency = $crate::__private::resolve_async::<$dep>(world)
+                    .await
+                    .map_err(
+                        <$error as $crate::dependency::ErrorFrom<
+                            <$dep as $crate::FromAsyncWorld>::Error,
+                        >>::error_from,
+                    )?;
+
+                $crate::dependency!(@build world, dependency, $ty, $dep $(, $build)?)
+            }
+        }
+    };
+
+    (
+        $ty:ty,
+        dep = $dep:ty,
+        world = $world:ty,
+        error = $error:ty
+        $(, build = $build:expr)?
+        $(,)?
+    ) => {
+        impl $crate::FromWorld for $ty {
+            type World<'a> = $world;
+            type Error = $error;
+
+            fn from_world(world: &Self::World<'_>) -> ::core::result::Result<Self, Self::Error> {
+                let dependency = $crate::__private::resolve::<$dep>(world).map_err(
+                    <$error as $crate::dependency::ErrorFrom<
+                        <$dep as $crate::FromWorld>::Error,
+                    >>::error_from,
+                )?;
+
+                $crate::dependency!(@build world, dependency, $ty, $dep $(, $build)?)
+            }
+        }
+    };
+}
+
+/// # Implements `FromWorld` for several types sharing a World and an error
+///
+/// See the [module documentation](mod@crate::dependency)
+#[macro_export]
+macro_rules! impl_from_world {
+    (
+        async world = $world:ty, error = $error:ty;
+        $($ty:ty { |$w:ident| $body:expr }),+ $(,)?
+    ) => {
+        $(
+            #[$crate::async_trait]
+            impl $crate::FromAsyncWorld for $ty {
+                type World<'a> = $world;
+                type Error = $error;
+
+                async fn from_world<'a>(
+                    $w: &'a Self::World<'a>,
+                ) -> ::core::result::Result<Self, Self::Error> {
+                    $body
+                }
+            }
+        )+
+    };
+
+    (
+        world = $world:ty, error = $error:ty;
+        $($ty:ty { |$w:ident| $body:expr }),+ $(,)?
+    ) => {
+        $(
+            impl $crate::FromWorld for $ty {
+                type World<'a> = $world;
+                type Error = $error;
+
+                fn from_world($w: &Self::World<'_>) -> ::core::result::Result<Self, Self::Error> {
+                    $body
+                }
+            }
+        )+
+    };
+}
+
+/// # Implements `FromWorld` for the Worlds satisfying a bound
+///
+/// See the [module documentation](mod@crate::dependency#conditional-dependencies)
+///
+/// # Usage
+/// ```
+/// use je_di::{DIContainer, conditional_from_world};
+/// use std::marker::PhantomData;
+///
+/// trait HasLocale {
+///     fn locale(&self) -> &str;
+/// }
+///
+/// struct WebWorld {
+///     locale: String,
+/// }
+///
+/// impl HasLocale for WebWorld {
+///     fn locale(&self) -> &str {
+///         &self.locale
+///     }
+/// }
+///
+/// struct Greeting<W> {
+///     text: String,
+///     _world: PhantomData<fn() -> W>,
+/// }
+///
+/// conditional_from_world! {
+///     impl<W> FromWorld for Greeting<W>
+///     where
+///         W: HasLocale,
+///     {
+///         type Error = String;
+///
+///         fn from_world(world: &W) -> Result<Self, Self::Error> {
+///             let text = match world.locale() {
+///                 "fr" => "bonjour",
+///                 "en" => "hello",
+///                 locale => return Err(format!("unsupported locale {locale}")),
+///             };
+///             Ok(Greeting { text: text.to_string(), _world: PhantomData })
+///         }
+///     }
+/// }
+///
+/// let container = DIContainer::new(WebWorld { locale: "fr".to_string() });
+/// let greeting: Greeting<_> = container.extract().unwrap();
+/// assert_eq!(greeting.text, "bonjour");
+/// ```
+///
+/// Worlds not implementing the bound can't resolve the dependency:
+///
+/// ```compile_fail,E0277
+/// # use je_di::{DIContainer, conditional_from_world};
+/// # use std::marker::PhantomData;
+/// # trait HasLocale {}
+/// # struct Greeting<W>(PhantomData<fn() -> W>);
+/// # conditional_from_world! {
+/// #     impl<W> FromWorld for Greeting<W>
+/// #     where
+/// #         W: HasLocale,
+/// #     {
+/// #         type Error = String;
+/// #
+/// #         fn from_world(_world: &W) -> Result<Self, Self::Error> {
+/// #             Ok(Greeting(PhantomData))
+/// #         }
+/// #     }
+/// # }
+/// struct WorkerWorld;
+///
+/// let container = DIContainer::new(WorkerWorld);
+/// let greeting: Greeting<_> = container.extract().unwrap();
+/// ```
+#[macro_export]
+macro_rules! conditional_from_world {
+    (
+        @bounds [$($generics:ident),+] [$ty:ty] [$($bounds:tt)*]
+        {
+            type Error = $error:ty;
+
+            fn from_world($world:ident: &$world_ty:ty) -> $ret:ty $body:block
+        }
+    ) => {
+        impl<$($generics),+> $crate::FromWorld for $ty
+        where
+            $($bounds)*
+        {
+            type World<'a> = $world_ty;
+            type Error = $error;
+
+            fn from_world($world: &Self::World<'_>) -> $ret $body
+        }
+    };
+
+    (@bounds [$($generics:ident),+] [$ty:ty] [$($bounds:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::conditional_from_world!(
+            @bounds [$($generics),+] [$ty] [$($bounds)* $next] $($rest)*
+        );
+    };
+
+    (impl<$($generics:ident),+ $(,)?> FromWorld for $ty:ty where $($rest:tt)+) => {
+        $crate::conditional_from_world!(@bounds [$($generics),+] [$ty] [] $($rest)+);
+    };
+}
@@ -0,0 +1,203 @@
+//! # Runtime dispatch
+//!
+//! A dependency chosen at runtime, e.g. the storage backend named by the configuration: every
+//! backend is a concrete [`FromWorld`](crate::FromWorld) type implementing a common trait, an
+//! enum names them. [`dispatch_from_world!`](crate::dispatch_from_world) registers which type
+//! each variant resolves, [`DIContainer::extract_dispatched`] matches the variant, resolves the
+//! corresponding type and returns it as a trait object.
+//!
+//! ```ignore
+//! dispatch_from_world! {
+//!     dyn Storage: StorageKind {
+//!         StorageKind::S3 => S3Storage,
+//!         StorageKind::Gcs => GcsStorage,
+//!         StorageKind::Local => LocalStorage,
+//!     }
+//! }
+//!
+//! let storage: Box<dyn Storage> = container.extract_dispatched::<dyn Storage>(kind)?;
+//! ```
+//!
+//! Only the type of the matched variant is resolved. The left hand sides are patterns: a type can
+//! serve several variants (`StorageKind::S3 | StorageKind::Minio => S3Storage`) and the match must
+//! be exhaustive, a new variant without a type is a compile error.
+//!
+//! # Trait object output
+//!
+//! The concrete types are boxed into the trait object, `Box<dyn Storage>`: the dispatch allocates
+//! once per resolution and the caller only sees the trait. Auto traits are part of the trait
+//! object type, register `dyn Storage + Send + Sync` to share the output across threads.
+//!
+//! # Error unification
+//!
+//! The concrete types share the World. Their errors are converted through [`From`] into the
+//! error of the first type, or with `dyn Storage: StorageKind, error = AppError { .. }` into
+//! `AppError`.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, dispatch_from_world};
+//!
+//! struct World {
+//!     bucket: &'static str,
+//! }
+//!
+//! trait Storage {
+//!     fn location(&self) -> String;
+//! }
+//!
+//! struct S3Storage(&'static str);
+//!
+//! impl FromWorld for S3Storage {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.bucket))
+//!     }
+//! }
+//!
+//! impl Storage for S3Storage {
+//!     fn location(&self) -> String {
+//!         format!("s3://{}", self.0)
+//!     }
+//! }
+//!
+//! struct LocalStorage;
+//!
+//! impl FromWorld for LocalStorage {
+//!     type World<'a> = World;
+//!     type Error = std::convert::Infallible;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl Storage for LocalStorage {
+//!     fn location(&self) -> String {
+//!         "file:///var/data".to_string()
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! struct AppError(String);
+//!
+//! impl From<String> for AppError {
+//!     fn from(error: String) -> Self {
+//!         Self(error)
+//!     }
+//! }
+//!
+//! impl From<std::convert::Infallible> for AppError {
+//!     fn from(error: std::convert::Infallible) -> Self {
+//!         match error {}
+//!     }
+//! }
+//!
+//! enum StorageKind {
+//!     S3,
+//!     Minio,
+//!     Local,
+//! }
+//!
+//! dispatch_from_world! {
+//!     dyn Storage: StorageKind, error = AppError {
+//!         StorageKind::S3 | StorageKind::Minio => S3Storage,
+//!         StorageKind::Local => LocalStorage,
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { bucket: "assets" });
+//!
+//! let storage = container.extract_dispatched::<dyn Storage>(StorageKind::S3).unwrap();
+//! assert_eq!(storage.location(), "s3://assets");
+//!
+//! let storage = container.extract_dispatched::<dyn Storage>(StorageKind::Local).unwrap();
+//! assert_eq!(storage.location(), "file:///var/data");
+//! ```
+
+use crate::DIContainer;
+use alloc::boxed::Box;
+
+/// # Resolves a runtime selected implementation of a trait object
+///
+/// Implemented by [`dispatch_from_world!`](crate::dispatch_from_world)
+pub trait Dispatch {
+    /// Selects the implementation
+    type Kind;
+    type World<'a>;
+    type Error;
+
+    fn dispatch(world: &Self::World<'_>, kind: Self::Kind) -> Result<Box<Self>, Self::Error>;
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts the implementation of the trait object `T` selected by `kind`
+    pub fn extract_dispatched<T>(&self, kind: T::Kind) -> Result<Box<T>, T::Error>
+    where
+        T: ?Sized + for<'a> Dispatch<World<'a> = World>,
+    {
+        T::dispatch(&self.world, kind)
+    }
+}
+
+/// # Registers the type resolved for each variant of a kind
+///
+/// ```ignore
+/// dispatch_from_world! {
+///     dyn Trait: Kind [, error = Error] {
+///         Kind::A => ImplA,
+///         Kind::B | Kind::C => ImplB,
+///     }
+/// }
+/// ```
+///
+/// See the [module documentation](crate::dispatch)
+#[macro_export]
+macro_rules! dispatch_from_world {
+    (@error $first:ty) => {
+        <$first as $crate::FromWorld>::Error
+    };
+
+    (@error $first:ty, $error:ty) => {
+        $error
+    };
+
+    (
+        $target:ty: $kind:ty $(, error = $error:ty)? {
+            $first_pattern:pat => $first:ty
+            $(, $pattern:pat => $ty:ty)* $(,)?
+        }
+    ) => {
+        impl $crate::dispatch::Dispatch for $target {
+            type Kind = $kind;
+            type World<'a> = <$first as $crate::FromWorld>::World<'a>;
+            type Error = $crate::dispatch_from_world!(@error $first $(, $error)?);
+
+            fn dispatch(
+                world: &Self::World<'_>,
+                kind: Self::Kind,
+            ) -> ::core::result::Result<$crate::__private::Box<Self>, Self::Error> {
+                match kind {
+                    $first_pattern => match $crate::__private::resolve::<$first>(world) {
+                        ::core::result::Result::Ok(value) => {
+                            ::core::result::Result::Ok($crate::__private::Box::new(value))
+                        }
+                        ::core::result::Result::Err(error) => {
+                            ::core::result::Result::Err(::core::convert::From::from(error))
+                        }
+                    },
+                    $($pattern => match $crate::__private::resolve::<$ty>(world) {
+                        ::core::result::Result::Ok(value) => {
+                            ::core::result::Result::Ok($crate::__private::Box::new(value))
+                        }
+                        ::core::result::Result::Err(error) => {
+                            ::core::result::Result::Err(::core::convert::From::from(error))
+                        }
+                    },)*
+                }
+            }
+        }
+    };
+}
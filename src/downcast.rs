@@ -0,0 +1,139 @@
+//! # Downcasting trait object dependencies
+//!
+//! Dependencies resolved as `Box<dyn Trait>` hide their concrete type, which tests sometimes
+//! need back. [`DIContainer::extract_concrete`] resolves the trait object and downcasts it,
+//! failing with [`DowncastError::DowncastFailed`] when the concrete type differs.
+//!
+//! # The `AsAny` bound
+//!
+//! `dyn Trait` implements [`Any`] itself, with its own [`TypeId`](core::any::TypeId) rather than
+//! the one of the concrete type: downcasting needs a method dispatched through the vtable of the
+//! trait. The trait must have [`AsAny`] as a supertrait, `trait Service: AsAny`, which is
+//! implemented for every `'static` type: implementors of the trait can't hold non-`'static`
+//! references.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, downcast::{AsAny, DowncastError}};
+//!
+//! struct World;
+//!
+//! trait Mailer: AsAny {
+//!     fn send(&self, to: &str) -> String;
+//! }
+//!
+//! struct SpyMailer;
+//!
+//! impl Mailer for SpyMailer {
+//!     fn send(&self, to: &str) -> String {
+//!         format!("spied on {to}")
+//!     }
+//! }
+//!
+//! struct SmtpMailer;
+//!
+//! impl FromWorld for Box<dyn Mailer> {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Box::new(SpyMailer))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let spy = container.extract_concrete::<Box<dyn Mailer>, SpyMailer>().unwrap();
+//! assert_eq!(spy.send("alice"), "spied on alice");
+//!
+//! let smtp = container.extract_concrete::<Box<dyn Mailer>, SmtpMailer>();
+//! assert!(matches!(smtp, Err(DowncastError::DowncastFailed { .. })));
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use alloc::boxed::Box;
+use core::{any::Any, fmt};
+
+/// # Supertrait of the traits whose objects can be downcast
+///
+/// Implemented for every `'static` type
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// # Trait object that can be downcast to its concrete type
+///
+/// Implemented for `Box<dyn Trait>` when `Trait: AsAny`
+pub trait Downcast {
+    fn into_any(self) -> Box<dyn Any>;
+}
+
+impl<T: AsAny + ?Sized> Downcast for Box<T> {
+    fn into_any(self) -> Box<dyn Any> {
+        AsAny::into_any(self)
+    }
+}
+
+/// # Error of [`DIContainer::extract_concrete`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DowncastError<E> {
+    Resolve(E),
+    /// The trait object doesn't hold a value of the expected type
+    DowncastFailed {
+        expected: &'static str,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for DowncastError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DowncastError::Resolve(error) => error.fmt(f),
+            DowncastError::DowncastFailed { expected } => {
+                write!(f, "the resolved trait object is not a {expected}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for DowncastError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DowncastError::Resolve(error) => Some(error),
+            DowncastError::DowncastFailed { .. } => None,
+        }
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts the trait object `Object` and downcasts it to `Concrete`
+    pub fn extract_concrete<Object, Concrete>(
+        &self,
+    ) -> Result<Concrete, DowncastError<Object::Error>>
+    where
+        Object: for<'a> FromWorld<World<'a> = World> + Downcast,
+        Concrete: Any,
+    {
+        let object = resolve::<Object>(&self.world).map_err(DowncastError::Resolve)?;
+
+        object
+            .into_any()
+            .downcast::<Concrete>()
+            .map(|concrete| *concrete)
+            .map_err(|_| DowncastError::DowncastFailed {
+                expected: core::any::type_name::<Concrete>(),
+            })
+    }
+}
@@ -0,0 +1,191 @@
+//! # Environment variables
+//!
+//! For quick wiring, [`EnvVar<Key>`] resolves straight from an environment variable, bypassing
+//! the World, and [`EnvVarParsed<T, Key>`] parses it through [`FromStr`].
+//!
+//! # Variable names
+//!
+//! Const generic string parameters (`EnvVar<"SERVICE_URL">`) are not supported by stable Rust,
+//! the name is carried by a type implementing [`EnvKey`] instead:
+//!
+//! ```ignore
+//! struct ServiceUrl;
+//!
+//! impl EnvKey for ServiceUrl {
+//!     const NAME: &'static str = "SERVICE_URL";
+//! }
+//!
+//! let url: EnvVar<ServiceUrl> = container.extract()?;
+//! ```
+//!
+//! # World and Error
+//!
+//! The World is not read, it defaults to `()`: set it to the World of the dependency tree when
+//! used as a dependency. For the error, see the
+//! [error of the dependency](crate::FromDependency#error-of-the-dependency).
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, env::{EnvKey, EnvVar, EnvVarError, EnvVarParsed}};
+//!
+//! struct Port;
+//!
+//! impl EnvKey for Port {
+//!     const NAME: &'static str = "JE_DI_EXAMPLE_PORT";
+//! }
+//!
+//! let container = DIContainer::new(());
+//!
+//! assert!(container.extract::<EnvVar<Port>>().is_err());
+//!
+//! unsafe { std::env::set_var("JE_DI_EXAMPLE_PORT", "8080") };
+//! let port: EnvVarParsed<u16, Port> = container.extract().unwrap();
+//! assert_eq!(*port, 8080);
+//!
+//! unsafe { std::env::set_var("JE_DI_EXAMPLE_PORT", "http") };
+//! let Err(error) = container.extract::<EnvVarParsed<u16, Port>>() else {
+//!     unreachable!()
+//! };
+//! assert!(matches!(error, EnvVarError::Invalid { name: "JE_DI_EXAMPLE_PORT", .. }));
+//! ```
+
+use crate::FromWorld;
+use std::{marker::PhantomData, ops::Deref, str::FromStr};
+
+type Marker<K, World, Error> = PhantomData<fn() -> (K, World, Error)>;
+
+/// # Name of an environment variable
+pub trait EnvKey {
+    const NAME: &'static str;
+}
+
+/// # Value of the environment variable [`EnvKey::NAME`]
+pub struct EnvVar<K, World = (), Error = MissingEnvVar> {
+    pub value: String,
+    _marker: Marker<K, World, Error>,
+}
+
+impl<K, World, Error> EnvVar<K, World, Error> {
+    pub fn into_inner(self) -> String {
+        self.value
+    }
+}
+
+impl<K, World, Error> Deref for EnvVar<K, World, Error> {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<K, W, E> FromWorld for EnvVar<K, W, E>
+where
+    K: EnvKey,
+    E: From<MissingEnvVar>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: read::<K>()?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # Value of the environment variable [`EnvKey::NAME`] parsed through [`FromStr`]
+pub struct EnvVarParsed<T, K, World = (), Error = EnvVarError> {
+    pub value: T,
+    _marker: Marker<K, World, Error>,
+}
+
+impl<T, K, World, Error> EnvVarParsed<T, K, World, Error> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, K, World, Error> Deref for EnvVarParsed<T, K, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, K, W, E> FromWorld for EnvVarParsed<T, K, W, E>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+    K: EnvKey,
+    E: From<EnvVarError>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let value = read::<K>().map_err(EnvVarError::Missing)?;
+        let value = value.parse().map_err(|error: T::Err| EnvVarError::Invalid {
+            name: K::NAME,
+            message: error.to_string(),
+        })?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
+
+fn read<K: EnvKey>() -> Result<String, MissingEnvVar> {
+    std::env::var(K::NAME).map_err(|_| MissingEnvVar { name: K::NAME })
+}
+
+/// # Error returned when an environment variable is not set, or not valid unicode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingEnvVar {
+    pub name: &'static str,
+}
+
+impl std::fmt::Display for MissingEnvVar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "environment variable `{}` is not set", self.name)
+    }
+}
+
+impl std::error::Error for MissingEnvVar {}
+
+/// # Error resolving an [`EnvVarParsed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvVarError {
+    Missing(MissingEnvVar),
+    Invalid { name: &'static str, message: String },
+}
+
+impl From<MissingEnvVar> for EnvVarError {
+    fn from(missing: MissingEnvVar) -> Self {
+        EnvVarError::Missing(missing)
+    }
+}
+
+impl std::fmt::Display for EnvVarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvVarError::Missing(missing) => missing.fmt(f),
+            EnvVarError::Invalid { name, message } => {
+                write!(f, "invalid environment variable `{name}`: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvVarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnvVarError::Missing(missing) => Some(missing),
+            EnvVarError::Invalid { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! # Expiring dependencies
+//!
+//! [`Expiring<T>`] holds a built `T` for a time to live, [`Expiring::get_or_refresh`] rebuilds it
+//! through [`FromWorld`] once it expired, e.g. short-lived credentials behind a `TokenProvider`.
+//!
+//! # Design
+//!
+//! The World is passed to every [`Expiring::get_or_refresh`] call instead of being stored: an
+//! [`Expiring<T>`] is usually held by the World itself (or next to it), holding a reference back
+//! to the World would make it self-referential, and cloning it, or requiring an `Arc`, would
+//! force a shape on every World.
+//!
+//! # Concurrency
+//!
+//! Refreshes are single-flight: the lock is held while `T` is rebuilt, concurrent callers wait
+//! for the refresh and share the fresh value instead of each building their own. Callers hitting
+//! a valid value only take the lock for the duration of an `Arc` clone.
+//!
+//! A failed refresh leaves the expired value in place, the next call tries again.
+//!
+//! # Time to live
+//!
+//! A time to live too large for the clock, e.g. [`Duration::MAX`], never expires: `T` is built
+//! once and only rebuilt after an [`Expiring::invalidate`].
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromWorld, expiring::Expiring};
+//! use std::{sync::Arc, time::Duration};
+//!
+//! struct World {
+//!     secret: String,
+//! }
+//!
+//! struct Token(String);
+//!
+//! impl FromWorld for Token {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(format!("token-for-{}", world.secret)))
+//!     }
+//! }
+//!
+//! let world = World { secret: "s3cr3t".to_string() };
+//! let token = Expiring::<Token>::new(Duration::from_secs(60));
+//!
+//! let first = token.get_or_refresh(&world).unwrap();
+//! let second = token.get_or_refresh(&world).unwrap();
+//! assert_eq!(first.0, "token-for-s3cr3t");
+//! assert!(Arc::ptr_eq(&first, &second));
+//!
+//! token.invalidate();
+//! let third = token.get_or_refresh(&world).unwrap();
+//! assert!(!Arc::ptr_eq(&first, &third));
+//!
+//! let forever = Expiring::<Token>::new(Duration::MAX);
+//! let first = forever.get_or_refresh(&world).unwrap();
+//! assert!(Arc::ptr_eq(&first, &forever.get_or_refresh(&world).unwrap()));
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+struct Entry<T> {
+    value: Arc<T>,
+    /// `None` when the time to live overflows the clock, the value never expires
+    expires_at: Option<Instant>,
+}
+
+/// # `T` rebuilt from the World once its time to live elapsed
+pub struct Expiring<T> {
+    ttl: Duration,
+    entry: Mutex<Option<Entry<T>>>,
+}
+
+impl<T> Expiring<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Forces the next [`Expiring::get_or_refresh`] to rebuild `T`
+    pub fn invalidate(&self) {
+        *self.lock() = None;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Entry<T>>> {
+        self.entry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn valid(entry: &Option<Entry<T>>) -> Option<Arc<T>> {
+        entry
+            .as_ref()
+            .filter(|entry| entry.expires_at.is_none_or(|at| at > Instant::now()))
+            .map(|entry| entry.value.clone())
+    }
+
+    fn store(&self, entry: &mut Option<Entry<T>>, value: T) -> Arc<T> {
+        let value = Arc::new(value);
+        *entry = Some(Entry {
+            value: value.clone(),
+            expires_at: Instant::now().checked_add(self.ttl),
+        });
+
+        value
+    }
+
+    /// Returns the current value, building it with `build` under the lock if it is missing or
+    /// expired
+    pub(crate) fn get_or_build<E>(
+        &self,
+        build: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Arc<T>, E> {
+        let mut entry = self.lock();
+        if let Some(value) = Self::valid(&entry) {
+            return Ok(value);
+        }
+
+        let value = build()?;
+        Ok(self.store(&mut entry, value))
+    }
+
+    /// Returns the current value if it is still valid, without building it
+    #[cfg(feature = "fs")]
+    pub(crate) fn cached(&self) -> Option<Arc<T>> {
+        Self::valid(&self.lock())
+    }
+
+    /// Replaces the current value, for callers building it without holding the lock
+    #[cfg(feature = "fs")]
+    pub(crate) fn insert(&self, value: T) -> Arc<T> {
+        self.store(&mut self.lock(), value)
+    }
+}
+
+impl<T: FromWorld> Expiring<T> {
+    /// Returns the current `T`, rebuilding it if it is missing or expired
+    pub fn get_or_refresh(&self, world: &T::World<'_>) -> Result<Arc<T>, T::Error> {
+        self.get_or_build(|| resolve::<T>(world))
+    }
+}
@@ -0,0 +1,153 @@
+//! # Feature gated dependencies
+//!
+//! Dependencies only resolvable with a cargo feature of the crate declaring them (`tls`,
+//! `postgres`) are declared with [`feature_gated_dependency!`](crate::feature_gated_dependency).
+//! With the feature enabled the implementation is emitted as written, without it a stub
+//! implementation is emitted instead, bounded on [`FeatureEnabled`] which is never implemented.
+//! The stub fails to compile as soon as the dependency is extracted or depended upon, and the
+//! error points at the `feature_gated_dependency!` declaration instead of reporting a missing
+//! implementation:
+//!
+//! ```text
+//! error[E0277]: the trait bound `TlsClient: FromWorld` is not satisfied
+//!   |
+//!   |     let client: TlsClient = container.extract().unwrap();
+//!   |                                       ^^^^^^^ unsatisfied trait bound
+//!   |
+//! help: the trait `FromWorld` is implemented for `TlsClient`
+//!   |
+//!   | / feature_gated_dependency! {
+//!   | |     #[feature = "tls"]
+//! ```
+//!
+//! The dependency type itself must exist with and without the feature, gate its fields rather
+//! than the type. The `feature` is evaluated in the crate invoking the macro.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, feature_gated_dependency};
+//!
+//! struct World;
+//!
+//! struct TlsClient;
+//!
+//! feature_gated_dependency! {
+//!     #[feature = "tls"]
+//!     impl FromWorld for TlsClient {
+//!         type World<'a> = World;
+//!         type Error = String;
+//!
+//!         fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!             Ok(Self)
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Without the `tls` feature, extracting it doesn't compile:
+//!
+//! ```compile_fail,E0277
+//! # use je_di::{DIContainer, feature_gated_dependency};
+//! # struct World;
+//! # struct TlsClient;
+//! # feature_gated_dependency! {
+//! #     #[feature = "tls"]
+//! #     impl FromWorld for TlsClient {
+//! #         type World<'a> = World;
+//! #         type Error = String;
+//! #         fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//! #             Ok(Self)
+//! #         }
+//! #     }
+//! # }
+//! let container = DIContainer::new(World);
+//! let client: TlsClient = container.extract().unwrap();
+//! ```
+
+use core::marker::PhantomData;
+
+/// Never implemented, the bound of the stub implementation of a feature gated dependency
+#[diagnostic::on_unimplemented(
+    message = "`{T}` is behind a disabled cargo feature",
+    label = "requires a disabled cargo feature",
+    note = "enable the feature named in the `feature_gated_dependency!` declaration of `{T}`"
+)]
+pub trait FeatureEnabled<T> {}
+
+/// Higher ranked subject of [`FeatureEnabled`], deferring the bound to the uses of the stub
+pub struct Gate<'a, T>(PhantomData<&'a T>);
+
+/// # Declares a dependency resolvable only with a cargo feature
+///
+/// Takes a `FromWorld` or `FromAsyncWorld` implementation preceded by `#[feature = "name"]`, see
+/// the [module documentation](crate::feature_gate)
+#[macro_export]
+macro_rules! feature_gated_dependency {
+    (
+        #[feature = $feature:literal]
+        impl FromWorld for $ty:ty {
+            type World<$lt:lifetime> = $world:ty;
+            type Error = $error:ty;
+
+            $($body:tt)*
+        }
+    ) => {
+        #[cfg(feature = $feature)]
+        impl $crate::FromWorld for $ty {
+            type World<$lt> = $world;
+            type Error = $error;
+
+            $($body)*
+        }
+
+        #[cfg(not(feature = $feature))]
+        impl $crate::FromWorld for $ty
+        where
+            for<'__gate> $crate::feature_gate::Gate<'__gate, $ty>:
+                $crate::feature_gate::FeatureEnabled<$ty>,
+        {
+            type World<$lt> = $world;
+            type Error = $error;
+
+            fn from_world(_world: &Self::World<'_>) -> ::core::result::Result<Self, Self::Error> {
+                ::core::unreachable!("the bound of a feature gated stub is never satisfied")
+            }
+        }
+    };
+
+    (
+        #[feature = $feature:literal]
+        impl FromAsyncWorld for $ty:ty {
+            type World<$lt:lifetime> = $world:ty;
+            type Error = $error:ty;
+
+            $($body:tt)*
+        }
+    ) => {
+        #[cfg(feature = $feature)]
+        #[$crate::async_trait]
+        impl $crate::FromAsyncWorld for $ty {
+            type World<$lt> = $world;
+            type Error = $error;
+
+            $($body)*
+        }
+
+        #[cfg(not(feature = $feature))]
+        #[$crate::async_trait]
+        impl $crate::FromAsyncWorld for $ty
+        where
+            for<'__gate> $crate::feature_gate::Gate<'__gate, $ty>:
+                $crate::feature_gate::FeatureEnabled<$ty>,
+        {
+            type World<$lt> = $world;
+            type Error = $error;
+
+            async fn from_world<'a>(
+                _world: &'a Self::World<'a>,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                ::core::unreachable!("the bound of a feature gated stub is never satisfied")
+            }
+        }
+    };
+}
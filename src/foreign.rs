@@ -0,0 +1,177 @@
+//! # Foreign structs
+//!
+//! A struct of another crate can't implement [`FromWorld`](crate::FromWorld) nor derive anything,
+//! but its fields can still be dependencies. [`extract_struct!`](crate::extract_struct) resolves
+//! every field from a container and builds the struct, named-field or tuple:
+//!
+//! ```ignore
+//! let service: Service = extract_struct! { Service { db: Database, mailer: Mailer } from container }?;
+//! let pair: Pair = extract_struct! { Pair(Database, Mailer) from container }?;
+//! ```
+//!
+//! The macro evaluates to a `Result` of the struct. The field types are the types of the
+//! dependencies, resolved with [`DIContainer::extract`](crate::DIContainer::extract), so they
+//! all share the World of the container.
+//!
+//! # Errors
+//!
+//! The fields are resolved in declaration order, the first error is returned and the following
+//! fields aren't resolved. The errors of the fields must be of the same type, or with a trailing
+//! `error = Type` convert into `Type` through [`From`]:
+//!
+//! ```ignore
+//! extract_struct! { Service { db: Database, mailer: Mailer } from container, error = AppError }
+//! ```
+//!
+//! # Conversion
+//!
+//! The dependencies are converted into the field types with [`Into`]: a field can be a
+//! dependency type itself, or a plain type a dependency converts into.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, extract_struct};
+//!
+//! mod foreign {
+//!     pub struct Service {
+//!         pub url: String,
+//!         pub retries: u32,
+//!     }
+//!
+//!     pub struct Pair(pub String, pub u32);
+//!
+//!     pub struct Endpoint {
+//!         pub url: String,
+//!     }
+//! }
+//!
+//! struct World;
+//!
+//! struct Url(String);
+//!
+//! impl FromWorld for Url {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("https://example.com".to_string()))
+//!     }
+//! }
+//!
+//! impl From<Url> for String {
+//!     fn from(url: Url) -> Self {
+//!         url.0
+//!     }
+//! }
+//!
+//! struct Retries;
+//!
+//! impl FromWorld for Retries {
+//!     type World<'a> = World;
+//!     type Error = std::num::ParseIntError;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl From<Retries> for u32 {
+//!     fn from(_: Retries) -> Self {
+//!         3
+//!     }
+//! }
+//!
+//! #[derive(Debug)]
+//! enum AppError {
+//!     Config(String),
+//!     Parse(std::num::ParseIntError),
+//! }
+//!
+//! impl From<String> for AppError {
+//!     fn from(error: String) -> Self {
+//!         Self::Config(error)
+//!     }
+//! }
+//!
+//! impl From<std::num::ParseIntError> for AppError {
+//!     fn from(error: std::num::ParseIntError) -> Self {
+//!         Self::Parse(error)
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let service = extract_struct! {
+//!     foreign::Service { url: Url, retries: Retries } from container, error = AppError
+//! }
+//! .unwrap();
+//! assert_eq!((service.url.as_str(), service.retries), ("https://example.com", 3));
+//!
+//! let pair = extract_struct! { foreign::Pair(Url, Retries) from container, error = AppError }.unwrap();
+//! assert_eq!((pair.0.as_str(), pair.1), ("https://example.com", 3));
+//!
+//! let endpoint: Result<foreign::Endpoint, String> =
+//!     extract_struct! { foreign::Endpoint { url: Url } from container };
+//! assert_eq!(endpoint.unwrap().url, "https://example.com");
+//! ```
+
+/// # Resolves every field of a struct from a container
+///
+/// See the [module documentation](crate::foreign)
+#[macro_export]
+macro_rules! extract_struct {
+    (@field $container:ident, $ty:ty, [$($error:ty)?]) => {
+        match $container.extract::<$ty>() {
+            ::core::result::Result::Ok(value) => ::core::convert::Into::into(value),
+            ::core::result::Result::Err(error) => {
+                return ::core::result::Result::Err(
+                    $crate::extract_struct!(@error error $(, $error)?),
+                );
+            }
+        }
+    };
+
+    (@error $value:ident) => {
+        $value
+    };
+
+    (@error $value:ident, $error:ty) => {
+        <$error as ::core::convert::From<_>>::from($value)
+    };
+
+    (@named $error:tt $($name:ident)::+ { $($field:ident: $ty:ty),+ } from $container:expr) => {
+        (|| {
+            let container = &$container;
+
+            ::core::result::Result::Ok($($name)::+ {
+                $($field: $crate::extract_struct!(@field container, $ty, $error)),+
+            })
+        })()
+    };
+
+    (@tuple $error:tt $($name:ident)::+ ( $($ty:ty),+ ) from $container:expr) => {
+        (|| {
+            let container = &$container;
+
+            ::core::result::Result::Ok($($name)::+ (
+                $($crate::extract_struct!(@field container, $ty, $error)),+
+            ))
+        })()
+    };
+
+    (
+        $($name:ident)::+ { $($field:ident: $ty:ty),+ $(,)? } from $container:expr
+        $(, error = $error:ty)? $(,)?
+    ) => {
+        $crate::extract_struct!(
+            @named [$($error)?] $($name)::+ { $($field: $ty),+ } from $container
+        )
+    };
+
+    (
+        $($name:ident)::+ ( $($ty:ty),+ $(,)? ) from $container:expr
+        $(, error = $error:ty)? $(,)?
+    ) => {
+        $crate::extract_struct!(@tuple [$($error)?] $($name)::+ ( $($ty),+ ) from $container)
+    };
+}
@@ -0,0 +1,185 @@
+//! # Dependency graph description
+//!
+//! Rust cannot enumerate the [`FromWorld`] implementations of a crate, so the shape of a
+//! dependency tree has to be described by the types themselves through
+//! [`DescribeDependencies`].
+//!
+//! Every [`FromDependency`] type describes itself and its dependency automatically, leaf types
+//! (implementing [`FromWorld`] directly) only need an empty implementation:
+//!
+//! ```ignore
+//! use je_di::DescribeDependencies;
+//!
+//! impl DescribeDependencies for Printer {}
+//! ```
+//!
+//! Async dependency trees have no blanket implementation (it would overlap with the sync one),
+//! describe the node and its dependency by hand:
+//!
+//! ```ignore
+//! impl DescribeDependencies for Looper {
+//!     const DEPENDENCY_COUNT: usize = 1 + Printer::DEPENDENCY_COUNT;
+//!
+//!     fn describe(graph: &mut DependencyGraph) {
+//!         graph.add::<Self>();
+//!         Printer::describe(graph);
+//!     }
+//! }
+//! ```
+//!
+//! # Counting the resolutions
+//!
+//! [`DescribeDependencies::DEPENDENCY_COUNT`] is the number of `from_world` calls resolving a
+//! root makes, known at compile time: the root, plus the count of its dependency, the counts of
+//! the members of a tuple summed. A node shared by several dependents is counted once per
+//! dependent, as it is resolved once per dependent, while [`DependencyGraph`] lists it once:
+//! the count is the size of the tree, the graph its distinct nodes.
+//!
+//! A test, or a `const` assertion, bounding the count of a root catches an accidentally huge
+//! tree in review. Hand written implementations must set the count along with `describe`, the
+//! default of `1` describes a leaf.
+//!
+//! ```
+//! use je_di::{DependencyGraph, DescribeDependencies, FromDependency, FromWorld};
+//!
+//! struct World;
+//!
+//! struct Config;
+//!
+//! impl FromWorld for Config {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl DescribeDependencies for Config {}
+//!
+//! struct Database;
+//!
+//! impl FromDependency for Database {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Config;
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, _config: &Config) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! struct Server;
+//!
+//! impl FromDependency for Server {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = (Config, Database);
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         _dependencies: &(Config, Database),
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! // Server, Config, Database and the Config of the Database
+//! const _: () = assert!(Server::DEPENDENCY_COUNT == 4);
+//! assert_eq!(DependencyGraph::of::<Server>().nodes().count(), 3);
+//! ```
+
+use crate::{FromDependency, FromWorld};
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+/// # Flattened description of a dependency tree
+///
+/// Holds every node reachable from a root, each type is listed once
+#[derive(Debug, Default, Clone)]
+pub struct DependencyGraph {
+    nodes: Vec<(TypeId, &'static str)>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Describes `T` and its dependencies
+    pub fn of<T: DescribeDependencies>() -> Self {
+        let mut graph = Self::new();
+        T::describe(&mut graph);
+        graph
+    }
+
+    /// Adds a node to the graph, duplicated nodes are ignored
+    pub fn add<T: 'static>(&mut self) {
+        let type_id = TypeId::of::<T>();
+        if !self.contains(type_id) {
+            self.nodes.push((type_id, core::any::type_name::<T>()));
+        }
+    }
+
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.nodes.iter().any(|(id, _)| *id == type_id)
+    }
+
+    /// Iterates over the nodes of the graph as ([`TypeId`], type name) pairs
+    pub fn nodes(&self) -> impl Iterator<Item = (TypeId, &'static str)> + '_ {
+        self.nodes.iter().copied()
+    }
+}
+
+/// # Describes the shape of a dependency tree
+///
+/// The default implementation describes a leaf, adding only `Self` to the graph
+pub trait DescribeDependencies: 'static {
+    /// Number of `from_world` calls resolving `Self`, see the
+    /// [module documentation](crate::graph#counting-the-resolutions)
+    const DEPENDENCY_COUNT: usize = 1;
+
+    fn describe(graph: &mut DependencyGraph)
+    where
+        Self: core::marker::Sized,
+    {
+        graph.add::<Self>();
+    }
+}
+
+impl<T> DescribeDependencies for T
+where
+    T: FromDependency + 'static,
+    T::Dependency: FromWorld + DescribeDependencies,
+{
+    const DEPENDENCY_COUNT: usize = 1 + T::Dependency::DEPENDENCY_COUNT;
+
+    fn describe(graph: &mut DependencyGraph) {
+        graph.add::<Self>();
+        T::Dependency::describe(graph);
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($n:tt:$name:ident),+) => {
+        impl<$($name),+> DescribeDependencies for ($($name,)+)
+        where
+            $($name: DescribeDependencies),+
+        {
+            const DEPENDENCY_COUNT: usize = 0 $(+ $name::DEPENDENCY_COUNT)+;
+
+            fn describe(graph: &mut DependencyGraph) {
+                $($name::describe(graph);)+
+            }
+        }
+    };
+}
+
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7, 8:Dep8);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2);
+impl_tuple!(0:Dep0, 1:Dep1);
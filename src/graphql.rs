@@ -0,0 +1,122 @@
+//! # je-di async-graphql integration
+//!
+//! Resolves [`FromAsyncWorld`] dependencies inside GraphQL resolvers, from the
+//! [`Context`] of the field being resolved. The World of the dependencies is
+//! [`GraphQLWorld<'a, State>`], pairing:
+//!
+//! - the [`Context`], the per-request half: the data attached to the request with
+//!   [`Request::data`](async_graphql::Request::data), read with `ctx.data::<T>()`, the field
+//!   arguments, the selection set
+//! - the State, the shared half: the data attached to the schema with
+//!   [`SchemaBuilder::data`](async_graphql::SchemaBuilder::data), looked up once by
+//!   [`extract`] with `ctx.data::<State>()`
+//!
+//! [`graphql_world!`](crate::graphql_world) implements a dependency over this World, like
+//! [`axum_world!`](crate::axum_world) does for axum handlers.
+//!
+//! # Errors
+//!
+//! The error of the dependency is converted into an [`async_graphql::Error`] through
+//! [`Into`], implemented for every `Display + Send + Sync + 'static` error, and reported in
+//! the `errors` of the response. A State missing from the schema data is reported the same way.
+//!
+//! # Usage
+//! ```
+//! use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Request, Schema};
+//! use je_di::{graphql::extract, graphql_world};
+//!
+//! struct Database {
+//!     users: Vec<(&'static str, &'static str)>,
+//! }
+//!
+//! struct Token(&'static str);
+//!
+//! struct CurrentUser(&'static str);
+//!
+//! graphql_world! {
+//!     async fn from_world(ctx: &Context, database: &Database) -> Result<CurrentUser, String> {
+//!         let token = ctx.data::<Token>().map_err(|_| "unauthenticated".to_string())?;
+//!         let (_, name) = database
+//!             .users
+//!             .iter()
+//!             .find(|(user_token, _)| *user_token == token.0)
+//!             .ok_or_else(|| "unknown token".to_string())?;
+//!
+//!         Ok(CurrentUser(name))
+//!     }
+//! }
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+//!         let user: CurrentUser = extract::<_, Database>(ctx).await?;
+//!         Ok(user.0.to_string())
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+//!     .data(Database { users: vec![("secret", "alice")] })
+//!     .finish();
+//!
+//! let response = schema.execute(Request::new("{ me }").data(Token("secret"))).await;
+//! assert_eq!(response.data.to_string(), r#"{me: "alice"}"#);
+//!
+//! let response = schema.execute("{ me }").await;
+//! assert_eq!(response.errors[0].message, "unauthenticated");
+//! # });
+//! ```
+
+use crate::{FromAsyncWorld, resolve::resolve_async};
+use async_graphql::Context;
+
+/// World of the dependencies resolved in GraphQL resolvers, the context of the field and the
+/// State of the schema
+pub type GraphQLWorld<'a, State> = (&'a Context<'a>, &'a State);
+
+/// Extracts `T` from the context of a resolver, the State is looked up in the schema data
+pub async fn extract<T, State>(ctx: &Context<'_>) -> async_graphql::Result<T>
+where
+    T: for<'a> FromAsyncWorld<World<'a> = GraphQLWorld<'a, State>>,
+    T::Error: Into<async_graphql::Error>,
+    State: Send + Sync + 'static,
+{
+    let state = ctx.data::<State>()?;
+
+    resolve_async::<T>(&(ctx, state)).await.map_err(Into::into)
+}
+
+/// # Implements [`FromAsyncWorld`] over a [`GraphQLWorld`]
+///
+/// ```ignore
+/// graphql_world! {
+///     async fn from_world(ctx: &Context, state: &State) -> Result<Type, Error> {
+///         // implementation returning Result<Self, Error>
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! graphql_world {
+    (
+        async fn from_world(
+            $ctx:ident: &Context,
+            $state_ident:ident: &$state:ty
+        ) -> Result<$ty:ty, $error:ty> { $($expr:tt)* }
+    ) => {
+        #[$crate::async_trait]
+        impl $crate::async_dependency::FromAsyncWorld for $ty {
+            type World<'a> = $crate::graphql::GraphQLWorld<'a, $state>;
+            type Error = $error;
+
+            async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+                #[allow(unused)]
+                let $ctx = world.0;
+                #[allow(unused)]
+                let $state_ident = world.1;
+                $($expr)*
+            }
+        }
+    };
+}
@@ -0,0 +1,122 @@
+//! # Lock guards
+//!
+//! World fields updated at runtime, e.g. a configuration reloaded on change, are often behind a
+//! [`RwLock`]. [`ReadGuard<'a, T, World>`] is a dependency holding a read guard of the
+//! `RwLock<T>` of the World, extracted with [`DIContainer::extract_ref`]: the value is read in
+//! place, without cloning it out of the lock.
+//!
+//! The World exposes the lock through [`AsRef<RwLock<T>>`]. A poisoned lock is still read, like
+//! the other locks of the crate: the value is left as the panicking writer left it.
+//!
+//! # Lifetimes
+//!
+//! The guard borrows the lock from the World of the container for `'a`, through
+//! [`BorrowFromWorld<'a>`] with `World = &'a World`: the container stays borrowed as long as the
+//! guard lives, and the guard can't outlive it.
+//!
+//! # Deadlocks
+//!
+//! The lock stays read locked as long as the guard lives, writers wait until it is dropped. Keep
+//! guards short-lived:
+//!
+//! - writing the lock on the thread holding a guard deadlocks, or panics
+//! - [`RwLock`] may block new readers while a writer is waiting, depending on the platform.
+//!   Extracting a second guard of the same lock on the thread holding one can then deadlock: the
+//!   second read waits for the writer, which waits for the first guard
+//! - a guard held across an `.await` keeps the writers waiting for as long as the task is
+//!   suspended, and isn't `Send`
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, guard::ReadGuard};
+//! use std::sync::{Arc, RwLock};
+//!
+//! struct Config {
+//!     log_level: &'static str,
+//! }
+//!
+//! struct World {
+//!     config: Arc<RwLock<Config>>,
+//! }
+//!
+//! impl AsRef<RwLock<Config>> for World {
+//!     fn as_ref(&self) -> &RwLock<Config> {
+//!         &self.config
+//!     }
+//! }
+//!
+//! type ConfigGuard<'a> = ReadGuard<'a, Config, World>;
+//!
+//! let config = Arc::new(RwLock::new(Config { log_level: "info" }));
+//! let container = DIContainer::new(World { config: config.clone() });
+//!
+//! let guard = container.extract_ref::<ConfigGuard>().unwrap();
+//! assert_eq!(guard.log_level, "info");
+//! assert!(config.try_write().is_err());
+//! drop(guard);
+//!
+//! config.write().unwrap().log_level = "debug";
+//! assert_eq!(container.extract_ref::<ConfigGuard>().unwrap().log_level, "debug");
+//! ```
+
+use crate::{BorrowFromWorld, DIContainer};
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{PoisonError, RwLock, RwLockReadGuard},
+};
+
+/// # Read guard of the `RwLock<T>` of the World
+///
+/// See the [error of the dependency](crate::FromDependency#error-of-the-dependency)
+pub struct ReadGuard<'a, T, World, Error = Infallible> {
+    guard: RwLockReadGuard<'a, T>,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<'a, T, World, Error> ReadGuard<'a, T, World, Error> {
+    pub fn into_inner(self) -> RwLockReadGuard<'a, T> {
+        self.guard
+    }
+}
+
+impl<T, World, Error> Deref for ReadGuard<'_, T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<'a, T, W, E> BorrowFromWorld<'a> for ReadGuard<'a, T, W, E>
+where
+    T: 'a,
+    W: AsRef<RwLock<T>> + 'a,
+{
+    type World = &'a W;
+    type Error = E;
+
+    fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+        Ok(Self {
+            guard: world
+                .as_ref()
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts a guard borrowing from the World, such as a [`ReadGuard`], the container stays
+    /// borrowed as long as the guard lives
+    ///
+    /// See the [deadlock risks](crate::guard#deadlocks) of holding a guard
+    pub fn extract_ref<'a, G>(&'a self) -> Result<G, G::Error>
+    where
+        G: BorrowFromWorld<'a, World = &'a World> + Deref,
+    {
+        G::borrow_from_world(&self.world)
+    }
+}
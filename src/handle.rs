@@ -0,0 +1,109 @@
+//! # Container handles
+//!
+//! [`ContainerHandle<World>`] resolves to a container over a clone of the World, for legacy
+//! code doing ad-hoc lookups with [`extract`](crate::DIContainer::extract) after its own
+//! resolution, usually while migrating it to declared dependencies.
+//!
+//! # Caveats
+//!
+//! This is the service locator pattern, prefer declaring dependencies:
+//!
+//! - the dependencies looked up through the handle are missing from the signature of the
+//!   dependency, from [`graph`](crate::graph) and from compile time checks of the World
+//! - lookup errors surface when the handle is used, not when the dependency is resolved
+//! - the World is cloned on every resolution of a handle: it must be [`Clone`], keep it cheap to
+//!   clone by sharing it behind an [`Arc`](alloc::sync::Arc) or holding `Arc`s of its fields
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromDependency, FromWorld, handle::ContainerHandle};
+//! use std::sync::Arc;
+//!
+//! struct Settings {
+//!     greeting: &'static str,
+//! }
+//!
+//! type World = Arc<Settings>;
+//!
+//! struct Greeting(&'static str);
+//!
+//! impl FromWorld for Greeting {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.greeting))
+//!     }
+//! }
+//!
+//! struct LegacyService {
+//!     container: ContainerHandle<World, String>,
+//! }
+//!
+//! impl LegacyService {
+//!     fn greet(&self) -> Result<&'static str, String> {
+//!         Ok(self.container.extract::<Greeting>()?.0)
+//!     }
+//! }
+//!
+//! impl FromDependency for LegacyService {
+//!     type Error = String;
+//!     type World<'a> = World;
+//!     type Dependency = ContainerHandle<World, String>;
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         container: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self { container: container.clone() })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(Arc::new(Settings { greeting: "hello" }));
+//! let service: LegacyService = container.extract().unwrap();
+//! assert_eq!(service.greet().unwrap(), "hello");
+//! ```
+
+use crate::{DIContainer, FromWorld};
+use core::{convert::Infallible, marker::PhantomData, ops::Deref};
+
+/// # Container over a clone of the World
+pub struct ContainerHandle<World, Error = Infallible> {
+    container: DIContainer<World>,
+    _marker: PhantomData<fn() -> Error>,
+}
+
+impl<World, Error> ContainerHandle<World, Error> {
+    pub fn into_container(self) -> DIContainer<World> {
+        self.container
+    }
+}
+
+impl<World: Clone, Error> Clone for ContainerHandle<World, Error> {
+    fn clone(&self) -> Self {
+        Self {
+            container: DIContainer::new(self.container.world.clone()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<World, Error> Deref for ContainerHandle<World, Error> {
+    type Target = DIContainer<World>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.container
+    }
+}
+
+impl<W: Clone, E> FromWorld for ContainerHandle<W, E> {
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            container: DIContainer::new(world.clone()),
+            _marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+//! # Health checks
+//!
+//! For startup validation and readiness probes, [`DIContainer::health_check`] resolves a list of
+//! root types and reports every failure in a [`HealthReport`], instead of stopping at the first
+//! one like extracting a tuple does. The resolved values are dropped.
+//!
+//! [`health_check!`](crate::health_check) builds the list from the root types,
+//! [`HealthCheck::of`] builds a single check. Errors are boxed, the error of every root must
+//! convert into [`BoxError`].
+//!
+//! # Async
+//!
+//! [`DIContainer::health_check_async`] and `health_check!(async container; ...)` resolve
+//! [`FromAsyncWorld`](crate::FromAsyncWorld) roots, one after the other, in order.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, health_check};
+//!
+//! struct World {
+//!     database_url: Option<&'static str>,
+//! }
+//!
+//! struct Database;
+//!
+//! impl FromWorld for Database {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         world.database_url.map(|_| Self).ok_or_else(|| "DATABASE_URL is not set".to_string())
+//!     }
+//! }
+//!
+//! struct Cache;
+//!
+//! impl FromWorld for Cache {
+//!     type World<'a> = World;
+//!     type Error = std::io::Error;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { database_url: None });
+//!
+//! let report = health_check!(container; Database, Cache);
+//! assert!(!report.is_healthy());
+//! assert_eq!(report.resolved, [std::any::type_name::<Cache>()]);
+//! assert_eq!(report.failed[0].1.to_string(), "DATABASE_URL is not set");
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::fmt;
+
+pub use crate::boxed::BoxError;
+
+/// # Outcome of a health check
+#[derive(Debug, Default)]
+pub struct HealthReport {
+    /// Names of the roots that resolved, in order
+    pub resolved: Vec<&'static str>,
+    /// Names and errors of the roots that failed, in order
+    pub failed: Vec<(&'static str, BoxError)>,
+}
+
+impl HealthReport {
+    /// Whether every root resolved
+    pub fn is_healthy(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    fn record(&mut self, name: &'static str, result: Result<(), BoxError>) {
+        match result {
+            Ok(()) => self.resolved.push(name),
+            Err(error) => self.failed.push((name, error)),
+        }
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} resolved, {} failed",
+            self.resolved.len(),
+            self.failed.len()
+        )?;
+        for (name, error) in &self.failed {
+            write!(f, "\n  {name}: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// # Resolution of a root type checked by [`DIContainer::health_check`]
+pub struct HealthCheck<World> {
+    name: &'static str,
+    check: fn(&World) -> Result<(), BoxError>,
+}
+
+impl<World> HealthCheck<World> {
+    /// Checks that `T` resolves, named after its type
+    pub fn of<T>() -> Self
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+        T::Error: Into<BoxError>,
+    {
+        Self {
+            name: std::any::type_name::<T>(),
+            check: |world| resolve::<T>(world).map(drop).map_err(Into::into),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Runs every check, in order, collecting the failures
+    pub fn health_check(&self, checks: &[HealthCheck<World>]) -> HealthReport {
+        let mut report = HealthReport::default();
+        for check in checks {
+            report.record(check.name, (check.check)(&self.world));
+        }
+        report
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use self::r#async::AsyncHealthCheck;
+
+#[cfg(feature = "async")]
+mod r#async {
+    use super::{BoxError, HealthReport};
+    use crate::{DIContainer, FromAsyncWorld, resolve::resolve_async};
+    use std::{future::Future, pin::Pin};
+
+    type CheckFuture<'w> = Pin<Box<dyn Future<Output = Result<(), BoxError>> + Send + 'w>>;
+
+    /// # Resolution of a root type checked by [`DIContainer::health_check_async`]
+    pub struct AsyncHealthCheck<World> {
+        name: &'static str,
+        check: for<'w> fn(&'w World) -> CheckFuture<'w>,
+    }
+
+    impl<World> AsyncHealthCheck<World> {
+        /// Checks that `T` resolves, named after its type
+        pub fn of<T>() -> Self
+        where
+            T: for<'a> FromAsyncWorld<World<'a> = World>,
+            T::Error: Into<BoxError>,
+            World: Sync,
+        {
+            Self {
+                name: std::any::type_name::<T>(),
+                check: |world| {
+                    Box::pin(async move {
+                        resolve_async::<T>(world)
+                            .await
+                            .map(drop)
+                            .map_err(Into::into)
+                    })
+                },
+            }
+        }
+
+        pub fn name(&self) -> &'static str {
+            self.name
+        }
+    }
+
+    impl<World> DIContainer<World> {
+        /// Async variant of [`DIContainer::health_check`], the checks run one after the other
+        ///
+        /// # Usage
+        /// ```
+        /// # use je_di::{DIContainer, FromAsyncWorld, async_trait, health_check};
+        /// # struct World;
+        /// # struct Database;
+        /// # #[async_trait]
+        /// # impl FromAsyncWorld for Database {
+        /// #     type World<'a> = World;
+        /// #     type Error = String;
+        /// #     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        /// #         Ok(Self)
+        /// #     }
+        /// # }
+        /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+        /// let container = DIContainer::new(World);
+        ///
+        /// let report = health_check!(async container; Database).await;
+        /// assert!(report.is_healthy());
+        /// # });
+        /// ```
+        pub async fn health_check_async(&self, checks: &[AsyncHealthCheck<World>]) -> HealthReport {
+            let mut report = HealthReport::default();
+            for check in checks {
+                report.record(check.name, (check.check)(&self.world).await);
+            }
+            report
+        }
+    }
+}
+
+/// # Runs the health check of a list of root types
+///
+/// `health_check!(container; A, B)` evaluates to the [`HealthReport`],
+/// `health_check!(async container; A, B)` to its future
+#[macro_export]
+macro_rules! health_check {
+    (async $container:expr; $($ty:ty),+ $(,)?) => {
+        $container.health_check_async(&[$($crate::health::AsyncHealthCheck::of::<$ty>()),+])
+    };
+
+    ($container:expr; $($ty:ty),+ $(,)?) => {
+        $container.health_check(&[$($crate::health::HealthCheck::of::<$ty>()),+])
+    };
+}
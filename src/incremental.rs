@@ -0,0 +1,315 @@
+//! # Incremental resolution
+//!
+//! [`IncrementalContainer`] memoizes extractions and only resolves a type again when a World
+//! input it read changed since, e.g. the passes of a build tool reusing the results of unchanged
+//! inputs.
+//!
+//! # Dependency tracking
+//!
+//! The tracked inputs are the World fields wrapped in [`Input<T>`]: every [`Input::get`] made
+//! while an extraction resolves is recorded along with the revision of the input, and
+//! [`Input::set`] bumps the revision. Dropping an input bumps it too: an input replaced by a new
+//! one, alone or with the whole World, invalidates the types which read the old one.
+//!
+//! - the granularity is the [`Input`]: reading any part of an input depends on all of it, World
+//!   fields not wrapped in an [`Input`] are not tracked and assumed to never change
+//! - the extracted type is memoized, not its dependencies: the inputs read by the whole graph
+//!   below it are attributed to it, and an invalidated type resolves its graph again
+//! - memoized values are checked lazily: [`IncrementalContainer::update_world`] only bumps
+//!   revisions, the affected types resolve again on their next extraction
+//!
+//! Reads are recorded on the current thread, a dependency reading inputs from another thread is
+//! not tracked. A resolution extracting from an [`IncrementalContainer`] depends on the inputs
+//! read by the extracted type, whether it is resolved or memoized: a memoized value brings the
+//! reads recorded when it was resolved.
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromWorld, incremental::{IncrementalContainer, Input}};
+//! use std::sync::Arc;
+//!
+//! struct World {
+//!     source: Input<String>,
+//!     flags: Input<Vec<String>>,
+//! }
+//!
+//! struct Parsed(usize);
+//!
+//! impl FromWorld for Parsed {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.source.get().split_whitespace().count()))
+//!     }
+//! }
+//!
+//! let mut container = IncrementalContainer::new(World {
+//!     source: Input::new("fn main".to_string()),
+//!     flags: Input::new(vec![]),
+//! });
+//!
+//! let first = container.extract::<Parsed>().unwrap();
+//! assert_eq!(first.0, 2);
+//!
+//! container.update_world(|world| world.flags.set(vec!["-O".to_string()]));
+//! assert!(Arc::ptr_eq(&first, &container.extract::<Parsed>().unwrap()));
+//!
+//! container.update_world(|world| world.source.set("fn main() {}".to_string()));
+//! assert_eq!(container.extract::<Parsed>().unwrap().0, 3);
+//!
+//! container.update_world(|world| world.source = Input::new("let x = 1;".to_string()));
+//! assert_eq!(container.extract::<Parsed>().unwrap().0, 4);
+//!
+//! container.update_world(|world| {
+//!     *world = World {
+//!         source: Input::new("x".to_string()),
+//!         flags: Input::new(vec![]),
+//!     }
+//! });
+//! assert_eq!(container.extract::<Parsed>().unwrap().0, 1);
+//! ```
+//!
+//! Extractions nested in a resolution, here from the container of another World, attribute
+//! their reads to the type being resolved, memoized or not:
+//!
+//! ```
+//! use je_di::{FromWorld, incremental::{IncrementalContainer, Input}};
+//!
+//! struct Settings {
+//!     level: Input<u8>,
+//! }
+//!
+//! struct Level(u8);
+//!
+//! impl FromWorld for Level {
+//!     type World<'a> = Settings;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(*world.level.get()))
+//!     }
+//! }
+//!
+//! struct World {
+//!     settings: IncrementalContainer<Settings>,
+//! }
+//!
+//! struct Banner(String);
+//!
+//! impl FromWorld for Banner {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         let level = world.settings.extract::<Level>()?;
+//!         Ok(Self(format!("level {}", level.0)))
+//!     }
+//! }
+//!
+//! let settings = IncrementalContainer::new(Settings { level: Input::new(1) });
+//! // memoizes `Level` before `Banner` is resolved
+//! settings.extract::<Level>().unwrap();
+//!
+//! let mut container = IncrementalContainer::new(World { settings });
+//! assert_eq!(container.extract::<Banner>().unwrap().0, "level 1");
+//!
+//! container.update_world(|world| world.settings.update_world(|settings| settings.level.set(2)));
+//! assert_eq!(container.extract::<Banner>().unwrap().0, "level 2");
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+thread_local! {
+    static READS: RefCell<Option<Vec<Read>>> = const { RefCell::new(None) };
+}
+
+/// Input read during a resolution, with the revision it was read at
+#[derive(Clone)]
+struct Read {
+    revision: Arc<AtomicU64>,
+    seen: u64,
+}
+
+impl Read {
+    fn is_current(&self) -> bool {
+        self.revision.load(Ordering::Acquire) == self.seen
+    }
+}
+
+/// # World field tracked by an [`IncrementalContainer`]
+pub struct Input<T> {
+    value: T,
+    revision: Arc<AtomicU64>,
+}
+
+impl<T> Input<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            revision: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Reads the input, recording the read when called during an incremental extraction
+    pub fn get(&self) -> &T {
+        track(&[Read {
+            revision: self.revision.clone(),
+            seen: self.revision.load(Ordering::Acquire),
+        }]);
+
+        &self.value
+    }
+
+    /// Replaces the input, invalidating every memoized type which read it
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.revision.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Drop for Input<T> {
+    fn drop(&mut self) {
+        // the memos still hold the revision, a replaced input must not look unchanged to them
+        self.revision.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+struct Memo {
+    value: Arc<dyn Any + Send + Sync>,
+    reads: Vec<Read>,
+}
+
+type Memos = HashMap<TypeId, Memo>;
+
+/// # Container memoizing extractions until the inputs they read change
+pub struct IncrementalContainer<World> {
+    world: World,
+    memos: Mutex<Memos>,
+}
+
+impl<World> IncrementalContainer<World> {
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            memos: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Gives mutable access to the World, changed [`Input`]s invalidate the types which read them
+    pub fn update_world<R>(&mut self, update: impl FnOnce(&mut World) -> R) -> R {
+        update(&mut self.world)
+    }
+
+    /// Returns the memoized `T` if none of the inputs it read changed, resolves it otherwise
+    pub fn extract<T>(&self) -> Result<Arc<T>, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + Send + Sync + 'static,
+    {
+        if let Some(value) = self.memoized::<T>() {
+            return Ok(value);
+        }
+
+        let (value, reads) = record(|| resolve::<T>(&self.world));
+        let value = Arc::new(value?);
+
+        self.lock().insert(
+            TypeId::of::<T>(),
+            Memo {
+                value: value.clone(),
+                reads,
+            },
+        );
+
+        Ok(value)
+    }
+
+    /// Drops every memoized value
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn memoized<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        let memos = self.lock();
+        let memo = memos.get(&TypeId::of::<T>())?;
+
+        if !memo.reads.iter().all(Read::is_current) {
+            return None;
+        }
+
+        let value = memo.value.clone().downcast::<T>().ok()?;
+        track(&memo.reads);
+
+        Some(value)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Memos> {
+        self.memos.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Records `reads` into the recording of the current thread, if any
+fn track(reads: &[Read]) {
+    READS.with(|recording| {
+        if let Some(recording) = recording.borrow_mut().as_mut() {
+            recording.extend_from_slice(reads);
+        }
+    });
+}
+
+/// Recording of the reads of a resolution, restores the enclosing recording when dropped, even
+/// if the resolution panics
+struct Recording {
+    outer: Option<Vec<Read>>,
+}
+
+impl Recording {
+    fn start() -> Self {
+        Self {
+            outer: READS.with(|reads| reads.borrow_mut().replace(Vec::new())),
+        }
+    }
+
+    /// Stops the recording, returning its reads, also merged into the enclosing recording
+    fn finish(self) -> Vec<Read> {
+        let reads = READS
+            .with(|reads| reads.borrow_mut().take())
+            .unwrap_or_default();
+        drop(self);
+        track(&reads);
+
+        reads
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let outer = self.outer.take();
+        READS.with(|reads| *reads.borrow_mut() = outer);
+    }
+}
+
+/// Runs `f`, returning the inputs read by it, nested recordings are merged into the outer one
+fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<Read>) {
+    let recording = Recording::start();
+    let result = f();
+
+    (result, recording.finish())
+}
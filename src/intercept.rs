@@ -0,0 +1,220 @@
+//! # Interceptors
+//!
+//! [`DIContainer::intercept`] registers a function consulted before `T` is resolved: when it
+//! returns `Some`, the value is extracted as is and `from_world` is never called. Caching layers,
+//! test doubles and kill switches can all be expressed as interceptors.
+//!
+//! # Ordering
+//!
+//! The interceptors of a type are consulted from the last registered to the first, the first one
+//! returning `Some` wins: the last registered interceptor has the final word. When every
+//! interceptor returns `None`, `T` is resolved normally.
+//!
+//! Like decorators, interceptors apply to the extracted type, not to the graph below it.
+//!
+//! # Async
+//!
+//! [`InterceptedContainer::intercept_async`] registers an interceptor returning a future, only
+//! consulted by [`InterceptedContainer::extract_async`]. Async extractions consult the sync and
+//! async interceptors of the type together, in the same last-registered-first order.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//!
+//! struct World {
+//!     payments_enabled: bool,
+//! }
+//!
+//! struct Payments(&'static str);
+//!
+//! impl FromWorld for Payments {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("stripe"))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { payments_enabled: false })
+//!     .intercept(|world: &World| (!world.payments_enabled).then_some(Payments("disabled")));
+//!
+//! assert_eq!(container.extract::<Payments>().unwrap().0, "disabled");
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+#[cfg(feature = "async")]
+type InterceptFuture<'w, T> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Option<T>> + Send + 'w>>;
+
+type SyncInterceptor<World, T> = Box<dyn Fn(&World) -> Option<T> + Send + Sync>;
+
+#[cfg(feature = "async")]
+type AsyncInterceptor<World, T> =
+    Box<dyn for<'w> Fn(&'w World) -> InterceptFuture<'w, T> + Send + Sync>;
+
+enum Interceptor<World, T> {
+    Sync(SyncInterceptor<World, T>),
+    #[cfg(feature = "async")]
+    Async(AsyncInterceptor<World, T>),
+}
+
+/// # Container consulting registered interceptors before resolving
+pub struct InterceptedContainer<World> {
+    container: DIContainer<World>,
+    interceptors: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl<World: 'static> InterceptedContainer<World> {
+    /// The underlying container, extractions through it are not intercepted
+    pub fn container(&self) -> &DIContainer<World> {
+        &self.container
+    }
+
+    /// Registers an interceptor of `T`, consulted before the ones already registered
+    pub fn intercept<T: 'static>(
+        self,
+        interceptor: impl Fn(&World) -> Option<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register(Interceptor::Sync(Box::new(interceptor)))
+    }
+
+    pub fn extract<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + 'static,
+    {
+        let intercepted = self
+            .interceptors::<T>()
+            .iter()
+            .rev()
+            .find_map(|interceptor| match interceptor {
+                Interceptor::Sync(interceptor) => interceptor(&self.container.world),
+                #[cfg(feature = "async")]
+                Interceptor::Async(_) => None,
+            });
+
+        match intercepted {
+            Some(value) => Ok(value),
+            None => resolve::<T>(&self.container.world),
+        }
+    }
+
+    fn register<T: 'static>(mut self, interceptor: Interceptor<World, T>) -> Self {
+        self.interceptors
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<Interceptor<World, T>>::new()))
+            .downcast_mut::<Vec<Interceptor<World, T>>>()
+            .unwrap_or_else(|| unreachable!("interceptors are keyed by their TypeId"))
+            .push(interceptor);
+        self
+    }
+
+    fn interceptors<T: 'static>(&self) -> &[Interceptor<World, T>] {
+        self.interceptors
+            .get(&TypeId::of::<T>())
+            .and_then(|interceptors| interceptors.downcast_ref::<Vec<Interceptor<World, T>>>())
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World: 'static> InterceptedContainer<World> {
+    /// Registers an async interceptor of `T`, only consulted by async extractions
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromAsyncWorld, async_trait};
+    /// use std::sync::Arc;
+    /// use tokio::sync::Mutex;
+    ///
+    /// struct World {
+    ///     cached: Arc<Mutex<Option<String>>>,
+    /// }
+    ///
+    /// struct Session(String);
+    ///
+    /// #[async_trait]
+    /// impl FromAsyncWorld for Session {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+    ///         Ok(Self("fresh".to_string()))
+    ///     }
+    /// }
+    ///
+    /// let cached = Arc::new(Mutex::new(None));
+    /// let container = DIContainer::new(World { cached: cached.clone() }).intercept_async(
+    ///     |world: &World| {
+    ///         Box::pin(async move { world.cached.lock().await.clone().map(Session) })
+    ///     },
+    /// );
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// // nothing cached, `Session` is resolved
+    /// assert_eq!(container.extract_async::<Session>().await.unwrap().0, "fresh");
+    ///
+    /// *cached.lock().await = Some("cached".to_string());
+    /// assert_eq!(container.extract_async::<Session>().await.unwrap().0, "cached");
+    /// # });
+    /// ```
+    pub fn intercept_async<T: 'static>(
+        self,
+        interceptor: impl for<'w> Fn(&'w World) -> InterceptFuture<'w, T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register(Interceptor::Async(Box::new(interceptor)))
+    }
+
+    pub async fn extract_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World>,
+    {
+        for interceptor in self.interceptors::<T>().iter().rev() {
+            let intercepted = match interceptor {
+                Interceptor::Sync(interceptor) => interceptor(&self.container.world),
+                Interceptor::Async(interceptor) => interceptor(&self.container.world).await,
+            };
+
+            if let Some(value) = intercepted {
+                return Ok(value);
+            }
+        }
+
+        crate::resolve::resolve_async::<T>(&self.container.world).await
+    }
+}
+
+impl<World: 'static> DIContainer<World> {
+    /// Registers an interceptor of `T`, see [`InterceptedContainer::intercept`]
+    pub fn intercept<T: 'static>(
+        self,
+        interceptor: impl Fn(&World) -> Option<T> + Send + Sync + 'static,
+    ) -> InterceptedContainer<World> {
+        InterceptedContainer {
+            container: self,
+            interceptors: HashMap::new(),
+        }
+        .intercept(interceptor)
+    }
+
+    /// Registers an async interceptor of `T`, see [`InterceptedContainer::intercept_async`]
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub fn intercept_async<T: 'static>(
+        self,
+        interceptor: impl for<'w> Fn(&'w World) -> InterceptFuture<'w, T> + Send + Sync + 'static,
+    ) -> InterceptedContainer<World> {
+        InterceptedContainer {
+            container: self,
+            interceptors: HashMap::new(),
+        }
+        .intercept_async(interceptor)
+    }
+}
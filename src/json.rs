@@ -0,0 +1,101 @@
+//! # serde_json World
+//!
+//! For dynamic hosts whose World is a [`serde_json::Value`], dependencies are deserialized from a
+//! sub-path of the World.
+//!
+//! Paths are dot separated object keys, numeric segments index arrays (`"services.0.url"`), the
+//! empty path is the World itself.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, json::{FromJsonPath, JsonPath}};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct DbConfig {
+//!     url: String,
+//! }
+//!
+//! impl FromJsonPath for DbConfig {
+//!     const PATH: &'static str = "config.db";
+//! }
+//!
+//! let world = serde_json::json!({ "config": { "db": { "url": "postgres://localhost" } } });
+//! let container = DIContainer::new(world);
+//!
+//! let JsonPath(config): JsonPath<DbConfig> = container.extract().unwrap();
+//! assert_eq!(config.url, "postgres://localhost");
+//!
+//! let url: String = container.extract_path("config.db.url").unwrap();
+//! assert_eq!(url, "postgres://localhost");
+//!
+//! let error = container.extract_path::<String>("config.cache").unwrap_err();
+//! assert_eq!(error.path, "config.cache");
+//! ```
+
+use crate::{DIContainer, FromWorld};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// # Error resolving a dependency from a JSON World
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonResolveError {
+    /// path that failed to resolve
+    pub path: String,
+    /// serde error message, or the reason the path is missing
+    pub message: String,
+}
+
+impl std::fmt::Display for JsonResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to resolve `{}`: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for JsonResolveError {}
+
+/// # Deserializable dependency located at a fixed path of the World
+pub trait FromJsonPath: DeserializeOwned {
+    const PATH: &'static str;
+}
+
+/// # Dependency deserialized from [`FromJsonPath::PATH`]
+pub struct JsonPath<T>(pub T);
+
+impl<T> FromWorld for JsonPath<T>
+where
+    T: FromJsonPath,
+{
+    type World<'a> = Value;
+    type Error = JsonResolveError;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        resolve_path(world, T::PATH).map(JsonPath)
+    }
+}
+
+/// Deserializes `T` from the value at `path`
+pub fn resolve_path<T: DeserializeOwned>(world: &Value, path: &str) -> Result<T, JsonResolveError> {
+    let error = |message: String| JsonResolveError {
+        path: path.to_string(),
+        message,
+    };
+
+    let mut value = world;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let next = match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            value => value.get(segment),
+        };
+        value = next.ok_or_else(|| error(format!("no value at segment `{segment}`")))?;
+    }
+
+    T::deserialize(value).map_err(|e| error(e.to_string()))
+}
+
+impl DIContainer<Value> {
+    /// Deserializes `T` from the value at `path` of the World
+    pub fn extract_path<T: DeserializeOwned>(&self, path: &str) -> Result<T, JsonResolveError> {
+        resolve_path(&self.world, path)
+    }
+}
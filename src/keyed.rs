@@ -0,0 +1,263 @@
+//! # Keyed registries
+//!
+//! Routing tables and per-key service registries hold one instance of a dependency per key, e.g.
+//! one client per region. [`DIContainer::extract_map_by`] resolves `T` once for each key, with
+//! the key as scope, and collects the instances into a [`HashMap`] keyed by `key_of`:
+//!
+//! ```ignore
+//! let clients: HashMap<Region, RegionClient> =
+//!     container.extract_map_by(&regions, |client| client.region, Duplicates::Error)?;
+//! ```
+//!
+//! `T` is declared over the [`Scoped`] World pairing the container World with the key. `key_of`
+//! computes the key of the map from the resolved instance, usually the key it was resolved for
+//! but possibly normalized, e.g. lowercased hosts.
+//!
+//! # Ordering
+//!
+//! The keys are resolved in order, the first failed resolution fails the extraction with
+//! [`MapByError::Resolve`] without resolving the next keys.
+//!
+//! With the `batch` feature [`DIContainer::extract_map_by_async`] resolves every key
+//! concurrently on the current task, the first failure drops the resolutions still in flight.
+//! The instances are then inserted in the order of the keys, not in completion order: both
+//! variants build the same map.
+//!
+//! # Collisions
+//!
+//! Two instances with the same key of the map, duplicated keys or keys normalized to the same
+//! value, collide. [`Duplicates`] selects the behavior:
+//!
+//! - [`Duplicates::LastWins`] keeps the instance of the last of the keys, in the order of the
+//!   slice, the previous ones are dropped
+//! - [`Duplicates::Error`] fails with [`MapByError::Duplicate`] holding the colliding key
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromWorld,
+//!     keyed::{Duplicates, MapByError},
+//!     scope::Scoped,
+//! };
+//! use std::collections::HashMap;
+//!
+//! struct World {
+//!     domain: &'static str,
+//! }
+//!
+//! struct RegionClient {
+//!     region: &'static str,
+//!     endpoint: String,
+//! }
+//!
+//! impl FromWorld for RegionClient {
+//!     type World<'a> = Scoped<'a, World, &'static str>;
+//!     type Error = String;
+//!
+//!     fn from_world((world, region): &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { region, endpoint: format!("https://{region}.{}", world.domain) })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { domain: "example.com" });
+//! let key_of = |client: &RegionClient| client.region;
+//!
+//! let clients: HashMap<&str, RegionClient> = container
+//!     .extract_map_by(&["eu-west", "us-east"], key_of, Duplicates::Error)
+//!     .unwrap();
+//! assert_eq!(clients["eu-west"].endpoint, "https://eu-west.example.com");
+//!
+//! let clients = container
+//!     .extract_map_by(&["eu-west", "eu-west"], key_of, Duplicates::LastWins)
+//!     .unwrap();
+//! assert_eq!(clients.len(), 1);
+//!
+//! let error = container
+//!     .extract_map_by(&["eu-west", "eu-west"], key_of, Duplicates::Error)
+//!     .err()
+//!     .unwrap();
+//! assert_eq!(error, MapByError::Duplicate("eu-west"));
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve, scope::Scoped};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fmt,
+    hash::Hash,
+};
+
+/// # Behavior on colliding keys of the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    /// The instance of the last key is kept
+    LastWins,
+    /// The extraction fails with [`MapByError::Duplicate`]
+    Error,
+}
+
+/// # Error of a keyed extraction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapByError<K, E> {
+    Resolve(E),
+    /// Two instances have this key of the map
+    Duplicate(K),
+}
+
+impl<K: fmt::Debug, E: fmt::Display> fmt::Display for MapByError<K, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapByError::Resolve(error) => error.fmt(f),
+            MapByError::Duplicate(key) => write!(f, "duplicate key {key:?}"),
+        }
+    }
+}
+
+impl<K: fmt::Debug, E: std::error::Error + 'static> std::error::Error for MapByError<K, E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MapByError::Resolve(error) => Some(error),
+            MapByError::Duplicate(_) => None,
+        }
+    }
+}
+
+fn insert<K: Hash + Eq, T, E>(
+    map: &mut HashMap<K, T>,
+    key: K,
+    value: T,
+    duplicates: Duplicates,
+) -> Result<(), MapByError<K, E>> {
+    match (map.entry(key), duplicates) {
+        (Entry::Occupied(entry), Duplicates::Error) => {
+            Err(MapByError::Duplicate(entry.remove_entry().0))
+        }
+        (Entry::Occupied(mut entry), Duplicates::LastWins) => {
+            entry.insert(value);
+            Ok(())
+        }
+        (Entry::Vacant(entry), _) => {
+            entry.insert(value);
+            Ok(())
+        }
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T` for each of `keys`, keyed by `key_of`
+    pub fn extract_map_by<T, K>(
+        &self,
+        keys: &[K],
+        key_of: impl Fn(&T) -> K,
+        duplicates: Duplicates,
+    ) -> Result<HashMap<K, T>, MapByError<K, T::Error>>
+    where
+        T: for<'a> FromWorld<World<'a> = Scoped<'a, World, K>>,
+        K: Hash + Eq,
+    {
+        let mut map = HashMap::with_capacity(keys.len());
+        for key in keys {
+            let value = resolve::<T>(&(&self.world, key)).map_err(MapByError::Resolve)?;
+            insert(&mut map, key_of(&value), value, duplicates)?;
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "batch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "batch")))]
+impl<World> DIContainer<World> {
+    /// Async variant of [`extract_map_by`](DIContainer::extract_map_by), resolving the keys
+    /// concurrently
+    ///
+    /// Behind the `batch` feature rather than `async`: joining the resolutions takes the
+    /// `futures` dependency, which `batch` brings for
+    /// [`extract_each_async`](crate::batch::extract_each_async) and `async` alone doesn't.
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{
+    ///     DIContainer, FromAsyncWorld, async_trait,
+    ///     keyed::{Duplicates, MapByError},
+    ///     scope::Scoped,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// struct World {
+    ///     domain: &'static str,
+    /// }
+    ///
+    /// struct RegionClient {
+    ///     region: &'static str,
+    ///     endpoint: String,
+    /// }
+    ///
+    /// #[async_trait]
+    /// impl FromAsyncWorld for RegionClient {
+    ///     type World<'a> = Scoped<'a, World, &'static str>;
+    ///     type Error = String;
+    ///
+    ///     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+    ///         let &(world, &region) = world;
+    ///         // the first key completes last
+    ///         let millis = if region == "eu-west" { 20 } else { 1 };
+    ///         tokio::time::sleep(Duration::from_millis(millis)).await;
+    ///         Ok(Self { region, endpoint: format!("https://{region}.{}", world.domain) })
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let container = DIContainer::new(World { domain: "example.com" });
+    /// let key_of = |client: &RegionClient| client.region;
+    ///
+    /// let clients = container
+    ///     .extract_map_by_async(&["eu-west", "us-east"], key_of, Duplicates::Error)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(clients["eu-west"].endpoint, "https://eu-west.example.com");
+    /// assert_eq!(clients["us-east"].endpoint, "https://us-east.example.com");
+    ///
+    /// let clients = container
+    ///     .extract_map_by_async(&["eu-west", "us-east", "eu-west"], key_of, Duplicates::LastWins)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(clients.len(), 2);
+    ///
+    /// let error = container
+    ///     .extract_map_by_async(&["eu-west", "us-east", "eu-west"], key_of, Duplicates::Error)
+    ///     .await
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(error, MapByError::Duplicate("eu-west"));
+    /// # });
+    /// ```
+    pub async fn extract_map_by_async<T, K>(
+        &self,
+        keys: &[K],
+        key_of: impl Fn(&T) -> K,
+        duplicates: Duplicates,
+    ) -> Result<HashMap<K, T>, MapByError<K, T::Error>>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = Scoped<'a, World, K>>,
+        K: Hash + Eq,
+    {
+        let worlds = keys
+            .iter()
+            .map(|key| (&self.world, key))
+            .collect::<Vec<_>>();
+        let values = futures::future::try_join_all(
+            worlds
+                .iter()
+                .map(|world| crate::resolve::resolve_async::<T>(world)),
+        )
+        .await
+        .map_err(MapByError::Resolve)?;
+
+        let mut map = HashMap::with_capacity(keys.len());
+        for value in values {
+            insert(&mut map, key_of(&value), value, duplicates)?;
+        }
+
+        Ok(map)
+    }
+}
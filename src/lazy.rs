@@ -0,0 +1,133 @@
+//! # Lazy World fields
+//!
+//! [`LazyField<T>`] is a World field built on first use by the initializer stored alongside it,
+//! expensive fields (a parsed catalog, a compiled regex set) are only built if a dependency needs
+//! them. [`Lazy<T, World>`] resolves by initializing the field if needed and sharing it.
+//!
+//! The World exposes the field through [`AsRef<LazyField<T>>`].
+//!
+//! # Thread safety
+//!
+//! The field is backed by a [`OnceLock`]: the initializer runs at most once, concurrent
+//! resolutions block until it completes and all share the same value. If the initializer panics
+//! the field stays uninitialized and the next resolution runs it again.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, lazy::{Lazy, LazyField}};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static BUILDS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! struct Catalog(Vec<&'static str>);
+//!
+//! struct World {
+//!     catalog: LazyField<Catalog>,
+//! }
+//!
+//! impl AsRef<LazyField<Catalog>> for World {
+//!     fn as_ref(&self) -> &LazyField<Catalog> {
+//!         &self.catalog
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     catalog: LazyField::new(|| {
+//!         BUILDS.fetch_add(1, Ordering::SeqCst);
+//!         Catalog(vec!["book", "pen"])
+//!     }),
+//! });
+//! assert_eq!(BUILDS.load(Ordering::SeqCst), 0);
+//!
+//! let first: Lazy<Catalog, World> = container.extract().unwrap();
+//! let second: Lazy<Catalog, World> = container.extract().unwrap();
+//! assert_eq!(first.0, ["book", "pen"]);
+//! assert_eq!(BUILDS.load(Ordering::SeqCst), 1);
+//! # drop(second);
+//! ```
+
+use crate::FromWorld;
+use std::{
+    convert::Infallible,
+    fmt,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Arc, OnceLock},
+};
+
+/// # World field built on first use
+pub struct LazyField<T> {
+    cell: OnceLock<Arc<T>>,
+    init: Box<dyn Fn() -> T + Send + Sync>,
+}
+
+impl<T> LazyField<T> {
+    pub fn new(init: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            cell: OnceLock::new(),
+            init: Box::new(init),
+        }
+    }
+
+    /// Returns the value, running the initializer if it was never built
+    pub fn get_or_init(&self) -> &Arc<T> {
+        self.cell.get_or_init(|| Arc::new((self.init)()))
+    }
+
+    /// Returns the value if it was already built
+    pub fn get(&self) -> Option<&Arc<T>> {
+        self.cell.get()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LazyField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyField")
+            .field("value", &self.cell.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// # Shared [`LazyField`] of the World
+pub struct Lazy<T, World, Error = Infallible> {
+    value: Arc<T>,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> Lazy<T, World, Error> {
+    pub fn into_arc(self) -> Arc<T> {
+        self.value
+    }
+}
+
+impl<T, World, Error> Clone for Lazy<T, World, Error> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, World, Error> Deref for Lazy<T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, W, E> FromWorld for Lazy<T, W, E>
+where
+    W: AsRef<LazyField<T>>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: world.as_ref().get_or_init().clone(),
+            _marker: PhantomData,
+        })
+    }
+}
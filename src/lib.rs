@@ -1,10 +1,26 @@
 #![cfg_attr(docsrs, doc = include_str!("../README.md"))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+extern crate alloc;
+
+#[cfg(feature = "allocator_api")]
+#[cfg_attr(docsrs, doc(cfg(feature = "allocator_api")))]
+pub mod allocator;
 
 #[cfg(feature = "axum")]
 #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
 pub mod axum;
 
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod bridge;
+
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub mod async_dependency;
@@ -17,6 +33,212 @@ pub use async_dependency::*;
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use async_trait::async_trait;
 
+#[cfg(feature = "channel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "channel")))]
+pub mod channel;
+
+#[cfg(feature = "clap")]
+#[cfg_attr(docsrs, doc(cfg(feature = "clap")))]
+pub mod clap;
+
+#[cfg(feature = "config")]
+#[cfg_attr(docsrs, doc(cfg(feature = "config")))]
+pub mod config;
+
+#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bb8", feature = "deadpool"))))]
+pub mod connection;
+
+#[cfg(feature = "graphql")]
+#[cfg_attr(docsrs, doc(cfg(feature = "graphql")))]
+pub mod graphql;
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod json;
+
+#[cfg(feature = "local")]
+#[cfg_attr(docsrs, doc(cfg(feature = "local")))]
+pub mod local;
+
+#[cfg(feature = "observe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+pub mod observe;
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub mod parallel;
+
+#[cfg(feature = "plugins")]
+#[cfg_attr(docsrs, doc(cfg(feature = "plugins")))]
+pub mod plugin;
+
+#[cfg(feature = "race")]
+#[cfg_attr(docsrs, doc(cfg(feature = "race")))]
+pub mod race;
+
+#[cfg(feature = "axum-errors")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum-errors")))]
+pub mod rejection;
+
+#[cfg(feature = "resilient")]
+#[cfg_attr(docsrs, doc(cfg(feature = "resilient")))]
+pub mod resilient;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod runtime;
+
+#[cfg(feature = "semaphore")]
+#[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+pub mod semaphore;
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod snapshot;
+
+#[cfg(feature = "timeout")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timeout")))]
+pub mod timeout;
+
+#[cfg(feature = "tonic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tonic")))]
+pub mod tonic;
+
+#[cfg(feature = "trace-export")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace-export")))]
+pub mod trace;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub mod trace_context;
+
+#[cfg(feature = "watch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "watch")))]
+pub mod watch;
+
+#[cfg(feature = "watch-file")]
+#[cfg_attr(docsrs, doc(cfg(feature = "watch-file")))]
+pub mod watch_file;
+
+pub mod and_then;
+pub mod assert;
+pub mod batch;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod boxed;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod builder;
+pub mod bundle;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod cache;
+pub mod callback;
+pub mod capability;
+pub mod chain;
+pub mod collection;
+pub mod dependency;
+pub mod dispatch;
+pub mod downcast;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod decorate;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod env;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod expiring;
+pub mod feature_gate;
+pub mod foreign;
+pub mod graph;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod guard;
+pub mod handle;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod health;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod incremental;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod intercept;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod keyed;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod lazy;
+pub mod merge;
+pub mod mock;
+pub mod optional;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod overrides;
+pub mod page;
+pub mod phase;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod parsed;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod pool;
+pub mod projection;
+pub mod provider;
+mod resolve;
+pub mod resolver;
+pub mod scope;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod secret;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod singleton;
+pub mod tenant;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod type_map;
+pub mod validate;
+pub mod version;
+pub mod world_builder;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use builder::DIContainerBuilder;
+pub use graph::{DependencyGraph, DescribeDependencies};
+pub use optional::{FromOptionalDependency, Optional};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use optional::FromOptionalAsyncDependency;
+pub use provider::{Provided, Provider};
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use je_di_macros::{
+    Capabilities, DependencyError, Merge, SubWorld, WorldBuilder, di_impl, world,
+};
+
+#[cfg(all(feature = "derive", feature = "axum"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "derive", feature = "axum"))))]
+pub use je_di_macros::AxumDependency;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "axum")]
+    pub use axum;
+    #[cfg(feature = "plugins")]
+    pub use inventory;
+
+    pub use alloc::boxed::Box;
+
+    pub use crate::resolve::resolve;
+    #[cfg(feature = "async")]
+    pub use crate::resolve::resolve_async;
+}
+
 /// # Entry point to je-di
 ///
 /// Describes a struct that can be constructed from a given World
@@ -40,13 +262,67 @@ pub use async_trait::async_trait;
 ///     }
 /// }
 /// ```
+///
+/// # Tuples
+///
+/// Tuples of up to 9 dependencies sharing the same World and Error implement [`FromWorld`].
+/// The elements are resolved in declaration order, stopping at the first error: side effects of
+/// the constructors (logging, counters) happen in the order the tuple is written. This is
+/// guaranteed for the sync and async implementations, concurrent resolution is always opt-in.
+///
+/// ```
+/// use je_di::{DIContainer, FromWorld};
+/// use std::cell::RefCell;
+///
+/// struct World {
+///     log: RefCell<Vec<&'static str>>,
+/// }
+///
+/// macro_rules! logged {
+///     ($($name:ident),+) => {
+///         $(
+///             struct $name;
+///
+///             impl FromWorld for $name {
+///                 type World<'a> = World;
+///                 type Error = ();
+///
+///                 fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+///                     world.log.borrow_mut().push(stringify!($name));
+///                     Ok(Self)
+///                 }
+///             }
+///         )+
+///     };
+/// }
+///
+/// logged!(A, B, C);
+///
+/// let container = DIContainer::new(World { log: RefCell::default() });
+///
+/// let _: (C, A, B) = container.extract().unwrap();
+/// let _: (A, (B, C)) = container.extract().unwrap();
+///
+/// assert_eq!(
+///     *container.extract::<Log>().unwrap().0,
+///     ["C", "A", "B", "A", "B", "C"]
+/// );
+/// # struct Log(Vec<&'static str>);
+/// # impl FromWorld for Log {
+/// #     type World<'a> = World;
+/// #     type Error = ();
+/// #     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+/// #         Ok(Self(world.log.borrow().clone()))
+/// #     }
+/// # }
+/// ```
 pub trait FromWorld {
     type World<'a>;
     type Error;
 
     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error>
     where
-        Self: std::marker::Sized;
+        Self: core::marker::Sized;
 }
 
 /// # Defines a dependency
@@ -54,6 +330,15 @@ pub trait FromWorld {
 /// Describes a struct that can be constructed from a world and a dependency that implements
 /// FromWorld for the same World
 ///
+/// # Error of the dependency
+///
+/// `Dependency` must fail with `Self::Error`, a dependency tree shares a single error type. The
+/// wrappers of the crate take their error as a type parameter for that reason, defaulting to the
+/// error they fail with, or to [`Infallible`](core::convert::Infallible) when they can't fail.
+/// Set it to the error of the tree when the wrapper is a `Dependency`, or to the error expected
+/// by the caller of [`BorrowFromWorld::borrow_from_world`] for the borrowing wrappers: it must
+/// convert from the error of the wrapper.
+///
 /// # Usage
 /// ```ignore
 /// use je_di::FromDependency;
@@ -84,7 +369,69 @@ pub trait FromDependency {
         dependency: &Self::Dependency,
     ) -> Result<Self, Self::Error>
     where
-        Self: std::marker::Sized;
+        Self: core::marker::Sized;
+}
+
+/// # Defines a dependency borrowing from its World
+///
+/// Unlike [`FromWorld`], `Self` may hold references living as long as `'a`, enabling zero-copy
+/// cursors and iterators. The World is a borrowed view passed by value, extracting through
+/// [`DIContainer::extract_borrowing`] requires `World = &'a ContainerWorld`, the extracted value
+/// then keeps the container borrowed.
+///
+/// # Usage
+/// ```
+/// use je_di::{BorrowFromWorld, DIContainer};
+///
+/// struct Connection {
+///     rows: Vec<String>,
+/// }
+///
+/// struct World {
+///     connection: Connection,
+/// }
+///
+/// struct RowIterator<'a> {
+///     connection: &'a Connection,
+///     position: usize,
+/// }
+///
+/// impl<'a> Iterator for RowIterator<'a> {
+///     type Item = &'a str;
+///
+///     fn next(&mut self) -> Option<Self::Item> {
+///         let row = self.connection.rows.get(self.position)?;
+///         self.position += 1;
+///         Some(row)
+///     }
+/// }
+///
+/// impl<'a> BorrowFromWorld<'a> for RowIterator<'a> {
+///     type World = &'a World;
+///     type Error = std::convert::Infallible;
+///
+///     fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+///         Ok(Self {
+///             connection: &world.connection,
+///             position: 0,
+///         })
+///     }
+/// }
+///
+/// let container = DIContainer::new(World {
+///     connection: Connection {
+///         rows: vec!["first".to_string(), "second".to_string()],
+///     },
+/// });
+///
+/// let rows: RowIterator = container.extract_borrowing().unwrap();
+/// assert_eq!(rows.collect::<Vec<_>>(), ["first", "second"]);
+/// ```
+pub trait BorrowFromWorld<'a>: Sized {
+    type World: 'a;
+    type Error;
+
+    fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error>;
 }
 
 impl<T> FromWorld for T
@@ -96,7 +443,7 @@ where
     type World<'a> = T::World<'a>;
 
     fn from_world(world: &Self::World<'_>) -> Result<Self, T::Error> {
-        let dependency = <T::Dependency as FromWorld>::from_world(world)?;
+        let dependency = resolve::resolve::<T::Dependency>(world)?;
 
         Self::from_dependency(world, &dependency)
     }
@@ -114,14 +461,41 @@ macro_rules! impl_tuple {
 
             fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
                 Ok((
-                    $first_name::from_world(world)?,
-                    $($name::from_world(world)?),+
+                    resolve::resolve::<$first_name>(world)?,
+                    $(resolve::resolve::<$name>(world)?),+
+                ))
+            }
+        }
+
+        impl<$first_name, $($name),*> FromWorldLocated for ($first_name, $($name),+)
+        where
+            $first_name: FromWorld,
+            $($name: for<'a> FromWorld<World<'a> = $first_name::World<'a>, Error = $first_name::Error>),*
+        {
+            fn from_world_located(
+                world: &Self::World<'_>,
+            ) -> Result<Self, Located<Self::Error>> {
+                Ok((
+                    resolve::resolve::<$first_name>(world)
+                        .map_err(|error| ($first_n, core::any::type_name::<$first_name>(), error))?,
+                    $(resolve::resolve::<$name>(world)
+                        .map_err(|error| ($n, core::any::type_name::<$name>(), error))?),+
                 ))
             }
         }
     };
 }
 
+/// Error of a tuple element: its zero-based index, its type name and its error
+pub type Located<E> = (usize, &'static str, E);
+
+/// # Tuple reporting which element failed to resolve
+///
+/// Implemented by the tuples implementing [`FromWorld`], see [`DIContainer::extract_located`]
+pub trait FromWorldLocated: FromWorld + Sized {
+    fn from_world_located(world: &Self::World<'_>) -> Result<Self, Located<Self::Error>>;
+}
+
 impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7, 8:Dep8);
 impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7);
 impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6);
@@ -131,10 +505,89 @@ impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3);
 impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2);
 impl_tuple!(0:Dep0, 1:Dep1);
 
+/// # Container extracting the dependencies of a World
+///
+/// With the `derive` feature, `#[world]` on the World struct names its container with a type
+/// alias and adds a `container(self)` method building it
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use je_di::{DIContainer, FromWorld, world};
+///
+/// #[world]
+/// pub struct AppWorld {
+///     database_url: &'static str,
+/// }
+///
+/// #[world(container = Workers, method = false)]
+/// struct WorkerWorld {
+///     threads: usize,
+/// }
+///
+/// struct DatabaseUrl(&'static str);
+///
+/// impl FromWorld for DatabaseUrl {
+///     type World<'a> = AppWorld;
+///     type Error = String;
+///
+///     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+///         Ok(Self(world.database_url))
+///     }
+/// }
+///
+/// let container: AppContainer = AppWorld { database_url: "postgres://localhost" }.container();
+/// assert_eq!(container.extract::<DatabaseUrl>().unwrap().0, "postgres://localhost");
+///
+/// // the alias is the container type
+/// let _: DIContainer<AppWorld> = container;
+/// let _: Workers = DIContainer::new(WorkerWorld { threads: 4 });
+/// # }
+/// ```
 pub struct DIContainer<World> {
     world: World,
 }
 
+impl<World: Default> Default for DIContainer<World> {
+    fn default() -> Self {
+        Self::new(World::default())
+    }
+}
+
+impl<World: Default> DIContainer<World> {
+    /// # Builds a container over the default World
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// #[derive(Default)]
+    /// struct World {
+    ///     retries: u32,
+    /// }
+    ///
+    /// struct Retries(u32);
+    ///
+    /// impl FromWorld for Retries {
+    ///     type World<'a> = World;
+    ///     type Error = std::convert::Infallible;
+    ///
+    ///     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Ok(Self(world.retries))
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::<World>::default_new();
+    /// assert_eq!(container.extract::<Retries>().unwrap().0, 0);
+    ///
+    /// let container: DIContainer<World> = DIContainer::default();
+    /// assert_eq!(container.extract::<Retries>().unwrap().0, 0);
+    /// ```
+    pub fn default_new() -> Self {
+        Self::default()
+    }
+}
+
 impl<World> DIContainer<World> {
     pub fn new(world: World) -> Self {
         Self { world }
@@ -143,7 +596,248 @@ impl<World> DIContainer<World> {
     pub fn extract<T: for<'a> FromWorld<World<'a> = World>>(
         &self,
     ) -> Result<T, <T as FromWorld>::Error> {
-        T::from_world(&self.world)
+        resolve::resolve::<T>(&self.world)
+    }
+
+    /// Extracts a `T` borrowing from the World, the container stays borrowed as long as `T` lives
+    pub fn extract_borrowing<'a, T: BorrowFromWorld<'a, World = &'a World>>(
+        &'a self,
+    ) -> Result<T, <T as BorrowFromWorld<'a>>::Error> {
+        T::borrow_from_world(&self.world)
+    }
+
+    /// # Extracts a tuple, locating the element that failed
+    ///
+    /// The error holds the zero-based index of the first element that failed, in declaration
+    /// order, its type name and its error
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// struct World;
+    ///
+    /// struct Database;
+    ///
+    /// impl FromWorld for Database {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Ok(Self)
+    ///     }
+    /// }
+    ///
+    /// struct Mailer;
+    ///
+    /// impl FromWorld for Mailer {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Err("missing SMTP host".to_string())
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::new(World);
+    ///
+    /// let Err((index, type_name, error)) = container.extract_located::<(Database, Mailer)>() else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(index, 1);
+    /// assert!(type_name.ends_with("Mailer"));
+    /// assert_eq!(error, "missing SMTP host");
+    /// ```
+    pub fn extract_located<T>(&self) -> Result<T, Located<<T as FromWorld>::Error>>
+    where
+        T: for<'a> FromWorldLocated<World<'a> = World>,
+    {
+        T::from_world_located(&self.world)
+    }
+
+    /// # Extracts `T` and feeds it to `f`
+    ///
+    /// The resolution error and the error of `f` are flattened into `E`, the error of `f`, which
+    /// must be constructible from the resolution error through [`From`]
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// struct World(u32);
+    ///
+    /// struct Doubler(u32);
+    ///
+    /// impl FromWorld for Doubler {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Ok(Self(world.0))
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::new(World(21));
+    /// let doubled = container.extract_and_then(|doubler: Doubler| Ok::<_, String>(doubler.0 * 2));
+    /// assert_eq!(doubled, Ok(42));
+    /// ```
+    pub fn extract_and_then<T, R, E, F>(&self, f: F) -> Result<R, E>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+        E: From<<T as FromWorld>::Error>,
+        F: FnOnce(T) -> Result<R, E>,
+    {
+        f(self.extract::<T>()?)
+    }
+
+    /// # Defers the extraction of `T` to a closure
+    ///
+    /// Nothing is resolved until the closure is called, a branch that doesn't need `T` drops the
+    /// closure and never builds it. Unlike [`Lazy<T>`](crate::lazy::Lazy), a World field built
+    /// once and shared, the closure isn't a dependency and shares nothing: it resolves a new `T`
+    /// when called.
+    ///
+    /// The closure captures a reference to the World, it borrows the container for as long as it
+    /// lives: it can't be returned past the container, moved to a `'static` task, nor held while
+    /// the container is borrowed mutably.
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// struct World;
+    ///
+    /// struct Mailer;
+    ///
+    /// impl FromWorld for Mailer {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Err("missing SMTP host".to_string())
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::new(World);
+    /// let mailer = container.extract_deferred::<Mailer>();
+    ///
+    /// let notify = false;
+    /// if notify {
+    ///     mailer().unwrap();
+    /// }
+    /// ```
+    pub fn extract_deferred<T>(
+        &self,
+    ) -> impl FnOnce() -> Result<T, <T as FromWorld>::Error> + '_
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        move || self.extract::<T>()
+    }
+
+    /// # Extracts `T` and stores it back into the World
+    ///
+    /// `setter` moves the value into a World field, later extractions read it from there instead
+    /// of building it again: staged initialization, wired by hand, without a
+    /// [`SingletonContainer`](crate::singleton::SingletonContainer).
+    ///
+    /// Storing into the World takes `&mut self`: the stages run before the container is shared,
+    /// usually at startup. The dependencies reading the stored field must be extracted after the
+    /// stage storing it, they would otherwise find it unset.
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// struct World {
+    ///     database_url: &'static str,
+    ///     pool: Option<Pool>,
+    /// }
+    ///
+    /// struct Pool(String);
+    ///
+    /// impl FromWorld for Pool {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Ok(Self(format!("pool of {}", world.database_url)))
+    ///     }
+    /// }
+    ///
+    /// struct Repository(String);
+    ///
+    /// impl FromWorld for Repository {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         let pool = world.pool.as_ref().ok_or("the pool stage didn't run")?;
+    ///         Ok(Self(pool.0.clone()))
+    ///     }
+    /// }
+    ///
+    /// let mut container = DIContainer::new(World { database_url: "postgres://db", pool: None });
+    /// assert!(container.extract::<Repository>().is_err());
+    ///
+    /// container
+    ///     .extract_and_cache_into(|world, pool: Pool| world.pool = Some(pool))
+    ///     .unwrap();
+    ///
+    /// let repository: Repository = container.extract().unwrap();
+    /// assert_eq!(repository.0, "pool of postgres://db");
+    /// ```
+    pub fn extract_and_cache_into<T>(
+        &mut self,
+        setter: impl FnOnce(&mut World, T),
+    ) -> Result<(), <T as FromWorld>::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        let value = self.extract::<T>()?;
+        setter(&mut self.world, value);
+        Ok(())
+    }
+
+    /// # Extracts `T`, panicking on error
+    ///
+    /// For prototypes and startup code: on error the type name of `T` and the error are logged
+    /// with target `je_di` (`log` feature), then the method panics with them, at the location of
+    /// the caller.
+    ///
+    /// # Panics
+    ///
+    /// When `T` fails to resolve.
+    ///
+    /// # Usage
+    /// ```should_panic
+    /// use je_di::{DIContainer, FromWorld};
+    ///
+    /// struct World;
+    ///
+    /// struct Database;
+    ///
+    /// impl FromWorld for Database {
+    ///     type World<'a> = World;
+    ///     type Error = String;
+    ///
+    ///     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    ///         Err("DATABASE_URL is not set".to_string())
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::new(World);
+    ///
+    /// // panics with: failed to extract {crate}::Database: "DATABASE_URL is not set"
+    /// let database: Database = container.extract_unwrap_or_log();
+    /// ```
+    #[track_caller]
+    pub fn extract_unwrap_or_log<T>(&self) -> T
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+        <T as FromWorld>::Error: core::fmt::Debug,
+    {
+        resolve::unwrap_or_log(self.extract::<T>())
     }
 }
 
@@ -155,6 +849,51 @@ impl<World> DIContainer<World> {
     >(
         &self,
     ) -> Result<T, <T as crate::async_dependency::FromAsyncWorld>::Error> {
-        T::from_world(&self.world).await
+        resolve::resolve_async::<T>(&self.world).await
+    }
+
+    /// Async variant of [`DIContainer::extract_borrowing`]
+    pub async fn extract_borrowing_async<'a, T>(
+        &'a self,
+    ) -> Result<T, <T as crate::async_dependency::BorrowFromAsyncWorld<'a>>::Error>
+    where
+        T: crate::async_dependency::BorrowFromAsyncWorld<'a, World = &'a World>,
+    {
+        T::borrow_from_world(&self.world).await
+    }
+
+    /// # Extracts `T` and feeds it to the async `f`
+    ///
+    /// Async variant of [`DIContainer::extract_and_then`], errors are flattened the same way
+    ///
+    /// # Usage
+    /// ```ignore
+    /// let sent = container
+    ///     .extract_and_then_async(|mailer: Mailer| async move { mailer.send(message).await })
+    ///     .await?;
+    /// ```
+    pub async fn extract_and_then_async<T, R, E, F, Fut>(&self, f: F) -> Result<R, E>
+    where
+        T: for<'a> crate::async_dependency::FromAsyncWorld<World<'a> = World>,
+        E: From<<T as crate::async_dependency::FromAsyncWorld>::Error>,
+        F: FnOnce(T) -> Fut,
+        Fut: std::future::Future<Output = Result<R, E>>,
+    {
+        f(self.extract_async::<T>().await?).await
+    }
+
+    /// # Extracts the async `T`, panicking on error
+    ///
+    /// Async variant of [`DIContainer::extract_unwrap_or_log`]
+    ///
+    /// # Panics
+    ///
+    /// When `T` fails to resolve.
+    pub async fn extract_async_unwrap_or_log<T>(&self) -> T
+    where
+        T: for<'a> crate::async_dependency::FromAsyncWorld<World<'a> = World>,
+        <T as crate::async_dependency::FromAsyncWorld>::Error: core::fmt::Debug,
+    {
+        resolve::unwrap_or_log(self.extract_async::<T>().await)
     }
 }
@@ -0,0 +1,166 @@
+//! # Single-threaded async dependencies
+//!
+//! [`FromAsyncWorld`](crate::FromAsyncWorld) requires `Send + Sync` Worlds and errors and
+//! returns `Send` futures, so that resolutions can move between the threads of a work-stealing
+//! executor. On single-threaded executors (`tokio::task::LocalSet`, a current-thread runtime,
+//! `glommio`) these bounds only get in the way of `Rc`/`RefCell` Worlds.
+//!
+//! [`FromLocalAsyncWorld`] and [`FromLocalAsyncDependency`] mirror the async traits without the
+//! bounds, they are resolved with [`DIContainer::extract_local_async`] and implemented with
+//! `#[async_trait(?Send)]`.
+//!
+//! # Executors
+//!
+//! The resolution futures are not `Send`: they can't be spawned with `tokio::spawn` or on any
+//! multi-threaded executor, spawn them with `tokio::task::spawn_local` within a `LocalSet`, or
+//! await them on the thread of a current-thread runtime. The two families of traits don't mix, a
+//! local dependency can't depend on a [`FromAsyncWorld`](crate::FromAsyncWorld) dependency or
+//! the other way around.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, async_trait,
+//!     local::{FromLocalAsyncDependency, FromLocalAsyncWorld},
+//! };
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! struct World {
+//!     visits: Rc<RefCell<u32>>,
+//! }
+//!
+//! struct Visits(Rc<RefCell<u32>>);
+//!
+//! #[async_trait(?Send)]
+//! impl FromLocalAsyncWorld for Visits {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.visits.clone()))
+//!     }
+//! }
+//!
+//! struct Counter(u32);
+//!
+//! #[async_trait(?Send)]
+//! impl FromLocalAsyncDependency for Counter {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Visits;
+//!
+//!     async fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         visits: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         *visits.0.borrow_mut() += 1;
+//!         Ok(Self(*visits.0.borrow()))
+//!     }
+//! }
+//!
+//! let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+//! let local = tokio::task::LocalSet::new();
+//!
+//! local.block_on(&runtime, async {
+//!     let container = Rc::new(DIContainer::new(World {
+//!         visits: Rc::new(RefCell::new(0)),
+//!     }));
+//!
+//!     let task = tokio::task::spawn_local({
+//!         let container = container.clone();
+//!         async move { container.extract_local_async::<Counter>().await.unwrap().0 }
+//!     });
+//!     assert_eq!(task.await.unwrap(), 1);
+//!
+//!     let (first, second): (Counter, Counter) = container.extract_local_async().await.unwrap();
+//!     assert_eq!((first.0, second.0), (2, 3));
+//! });
+//! ```
+
+use crate::{DIContainer, async_dependency::cooperate, resolve::resolve_local_async};
+use async_trait::async_trait;
+
+/// # [`FromAsyncWorld`](crate::FromAsyncWorld) without the `Send`/`Sync` bounds
+#[async_trait(?Send)]
+pub trait FromLocalAsyncWorld: 'static {
+    type World<'a>;
+    type Error;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error>
+    where
+        Self: std::marker::Sized;
+}
+
+/// # [`FromAsyncDependency`](crate::FromAsyncDependency) without the `Send`/`Sync` bounds
+#[async_trait(?Send)]
+pub trait FromLocalAsyncDependency: 'static {
+    type Error;
+    type World<'a>;
+    type Dependency: for<'a> FromLocalAsyncWorld<World<'a> = Self::World<'a>>;
+
+    async fn from_dependency(
+        world: &Self::World<'_>,
+        dependency: &Self::Dependency,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: std::marker::Sized;
+}
+
+#[async_trait(?Send)]
+impl<T> FromLocalAsyncWorld for T
+where
+    T: FromLocalAsyncDependency,
+    T::Error: From<<T::Dependency as FromLocalAsyncWorld>::Error>,
+{
+    type Error = T::Error;
+    type World<'a> = T::World<'a>;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, T::Error> {
+        let dependency = resolve_local_async::<T::Dependency>(world).await?;
+
+        Self::from_dependency(world, &dependency).await
+    }
+}
+
+macro_rules! impl_tuple {
+    ($first_n:tt:$first_name:ident, $($n:tt:$name:ident),+) => {
+        #[async_trait(?Send)]
+        impl<$first_name, $($name),*> FromLocalAsyncWorld for ($first_name, $($name),+)
+        where
+            $first_name: FromLocalAsyncWorld,
+            $($name: for<'a> FromLocalAsyncWorld<World<'a> = $first_name::World<'a>, Error = $first_name::Error>),*
+        {
+            type Error = $first_name::Error;
+            type World<'a> = $first_name::World<'a>;
+
+            async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+                Ok((
+                    resolve_local_async::<$first_name>(world).await?,
+                    $({
+                        cooperate().await;
+                        resolve_local_async::<$name>(world).await?
+                    }),+
+                ))
+            }
+        }
+    };
+}
+
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7, 8:Dep8);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6, 7:Dep7);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5, 6:Dep6);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4, 5:Dep5);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3, 4:Dep4);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2, 3:Dep3);
+impl_tuple!(0:Dep0, 1:Dep1, 2:Dep2);
+impl_tuple!(0:Dep0, 1:Dep1);
+
+impl<World> DIContainer<World> {
+    /// Extracts a [`FromLocalAsyncWorld`] `T`, the future is not `Send`
+    pub async fn extract_local_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromLocalAsyncWorld<World<'a> = World>,
+    {
+        resolve_local_async::<T>(&self.world).await
+    }
+}
@@ -0,0 +1,144 @@
+//! # Layered Worlds
+//!
+//! Deployments usually configure an application in layers: built-in defaults, overridden by a
+//! configuration file, overridden by environment variables. [`LayeredWorld`] merges such
+//! layers, in precedence order, into the World of a container, each layer only overriding the
+//! fields it sets. The layers are loaded by the application, from any source, as values of the
+//! World type. [`LayeredWorld::build_container`] moves the merged World into a new container.
+//!
+//! Merging is defined by [`Merge`], derived for struct Worlds with `#[derive(Merge)]` (`derive`
+//! feature).
+//!
+//! # Conflict resolution
+//!
+//! Later layers win: when two layers set the same field, the value of the layer added last is
+//! kept. Structs are merged field by field, recursively.
+//!
+//! # Optional fields
+//!
+//! A layer leaves a field unset with `None`: `Option` fields are overridden by a later `Some`
+//! and kept as is by a later `None`, a layer can't unset a field set by an earlier one. Fields
+//! which are not `Option` nor `Merge` structs are marked `#[merge(replace)]`, the later layer
+//! then always overrides them: keep them out of the partial layers, or have every layer set
+//! them.
+//!
+//! Collections are not merged element-wise, wrap them in an `Option` to replace them as a whole
+//! when a layer sets them.
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use je_di::{DIContainer, FromWorld, Merge, merge::LayeredWorld};
+//!
+//! #[derive(Default, Merge)]
+//! struct Settings {
+//!     port: Option<u16>,
+//!     log_level: Option<String>,
+//!     database: DatabaseSettings,
+//! }
+//!
+//! #[derive(Default, Merge)]
+//! struct DatabaseSettings {
+//!     url: Option<String>,
+//!     pool_size: Option<u32>,
+//! }
+//!
+//! struct Server {
+//!     port: u16,
+//! }
+//!
+//! impl FromWorld for Server {
+//!     type World<'a> = Settings;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         let port = world.port.ok_or("no port configured")?;
+//!         Ok(Self { port })
+//!     }
+//! }
+//!
+//! let defaults = Settings {
+//!     port: Some(8080),
+//!     log_level: Some("info".to_string()),
+//!     database: DatabaseSettings { url: None, pool_size: Some(10) },
+//! };
+//! let file = Settings {
+//!     database: DatabaseSettings {
+//!         url: Some("postgres://db".to_string()),
+//!         pool_size: Some(20),
+//!     },
+//!     ..Default::default()
+//! };
+//! let env = Settings {
+//!     port: Some(3000),
+//!     ..Default::default()
+//! };
+//!
+//! // no configuration file for this environment
+//! let overrides = None;
+//!
+//! let settings = LayeredWorld::new(defaults)
+//!     .layer(file)
+//!     .layer_if_some(overrides)
+//!     .layer(env)
+//!     .build();
+//! assert_eq!(settings.port, Some(3000));
+//! assert_eq!(settings.log_level.as_deref(), Some("info"));
+//! assert_eq!(settings.database.url.as_deref(), Some("postgres://db"));
+//! assert_eq!(settings.database.pool_size, Some(20));
+//!
+//! let container = DIContainer::new(settings);
+//! let server: Server = container.extract().unwrap();
+//! assert_eq!(server.port, 3000);
+//! # }
+//! ```
+
+use crate::DIContainer;
+
+/// # Merges a later layer into `self`, see the [module documentation](crate::merge)
+pub trait Merge {
+    /// Overrides `self` with the fields set by `other`
+    fn merge(&mut self, other: Self);
+}
+
+impl<T> Merge for Option<T> {
+    fn merge(&mut self, other: Self) {
+        if other.is_some() {
+            *self = other;
+        }
+    }
+}
+
+/// # Builder merging layers into a World, in precedence order
+pub struct LayeredWorld<World> {
+    world: World,
+}
+
+impl<World: Merge> LayeredWorld<World> {
+    /// Starts from the lowest precedence layer, usually the defaults
+    pub fn new(base: World) -> Self {
+        Self { world: base }
+    }
+
+    /// Merges `layer`, overriding the previous layers
+    pub fn layer(mut self, layer: World) -> Self {
+        self.world.merge(layer);
+        self
+    }
+
+    /// Merges `layer` if there is one, e.g. an optional configuration file
+    pub fn layer_if_some(self, layer: Option<World>) -> Self {
+        match layer {
+            Some(layer) => self.layer(layer),
+            None => self,
+        }
+    }
+
+    pub fn build(self) -> World {
+        self.world
+    }
+
+    pub fn build_container(self) -> DIContainer<World> {
+        DIContainer::new(self.world)
+    }
+}
@@ -0,0 +1,125 @@
+//! # Mock Worlds
+//!
+//! Tests build a World with the values of the case and a container over it,
+//! [`mock_world!`](crate::mock_world) does both and checks the World provides the dependencies
+//! under test:
+//!
+//! ```ignore
+//! let container = mock_world! {
+//!     World { username: "alice".into(), ..Default::default() },
+//!     provides { Printer, Looper }
+//! };
+//! ```
+//!
+//! # Field initialization
+//!
+//! The World is the struct expression given, built as is: every field must be initialized, or
+//! the rest taken from a base with `..base`, e.g. `..Default::default()` for the fields the test
+//! doesn't care about. The values are moved into the World, the macro evaluates to the
+//! [`DIContainer`](crate::DIContainer) over it.
+//!
+//! # Provides check
+//!
+//! The types listed in `provides` are checked at compile time with
+//! [`assert_world_provides!`](crate::assert_world_provides): the test fails to build when one of
+//! them isn't resolvable from the World, instead of failing at the first extraction. Nothing is
+//! resolved by the macro, the dependencies are still resolved by the extractions of the test.
+//! With `provides async { .. }` the types are checked against
+//! [`FromAsyncWorld`](crate::FromAsyncWorld). The list is optional.
+//!
+//! # Deeper mocks
+//!
+//! The World only mocks the values the dependencies are built from. To replace a dependency
+//! itself deep in a tree, e.g. a client calling an external service, combine the container with
+//! [overrides](crate::overrides): `override_scope(Client::fake())` for the rest of the test, with
+//! the dependents depending on `Overridable<Client>`.
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromDependency, FromWorld, mock_world};
+//!
+//! #[derive(Default)]
+//! struct World {
+//!     username: String,
+//!     verbose: bool,
+//! }
+//!
+//! struct Printer {
+//!     username: String,
+//! }
+//!
+//! impl FromWorld for Printer {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { username: world.username.clone() })
+//!     }
+//! }
+//!
+//! struct Greeting(String);
+//!
+//! impl FromDependency for Greeting {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Printer;
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, printer: &Printer) -> Result<Self, Self::Error> {
+//!         Ok(Self(format!("hello {}", printer.username)))
+//!     }
+//! }
+//!
+//! let container = mock_world! {
+//!     World { username: "alice".into(), ..Default::default() },
+//!     provides { Printer, Greeting }
+//! };
+//!
+//! assert_eq!(container.extract::<Greeting>().unwrap().0, "hello alice");
+//! ```
+//!
+//! A dependency the World doesn't provide fails to build:
+//!
+//! ```compile_fail,E0277
+//! # use je_di::mock_world;
+//! # #[derive(Default)]
+//! # struct World;
+//! struct Unregistered;
+//!
+//! let container = mock_world! { World {}, provides { Unregistered } };
+//! ```
+
+/// # Builds a container over a mock World, checking the dependencies it provides
+///
+/// ```ignore
+/// mock_world! { World { field: value, .. }, provides { DepA, DepB } }
+/// mock_world! { World { field: value, .. }, provides async { AsyncDepA } }
+/// mock_world! { World { field: value, .. } }
+/// ```
+///
+/// See the [module documentation](crate::mock)
+#[macro_export]
+macro_rules! mock_world {
+    (@container $($world:ident)::+ { $($fields:tt)* }) => {
+        $crate::DIContainer::new($($world)::+ { $($fields)* })
+    };
+
+    (
+        $($world:ident)::+ { $($fields:tt)* },
+        provides async { $($ty:ty),+ $(,)? } $(,)?
+    ) => {{
+        $crate::assert_world_provides!(async $($world)::+: $($ty),+);
+        $crate::mock_world!(@container $($world)::+ { $($fields)* })
+    }};
+
+    (
+        $($world:ident)::+ { $($fields:tt)* },
+        provides { $($ty:ty),+ $(,)? } $(,)?
+    ) => {{
+        $crate::assert_world_provides!($($world)::+: $($ty),+);
+        $crate::mock_world!(@container $($world)::+ { $($fields)* })
+    }};
+
+    ($($world:ident)::+ { $($fields:tt)* } $(,)?) => {
+        $crate::mock_world!(@container $($world)::+ { $($fields)* })
+    };
+}
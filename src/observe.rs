@@ -0,0 +1,224 @@
+//! # Extraction observers
+//!
+//! An [`ExtractionObserver`] is notified of the resolution of every node of a dependency tree,
+//! when it starts and when it completes, with the elapsed time and whether it resolved: the hook
+//! for metrics and profiling.
+//!
+//! # Global and per-call observers
+//!
+//! [`set_global_observer`] installs an observer for the whole process, once, notified of every
+//! resolution, sync and async, on every thread.
+//!
+//! [`DIContainer::extract_observed`] attaches an observer to a single extraction instead, e.g. to
+//! profile one slow endpoint without paying for the observation of every other extraction. It is
+//! notified of the sync resolutions made on the thread calling `extract_observed`, while it runs.
+//!
+//! Both fire: a resolution observed by a per-call observer is also notified to the global
+//! observer, first. Per-call observers nest, a node resolved by an `extract_observed` running
+//! within another is notified to both, outermost first.
+//!
+//! Overridden nodes are not resolved and not observed. Without any observer, observing costs a
+//! global and a thread-local lookup per node.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromDependency, FromWorld, observe::ExtractionObserver};
+//! use std::{cell::RefCell, time::Duration};
+//!
+//! struct World;
+//!
+//! struct Config;
+//!
+//! impl FromWorld for Config {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! struct Server;
+//!
+//! impl FromDependency for Server {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Config;
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, _config: &Config) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! #[derive(Default)]
+//! struct Timings(RefCell<Vec<(&'static str, Duration)>>);
+//!
+//! impl ExtractionObserver for Timings {
+//!     fn on_end(&self, node: &'static str, elapsed: Duration, _resolved: bool) {
+//!         self.0.borrow_mut().push((node, elapsed));
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let timings = Timings::default();
+//! assert!(container.extract_observed::<Server>(&timings).is_ok());
+//!
+//! // the dependency completes first
+//! let timings = timings.0.into_inner();
+//! assert!(timings[0].0.ends_with("Config"));
+//! assert!(timings[1].0.ends_with("Server"));
+//!
+//! // the observer is detached after the call
+//! assert!(container.extract::<Server>().is_ok());
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::{
+    cell::RefCell,
+    fmt,
+    marker::PhantomData,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// # Observer of the resolutions
+pub trait ExtractionObserver {
+    /// The resolution of `node`, its type name, starts
+    fn on_start(&self, node: &'static str) {
+        let _ = node;
+    }
+
+    /// The resolution of `node` completed after `elapsed`
+    fn on_end(&self, node: &'static str, elapsed: Duration, resolved: bool);
+}
+
+static GLOBAL: OnceLock<Box<dyn ExtractionObserver + Send + Sync>> = OnceLock::new();
+
+/// # A global observer is already installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetObserverError;
+
+impl fmt::Display for SetObserverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a global extraction observer is already installed")
+    }
+}
+
+impl std::error::Error for SetObserverError {}
+
+/// Installs the global observer, fails if one is already installed
+pub fn set_global_observer(
+    observer: impl ExtractionObserver + Send + Sync + 'static,
+) -> Result<(), SetObserverError> {
+    GLOBAL.set(Box::new(observer)).map_err(|_| SetObserverError)
+}
+
+type Observer = *const (dyn ExtractionObserver + 'static);
+
+thread_local! {
+    /// Per-call observers of the extractions running on this thread, outermost first
+    static OBSERVERS: RefCell<Vec<Observer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Notifies `event` to the global observer, then to the per-call observers if `per_call`
+fn notify(event: impl Fn(&dyn ExtractionObserver), per_call: bool) -> bool {
+    let global = GLOBAL.get().map(|observer| event(&**observer)).is_some();
+    if !per_call {
+        return global;
+    }
+
+    let observers = OBSERVERS.with(|observers| observers.borrow().clone());
+    for &observer in &observers {
+        // SAFETY: the observers are pushed by an `ObserverGuard` borrowing them, and popped when
+        // it drops, on this thread: every pointer of the stack is borrowed for as long as it is
+        // in the stack
+        event(unsafe { &*observer });
+    }
+
+    global || !observers.is_empty()
+}
+
+fn notify_start<T>(per_call: bool) -> Option<Instant> {
+    notify(
+        |observer| observer.on_start(std::any::type_name::<T>()),
+        per_call,
+    )
+    .then(Instant::now)
+}
+
+fn notify_end<T>(started: Option<Instant>, resolved: bool, per_call: bool) {
+    let Some(start) = started else {
+        return;
+    };
+
+    let elapsed = start.elapsed();
+    notify(
+        |observer| observer.on_end(std::any::type_name::<T>(), elapsed, resolved),
+        per_call,
+    );
+}
+
+/// Notifies the start of the sync resolution of `T`, when it is observed
+pub(crate) fn start<T>() -> Option<Instant> {
+    notify_start::<T>(true)
+}
+
+/// Notifies the end of the resolution started by [`start`]
+pub(crate) fn end<T>(started: Option<Instant>, resolved: bool) {
+    notify_end::<T>(started, resolved, true);
+}
+
+/// Notifies the start of the async resolution of `T` to the global observer, if any
+#[cfg(feature = "async")]
+pub(crate) fn start_global<T>() -> Option<Instant> {
+    notify_start::<T>(false)
+}
+
+/// Notifies the end of the resolution started by [`start_global`]
+#[cfg(feature = "async")]
+pub(crate) fn end_global<T>(started: Option<Instant>, resolved: bool) {
+    notify_end::<T>(started, resolved, false);
+}
+
+/// Observes the resolutions on this thread while it lives, even if the resolution panics
+struct ObserverGuard<'a> {
+    _observer: PhantomData<&'a dyn ExtractionObserver>,
+}
+
+impl<'a> ObserverGuard<'a> {
+    fn attach(observer: &'a dyn ExtractionObserver) -> Self {
+        // SAFETY: only the lifetime is erased, the pointer is popped before the borrow ends
+        let observer = unsafe {
+            std::mem::transmute::<&'a dyn ExtractionObserver, &'static dyn ExtractionObserver>(
+                observer,
+            )
+        };
+        OBSERVERS.with(|observers| observers.borrow_mut().push(observer));
+
+        Self {
+            _observer: PhantomData,
+        }
+    }
+}
+
+impl Drop for ObserverGuard<'_> {
+    fn drop(&mut self) {
+        OBSERVERS.with(|observers| observers.borrow_mut().pop());
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, notifying `observer` of the resolution of its dependency tree
+    ///
+    /// The observer is attached to the calling thread for the duration of the call: async nodes,
+    /// and nodes resolved on other threads, e.g. the members of an `extract_parallel`, are
+    /// not notified to it, only to the global observer.
+    pub fn extract_observed<T>(&self, observer: &dyn ExtractionObserver) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        let _guard = ObserverGuard::attach(observer);
+        resolve::<T>(&self.world)
+    }
+}
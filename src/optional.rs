@@ -0,0 +1,227 @@
+//! # Optional dependencies
+//!
+//! [`FromOptionalDependency`] models "use the cache if available, else go direct": when the
+//! dependency fails to resolve, `from_dependency` receives `None` instead of the resolution
+//! failing.
+//!
+//! The optionality is on the dependency, not on `Self`: the dependent type is always resolved,
+//! and its own errors still fail the resolution. Making `Self` optional (resolving an
+//! `Option<Self>`) would instead hide the dependent type entirely when anything below it fails.
+//!
+//! Every [`FromOptionalDependency`] type implements [`FromDependency`] with
+//! [`Optional<Dependency, Error>`] as dependency, so it can be extracted and depended upon like
+//! any other dependency.
+//!
+//! # Async
+//!
+//! [`FromOptionalAsyncDependency`] is the async counterpart, every implementation is a
+//! `FromAsyncDependency` with [`Optional<Dependency, Error>`] as dependency:
+//!
+//! ```
+//! # #[cfg(feature = "async")] {
+//! use je_di::{DIContainer, FromAsyncWorld, FromOptionalAsyncDependency, async_trait};
+//!
+//! struct World {
+//!     cache_enabled: bool,
+//! }
+//!
+//! struct Cache;
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Cache {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         match world.cache_enabled {
+//!             true => Ok(Self),
+//!             false => Err("cache disabled".to_string()),
+//!         }
+//!     }
+//! }
+//!
+//! struct Repository {
+//!     cached: bool,
+//! }
+//!
+//! #[async_trait]
+//! impl FromOptionalAsyncDependency for Repository {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Cache;
+//!
+//!     async fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         cache: Option<&Self::Dependency>,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self { cached: cache.is_some() })
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let container = DIContainer::new(World { cache_enabled: false });
+//! let repository: Repository = container.extract_async().await.unwrap();
+//! assert!(!repository.cached);
+//!
+//! let container = DIContainer::new(World { cache_enabled: true });
+//! assert!(container.extract_async::<Repository>().await.unwrap().cached);
+//! # });
+//! # }
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromOptionalDependency, FromWorld};
+//!
+//! struct World {
+//!     cache_enabled: bool,
+//! }
+//!
+//! struct Cache;
+//!
+//! impl FromWorld for Cache {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         if world.cache_enabled {
+//!             Ok(Self)
+//!         } else {
+//!             Err("cache disabled".to_string())
+//!         }
+//!     }
+//! }
+//!
+//! struct Repository {
+//!     cached: bool,
+//! }
+//!
+//! impl FromOptionalDependency for Repository {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Cache;
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         cache: Option<&Self::Dependency>,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self { cached: cache.is_some() })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { cache_enabled: false });
+//! let repository: Repository = container.extract().unwrap();
+//! assert!(!repository.cached);
+//! ```
+
+use crate::{FromDependency, FromWorld, resolve::resolve};
+use core::marker::PhantomData;
+
+/// # Defines a dependency whose own dependency is optional
+pub trait FromOptionalDependency {
+    type Error;
+    type World<'a>;
+    type Dependency: for<'a> FromWorld<World<'a> = Self::World<'a>>;
+
+    fn from_dependency(
+        world: &Self::World<'_>,
+        dependency: Option<&Self::Dependency>,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: core::marker::Sized;
+}
+
+/// # Dependency resolving to `None` instead of failing
+///
+/// `Error` is the error type of the dependency tree, it is never returned
+pub struct Optional<D, Error> {
+    pub dependency: Option<D>,
+    _marker: PhantomData<fn() -> Error>,
+}
+
+impl<D, E> FromWorld for Optional<D, E>
+where
+    D: FromWorld,
+{
+    type World<'a> = D::World<'a>;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            dependency: resolve::<D>(world).ok(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> FromDependency for T
+where
+    T: FromOptionalDependency,
+{
+    type Error = T::Error;
+    type World<'a> = T::World<'a>;
+    type Dependency = Optional<T::Dependency, T::Error>;
+
+    fn from_dependency(
+        world: &Self::World<'_>,
+        dependency: &Self::Dependency,
+    ) -> Result<Self, Self::Error> {
+        <T as FromOptionalDependency>::from_dependency(world, dependency.dependency.as_ref())
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[crate::async_trait]
+impl<D, E> crate::FromAsyncWorld for Optional<D, E>
+where
+    D: crate::FromAsyncWorld + Send,
+    E: Send + Sync + 'static,
+{
+    type World<'a> = D::World<'a>;
+    type Error = E;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            dependency: crate::resolve::resolve_async::<D>(world).await.ok(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # Defines an async dependency whose own dependency is optional
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[crate::async_trait]
+pub trait FromOptionalAsyncDependency: 'static {
+    type Error: Send + Sync;
+    type World<'a>: Send + Sync;
+    type Dependency: for<'a> crate::FromAsyncWorld<World<'a> = Self::World<'a>> + Send + Sync;
+
+    async fn from_dependency(
+        world: &Self::World<'_>,
+        dependency: Option<&Self::Dependency>,
+    ) -> Result<Self, Self::Error>
+    where
+        Self: core::marker::Sized;
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[crate::async_trait]
+impl<T> crate::FromAsyncDependency for T
+where
+    T: FromOptionalAsyncDependency,
+{
+    type Error = T::Error;
+    type World<'a> = T::World<'a>;
+    type Dependency = Optional<T::Dependency, T::Error>;
+
+    async fn from_dependency(
+        world: &Self::World<'_>,
+        dependency: &Self::Dependency,
+    ) -> Result<Self, Self::Error> {
+        <T as FromOptionalAsyncDependency>::from_dependency(world, dependency.dependency.as_ref())
+            .await
+    }
+}
@@ -0,0 +1,426 @@
+//! # Dependency overrides
+//!
+//! [`DIContainer::extract_with_override`] resolves a root normally, except for one node of its
+//! graph which is replaced by a provided instance, e.g. to swap in an experiment variant picked
+//! from a runtime bucket without writing new implementations.
+//!
+//! # Overridable nodes
+//!
+//! Only the nodes declared overridable can be replaced: a dependent depends on
+//! [`Overridable<T>`] instead of `T`, which resolves to a clone of the override of `T` when
+//! there is one, its own dependencies are then never resolved, and to `T` resolved normally
+//! otherwise. The other nodes don't consult the overrides and don't pay for them.
+//!
+//! An overridable node is `Clone + 'static`: the override is a `T` stored as [`Any`] and
+//! downcast back, a node borrowing from its World can't be overridden.
+//!
+//! ```compile_fail,E0597
+//! # use je_di::overrides::override_scope;
+//! #[derive(Clone)]
+//! struct Name<'a>(&'a str);
+//!
+//! let name = String::from("borrowed");
+//! let _scope = override_scope(Name(&name));
+//! ```
+//!
+//! # Mechanism
+//!
+//! Overrides are stored in a thread local map keyed by the [`TypeId`] of the overridden node,
+//! each [`Overridable`] node looks its own type up before resolving it.
+//!
+//! # Scope
+//!
+//! - the override only applies to the current thread, for the duration of the extraction, it is
+//!   removed when the extraction returns or panics
+//! - only sync resolutions consult the overrides, an async task can move between threads
+//! - nested overrides of the same node shadow the outer one until they are removed
+//!
+//! # Scoped overrides
+//!
+//! [`override_scope`] installs an override for every extraction until the returned
+//! [`OverrideScope`] guard is dropped, typically at the end of a test or of a block within it.
+//! It is a free function rather than a container method because the override isn't tied to a
+//! container: every container of the thread sees it, whatever its World.
+//!
+//! - scopes nest: an inner scope of the same node shadows the outer one, dropping it reveals
+//!   the outer one again, dropping the guards out of order removes the right override
+//! - the registry is per thread: tests running in parallel on the test harness threads don't
+//!   see each other's overrides, and threads spawned by a test don't see its overrides either.
+//!   The guard is not `Send`, it is dropped on the thread that installed it
+//!
+//! ```
+//! use je_di::{DIContainer, FromWorld, overrides::{Overridable, override_scope}};
+//!
+//! struct Tenant {
+//!     started_at: u64,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Clock(u64);
+//!
+//! impl FromWorld for Clock {
+//!     type World<'a> = Tenant;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.started_at))
+//!     }
+//! }
+//!
+//! let acme = DIContainer::new(Tenant { started_at: 100 });
+//! let globex = DIContainer::new(Tenant { started_at: 200 });
+//!
+//! {
+//!     let _frozen = override_scope(Clock(0));
+//!
+//!     // not only the container the test had in mind: every container of the thread
+//!     assert_eq!(acme.extract::<Overridable<Clock>>().unwrap().0.0, 0);
+//!     assert_eq!(globex.extract::<Overridable<Clock>>().unwrap().0.0, 0);
+//!
+//!     // other threads don't see it
+//!     std::thread::scope(|scope| {
+//!         scope.spawn(|| assert_eq!(acme.extract::<Overridable<Clock>>().unwrap().0.0, 100));
+//!     });
+//! }
+//!
+//! assert_eq!(acme.extract::<Overridable<Clock>>().unwrap().0.0, 100);
+//! assert_eq!(globex.extract::<Overridable<Clock>>().unwrap().0.0, 200);
+//! ```
+//!
+//! # Snapshots
+//!
+//! For table-driven tests, [`snapshot_overrides`] captures the overrides of the current thread,
+//! e.g. a baseline installed by a few scopes, and [`restore_overrides`] puts them back after a
+//! case changed them. Like [`override_scope`], both act on the registry of the thread, not on a
+//! container.
+//!
+//! - restoring replaces the registry of the thread: the overrides installed since the snapshot
+//!   are removed, their guards then do nothing when dropped
+//! - only the overrides whose [`OverrideScope`] guard is still alive are restored: an override
+//!   whose guard was dropped since the snapshot stays removed, a snapshot never outlives the
+//!   scopes it captured
+//! - a snapshot shares the overrides with the registry instead of cloning the overridden values:
+//!   taking one allocates a map entry per overridden type, restoring drops the current registry.
+//!   [`OverrideSnapshot`] is [`Clone`] at the same cost, to restore it once per case
+//! - like the registry, a snapshot belongs to its thread, it is not `Send`
+//!
+//! ```
+//! # use je_di::{
+//! #     DIContainer, FromWorld,
+//! #     overrides::{Overridable, override_scope, restore_overrides, snapshot_overrides},
+//! # };
+//! # struct World;
+//! #[derive(Clone)]
+//! struct Region(&'static str);
+//!
+//! impl FromWorld for Region {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("us-east-1"))
+//!     }
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Currency(&'static str);
+//!
+//! impl FromWorld for Currency {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("USD"))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let _baseline = override_scope(Region("eu-west-1"));
+//! let baseline = snapshot_overrides();
+//!
+//! for currency in ["EUR", "CHF"] {
+//!     let case = override_scope(Currency(currency));
+//!     assert_eq!(container.extract::<Overridable<Region>>().unwrap().0.0, "eu-west-1");
+//!     assert_eq!(container.extract::<Overridable<Currency>>().unwrap().0.0, currency);
+//!
+//!     restore_overrides(baseline.clone());
+//!     assert_eq!(container.extract::<Overridable<Currency>>().unwrap().0.0, "USD");
+//!     drop(case);
+//! }
+//!
+//! assert_eq!(container.extract::<Overridable<Region>>().unwrap().0.0, "eu-west-1");
+//!
+//! // a snapshot doesn't bring back an override whose scope ended
+//! let euro = override_scope(Currency("EUR"));
+//! let with_euro = snapshot_overrides();
+//! drop(euro);
+//! restore_overrides(with_euro);
+//! assert_eq!(container.extract::<Overridable<Currency>>().unwrap().0.0, "USD");
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromDependency, FromWorld,
+//!     overrides::{Overridable, override_scope},
+//! };
+//!
+//! struct World;
+//!
+//! #[derive(Clone)]
+//! struct Variant(&'static str);
+//!
+//! impl FromWorld for Variant {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("control"))
+//!     }
+//! }
+//!
+//! struct Checkout(&'static str);
+//!
+//! impl FromDependency for Checkout {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Overridable<Variant>;
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         variant: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self(variant.0.0))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let checkout = container
+//!     .extract_with_override::<Checkout, _>(Variant("experiment"))
+//!     .unwrap();
+//! assert_eq!(checkout.0, "experiment");
+//!
+//! assert_eq!(container.extract::<Checkout>().unwrap().0, "control");
+//!
+//! {
+//!     let _outer = override_scope(Variant("outer"));
+//!     assert_eq!(container.extract::<Checkout>().unwrap().0, "outer");
+//!
+//!     // the nodes resolving `Variant` itself don't consult the overrides
+//!     assert_eq!(container.extract::<Variant>().unwrap().0, "control");
+//!
+//!     {
+//!         let _inner = override_scope(Variant("inner"));
+//!         assert_eq!(container.extract::<Checkout>().unwrap().0, "inner");
+//!     }
+//!
+//!     assert_eq!(container.extract::<Checkout>().unwrap().0, "outer");
+//! }
+//!
+//! assert_eq!(container.extract::<Checkout>().unwrap().0, "control");
+//! ```
+
+use crate::{DIContainer, FromWorld, bundle::BundleDependencies, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap, HashSet},
+    marker::PhantomData,
+    ops::Deref,
+    rc::Rc,
+};
+
+type Override = Rc<dyn Fn() -> Box<dyn Any>>;
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<TypeId, Vec<(u64, Override)>>> =
+        RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    /// Ids of the overrides whose guard is alive, the only ones a snapshot may restore
+    static LIVE: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+}
+
+/// Returns a clone of the override of `T` on the current thread, if any
+fn get<T: 'static>() -> Option<T> {
+    let node = OVERRIDES.with(|overrides| {
+        overrides
+            .borrow()
+            .get(&TypeId::of::<T>())?
+            .last()
+            .map(|(_, clone)| clone())
+    })?;
+
+    #[cfg(feature = "log")]
+    log::debug!(target: "je_di", "using the override of {}", std::any::type_name::<T>());
+
+    node.downcast::<T>().ok().map(|node| *node)
+}
+
+/// # Node replaced by the override of `T` on the current thread, if any
+///
+/// Resolves to a clone of the override of `T`, or to `T` resolved normally when it has none.
+/// See the [module documentation](crate::overrides#overridable-nodes)
+#[derive(Debug, Clone)]
+pub struct Overridable<T>(pub T);
+
+impl<T> Overridable<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Overridable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> FromWorld for Overridable<T>
+where
+    T: FromWorld + Clone + 'static,
+{
+    type World<'a> = T::World<'a>;
+    type Error = T::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        if let Some(node) = get::<T>() {
+            return Ok(Self(node));
+        }
+
+        resolve::<T>(world).map(Self)
+    }
+}
+
+impl<T> BundleDependencies for Overridable<T>
+where
+    T: BundleDependencies + Clone + 'static,
+{
+    fn resolve_bundled(
+        world: &Self::World<'_>,
+        intermediates: &mut BTreeMap<&'static str, String>,
+    ) -> Result<Self, Self::Error> {
+        if let Some(node) = get::<T>() {
+            return Ok(Self(node));
+        }
+
+        T::resolve_bundled(world, intermediates).map(Self)
+    }
+
+    fn record(&self, intermediates: &mut BTreeMap<&'static str, String>) {
+        self.0.record(intermediates);
+    }
+}
+
+/// Removes the override it was created with on drop
+struct OverrideGuard {
+    node: TypeId,
+    id: u64,
+    // the overrides are thread local, the guard must be dropped on the thread that pushed them
+    _not_send: PhantomData<*const ()>,
+}
+
+impl OverrideGuard {
+    fn push<Node: Clone + 'static>(node: Node) -> Self {
+        let node_id = TypeId::of::<Node>();
+        let clone: Override = Rc::new(move || Box::new(node.clone()));
+        let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+        LIVE.with(|live| live.borrow_mut().insert(id));
+
+        OVERRIDES.with(|overrides| {
+            overrides
+                .borrow_mut()
+                .entry(node_id)
+                .or_default()
+                .push((id, clone))
+        });
+
+        Self {
+            node: node_id,
+            id,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        LIVE.with(|live| live.borrow_mut().remove(&self.id));
+        OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            if let Some(stack) = overrides.get_mut(&self.node) {
+                stack.retain(|(id, _)| *id != self.id);
+                if stack.is_empty() {
+                    overrides.remove(&self.node);
+                }
+            }
+        });
+    }
+}
+
+/// # Scoped override, removed when dropped
+///
+/// Returned by [`override_scope`]
+#[must_use = "the override is removed when the guard is dropped"]
+pub struct OverrideScope {
+    _guard: OverrideGuard,
+}
+
+/// # Overrides `Node` with clones of `node` until the guard is dropped
+///
+/// The override covers the whole current thread: every extraction of every container running
+/// on it, not only those of a given container. See the
+/// [module documentation](crate::overrides#scoped-overrides)
+pub fn override_scope<Node: Clone + 'static>(node: Node) -> OverrideScope {
+    OverrideScope {
+        _guard: OverrideGuard::push(node),
+    }
+}
+
+/// # Overrides of a thread, captured by [`snapshot_overrides`]
+#[derive(Clone)]
+pub struct OverrideSnapshot {
+    overrides: HashMap<TypeId, Vec<(u64, Override)>>,
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `Root`, using a clone of `node` wherever its graph depends on `Overridable<Node>`
+    pub fn extract_with_override<Root, Node>(&self, node: Node) -> Result<Root, Root::Error>
+    where
+        Root: for<'a> FromWorld<World<'a> = World>,
+        Node: Clone + 'static,
+    {
+        let _guard = OverrideGuard::push(node);
+
+        resolve::<Root>(&self.world)
+    }
+}
+
+/// # Captures the overrides of the current thread
+///
+/// See the [module documentation](crate::overrides#snapshots)
+pub fn snapshot_overrides() -> OverrideSnapshot {
+    OverrideSnapshot {
+        overrides: OVERRIDES.with(|overrides| overrides.borrow().clone()),
+    }
+}
+
+/// # Replaces the overrides of the current thread with those of `snapshot`
+///
+/// The overrides of `snapshot` whose [`OverrideScope`] guard was dropped are skipped
+pub fn restore_overrides(snapshot: OverrideSnapshot) {
+    let mut restored = snapshot.overrides;
+    LIVE.with(|live| {
+        let live = live.borrow();
+        restored.retain(|_, stack| {
+            stack.retain(|(id, _)| live.contains(id));
+            !stack.is_empty()
+        });
+    });
+
+    // the replaced overrides are dropped once the registry is released
+    let _replaced = OVERRIDES.with(|overrides| overrides.replace(restored));
+}
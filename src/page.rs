@@ -0,0 +1,291 @@
+//! # Paginated dependencies
+//!
+//! A [`PageSource<T>`] is a World field owning a client and a closure fetching the page at an
+//! index from it. [`PageIter<'a, T, World>`] is an [`Iterator`] pulling the pages on demand,
+//! extracted with [`DIContainer::extract_borrowing`](crate::DIContainer::extract_borrowing): the
+//! pages are only fetched when the iterator is advanced.
+//!
+//! The closure returns `None` past the last page, which ends the iteration. Fallible clients
+//! return `Option<Result<Page, Error>>`, the iteration then goes on after an error unless the
+//! closure returns `None` for the next index.
+//!
+//! The World exposes the source through [`AsRef<PageSource<T>>`].
+//!
+//! # Lifetimes
+//!
+//! The iterator borrows the source from the World of the container for `'a`: the container
+//! stays borrowed, and can't be moved or dropped, as long as the iterator lives. Collect the
+//! pages to keep them past the container.
+//!
+//! # Async
+//!
+//! With the `stream` feature, [`AsyncPageSource<T>`] fetches the pages with an async closure
+//! and [`PageStream<'a, T, World>`] is the `Stream` counterpart of [`PageIter`], extracted with
+//! [`DIContainer::extract_borrowing_async`](crate::DIContainer::extract_borrowing_async).
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, page::{PageIter, PageSource}};
+//!
+//! struct OrdersClient {
+//!     orders: Vec<u32>,
+//! }
+//!
+//! impl OrdersClient {
+//!     fn list(&self, page: usize, per_page: usize) -> Option<Vec<u32>> {
+//!         let page = self.orders.chunks(per_page).nth(page)?;
+//!         Some(page.to_vec())
+//!     }
+//! }
+//!
+//! struct World {
+//!     orders: PageSource<Vec<u32>>,
+//! }
+//!
+//! impl AsRef<PageSource<Vec<u32>>> for World {
+//!     fn as_ref(&self) -> &PageSource<Vec<u32>> {
+//!         &self.orders
+//!     }
+//! }
+//!
+//! let client = OrdersClient { orders: (1..=5).collect() };
+//! let container = DIContainer::new(World {
+//!     orders: PageSource::new(client, |client, page| client.list(page, 2)),
+//! });
+//!
+//! let mut pages: PageIter<Vec<u32>, World> = container.extract_borrowing().unwrap();
+//! assert_eq!(pages.next(), Some(vec![1, 2]));
+//! assert_eq!(pages.collect::<Vec<_>>(), [vec![3, 4], vec![5]]);
+//! ```
+
+use crate::BorrowFromWorld;
+use alloc::boxed::Box;
+use core::{convert::Infallible, fmt, iter::FusedIterator, marker::PhantomData};
+
+type Fetch<T> = Box<dyn Fn(usize) -> Option<T> + Send + Sync>;
+
+/// # Client fetching pages by index
+pub struct PageSource<T> {
+    fetch: Fetch<T>,
+}
+
+impl<T> PageSource<T> {
+    /// Builds a source owning `client`, `fetch` returns the page at an index or `None` past the
+    /// last page
+    pub fn new<C: Send + Sync + 'static>(
+        client: C,
+        fetch: impl Fn(&C, usize) -> Option<T> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            fetch: Box::new(move |page| fetch(&client, page)),
+        }
+    }
+
+    /// Fetches the page at `page`
+    pub fn fetch(&self, page: usize) -> Option<T> {
+        (self.fetch)(page)
+    }
+}
+
+impl<T> fmt::Debug for PageSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageSource").finish_non_exhaustive()
+    }
+}
+
+/// # Iterator over the pages of the [`PageSource`] of the World
+///
+/// See the [error of the dependency](crate::FromDependency#error-of-the-dependency)
+pub struct PageIter<'a, T, World, Error = Infallible> {
+    source: Option<&'a PageSource<T>>,
+    page: usize,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> PageIter<'_, T, World, Error> {
+    /// Index of the next page to fetch
+    pub fn page(&self) -> usize {
+        self.page
+    }
+}
+
+impl<T, World, Error> Iterator for PageIter<'_, T, World, Error> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(page) = self.source?.fetch(self.page) else {
+            self.source = None;
+            return None;
+        };
+
+        self.page += 1;
+        Some(page)
+    }
+}
+
+impl<T, World, Error> FusedIterator for PageIter<'_, T, World, Error> {}
+
+impl<'a, T, W, E> BorrowFromWorld<'a> for PageIter<'a, T, W, E>
+where
+    W: AsRef<PageSource<T>> + 'a,
+{
+    type World = &'a W;
+    type Error = E;
+
+    fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+        Ok(Self {
+            source: Some(world.as_ref()),
+            page: 0,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+pub use stream::{AsyncPageSource, PageStream};
+
+#[cfg(feature = "stream")]
+mod stream {
+    use crate::BorrowFromAsyncWorld;
+    use core::{
+        convert::Infallible,
+        fmt,
+        marker::PhantomData,
+        pin::Pin,
+        task::{Context, Poll, ready},
+    };
+    use futures::{Stream, future::BoxFuture, stream::FusedStream};
+
+    type AsyncFetch<T> = Box<dyn Fn(usize) -> BoxFuture<'static, Option<T>> + Send + Sync>;
+
+    /// # Client fetching pages by index asynchronously
+    pub struct AsyncPageSource<T> {
+        fetch: AsyncFetch<T>,
+    }
+
+    impl<T> AsyncPageSource<T> {
+        /// Builds a source, `fetch` returns the future of the page at an index, resolving to
+        /// `None` past the last page
+        ///
+        /// The futures are `'static`: the client is usually an `Arc` cloned into each of them
+        pub fn new(
+            fetch: impl Fn(usize) -> BoxFuture<'static, Option<T>> + Send + Sync + 'static,
+        ) -> Self {
+            Self {
+                fetch: Box::new(fetch),
+            }
+        }
+
+        /// Fetches the page at `page`
+        pub fn fetch(&self, page: usize) -> BoxFuture<'static, Option<T>> {
+            (self.fetch)(page)
+        }
+    }
+
+    impl<T> fmt::Debug for AsyncPageSource<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("AsyncPageSource").finish_non_exhaustive()
+        }
+    }
+
+    /// # Stream of the pages of the [`AsyncPageSource`] of the World
+    ///
+    /// # Usage
+    /// ```
+    /// use futures::{FutureExt, StreamExt};
+    /// use je_di::{DIContainer, page::{AsyncPageSource, PageStream}};
+    /// use std::sync::Arc;
+    ///
+    /// struct World {
+    ///     pages: AsyncPageSource<Vec<u32>>,
+    /// }
+    ///
+    /// impl AsRef<AsyncPageSource<Vec<u32>>> for World {
+    ///     fn as_ref(&self) -> &AsyncPageSource<Vec<u32>> {
+    ///         &self.pages
+    ///     }
+    /// }
+    ///
+    /// let orders: Arc<Vec<u32>> = Arc::new((1..=5).collect());
+    /// let container = DIContainer::new(World {
+    ///     pages: AsyncPageSource::new(move |page| {
+    ///         let orders = orders.clone();
+    ///         async move { orders.chunks(2).nth(page).map(<[u32]>::to_vec) }.boxed()
+    ///     }),
+    /// });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let pages: PageStream<Vec<u32>, World> =
+    ///     container.extract_borrowing_async().await.unwrap();
+    /// assert_eq!(pages.collect::<Vec<_>>().await, [vec![1, 2], vec![3, 4], vec![5]]);
+    /// # });
+    /// ```
+    pub struct PageStream<'a, T, World, Error = Infallible> {
+        source: Option<&'a AsyncPageSource<T>>,
+        page: usize,
+        pending: Option<BoxFuture<'static, Option<T>>>,
+        _marker: PhantomData<fn() -> (World, Error)>,
+    }
+
+    impl<T, World, Error> PageStream<'_, T, World, Error> {
+        /// Index of the next page to fetch
+        pub fn page(&self) -> usize {
+            self.page
+        }
+    }
+
+    impl<T, World, Error> Unpin for PageStream<'_, T, World, Error> {}
+
+    impl<T, World, Error> Stream for PageStream<'_, T, World, Error> {
+        type Item = T;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let Some(source) = self.source else {
+                return Poll::Ready(None);
+            };
+
+            let page = self.page;
+            let pending = self.pending.get_or_insert_with(|| source.fetch(page));
+            let fetched = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+
+            match fetched {
+                Some(page) => {
+                    self.page += 1;
+                    Poll::Ready(Some(page))
+                }
+                None => {
+                    self.source = None;
+                    Poll::Ready(None)
+                }
+            }
+        }
+    }
+
+    impl<T, World, Error> FusedStream for PageStream<'_, T, World, Error> {
+        fn is_terminated(&self) -> bool {
+            self.source.is_none()
+        }
+    }
+
+    #[crate::async_trait]
+    impl<'a, T, W, E> BorrowFromAsyncWorld<'a> for PageStream<'a, T, W, E>
+    where
+        T: 'a,
+        W: AsRef<AsyncPageSource<T>> + Sync + 'a,
+        E: Send + Sync,
+    {
+        type World = &'a W;
+        type Error = E;
+
+        async fn borrow_from_world(world: Self::World) -> Result<Self, Self::Error> {
+            Ok(Self {
+                source: Some(world.as_ref()),
+                page: 0,
+                pending: None,
+                _marker: PhantomData,
+            })
+        }
+    }
+}
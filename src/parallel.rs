@@ -0,0 +1,165 @@
+//! # Parallel resolution
+//!
+//! Sync dependencies with a CPU-bound construction, e.g. building search indexes or parsing large
+//! files, are resolved one after the other by the tuple implementations of [`FromWorld`].
+//! [`DIContainer::extract_parallel`] resolves the members of a tuple in parallel on the rayon
+//! global thread pool instead, and joins them:
+//!
+//! ```ignore
+//! let (index, catalog, geo) = container.extract_parallel::<(SearchIndex, Catalog, GeoDb)>()?;
+//! ```
+//!
+//! Within a member, its dependency tree is resolved sequentially on the thread the member runs
+//! on. The World is shared by the threads, it must be `Sync`, the members and the error are sent
+//! back to the calling thread, they must be `Send`.
+//!
+//! # Errors
+//!
+//! Every member is resolved, a failed member doesn't cancel the others. The error returned is the
+//! one of the first failed member in the order of the tuple, not the first to fail in time: the
+//! result doesn't depend on the scheduling.
+//!
+//! # Determinism
+//!
+//! The members run concurrently, in no particular order. Constructors with side effects, e.g.
+//! logging, incrementing counters, allocating ids or writing files, interleave differently from
+//! one run to the next: members must not depend on the effects of one another, as they may when
+//! resolved in order. The resolution hooks recording on the calling thread, like
+//! [traces](crate::trace) and per-call [observers](crate::observe), don't see the members
+//! resolved on the threads of the pool.
+//!
+//! The same goes for the [overrides](crate::overrides), installed in a registry of the calling
+//! thread: the members resolve their [`Overridable`](crate::overrides::Overridable) nodes
+//! normally. The overrides can't follow them, they hold values which aren't `Send`: extract the
+//! tuples depending on overridden nodes with [`DIContainer::extract`].
+//!
+//! ```
+//! use je_di::{DIContainer, FromWorld, overrides::{Overridable, override_scope}};
+//!
+//! struct World;
+//!
+//! #[derive(Clone)]
+//! struct Region(&'static str);
+//!
+//! impl FromWorld for Region {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self("us-east-1"))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//! let _scope = override_scope(Region("eu-west-1"));
+//!
+//! type Regions = (Overridable<Region>, Overridable<Region>);
+//!
+//! let (first, _) = container.extract::<Regions>().unwrap();
+//! assert_eq!(first.0.0, "eu-west-1");
+//!
+//! // resolved on the threads of the pool, which don't see the override
+//! let (first, second) = container.extract_parallel::<Regions>().unwrap();
+//! assert_eq!((first.0.0, second.0.0), ("us-east-1", "us-east-1"));
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//!
+//! struct World {
+//!     documents: Vec<&'static str>,
+//! }
+//!
+//! struct WordCount(usize);
+//!
+//! impl FromWorld for WordCount {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.documents.iter().map(|doc| doc.split_whitespace().count()).sum()))
+//!     }
+//! }
+//!
+//! struct Longest(&'static str);
+//!
+//! impl FromWorld for Longest {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         let longest = world.documents.iter().max_by_key(|doc| doc.len());
+//!         longest.map(|doc| Self(doc)).ok_or_else(|| "no documents".to_string())
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { documents: vec!["a b c", "hello world"] });
+//!
+//! let (words, longest) = container.extract_parallel::<(WordCount, Longest)>().unwrap();
+//! assert_eq!(words.0, 5);
+//! assert_eq!(longest.0, "hello world");
+//!
+//! let empty = DIContainer::new(World { documents: vec![] });
+//! let error = empty.extract_parallel::<(WordCount, Longest)>().err();
+//! assert_eq!(error.as_deref(), Some("no documents"));
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+
+/// # Tuple resolving its members in parallel
+///
+/// Implemented for the tuples of `Send` members over a `Sync` World
+pub trait FromWorldParallel: FromWorld + Sized {
+    fn from_world_parallel(world: &Self::World<'_>) -> Result<Self, Self::Error>;
+}
+
+macro_rules! impl_tuple {
+    ($first_slot:ident:$first_name:ident, $($slot:ident:$name:ident),+) => {
+        impl<$first_name, $($name),*> FromWorldParallel for ($first_name, $($name),+)
+        where
+            $first_name: FromWorld + Send,
+            $first_name::Error: Send,
+            for<'a> $first_name::World<'a>: Sync,
+            $($name: for<'a> FromWorld<World<'a> = $first_name::World<'a>, Error = $first_name::Error> + Send),*
+        {
+            fn from_world_parallel(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+                let mut $first_slot = None;
+                $(let mut $slot = None;)+
+
+                rayon::scope(|scope| {
+                    let $first_slot = &mut $first_slot;
+                    scope.spawn(move |_| *$first_slot = Some(resolve::<$first_name>(world)));
+                    $(
+                        let $slot = &mut $slot;
+                        scope.spawn(move |_| *$slot = Some(resolve::<$name>(world)));
+                    )+
+                });
+
+                Ok((
+                    $first_slot.expect("resolved within the scope")?,
+                    $($slot.expect("resolved within the scope")?),+
+                ))
+            }
+        }
+    };
+}
+
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3, dep4:Dep4, dep5:Dep5, dep6:Dep6, dep7:Dep7, dep8:Dep8);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3, dep4:Dep4, dep5:Dep5, dep6:Dep6, dep7:Dep7);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3, dep4:Dep4, dep5:Dep5, dep6:Dep6);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3, dep4:Dep4, dep5:Dep5);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3, dep4:Dep4);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2, dep3:Dep3);
+impl_tuple!(dep0:Dep0, dep1:Dep1, dep2:Dep2);
+impl_tuple!(dep0:Dep0, dep1:Dep1);
+
+impl<World: Sync> DIContainer<World> {
+    /// Extracts the tuple `T`, resolving its members in parallel on the rayon thread pool
+    pub fn extract_parallel<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorldParallel<World<'a> = World>,
+    {
+        T::from_world_parallel(&self.world)
+    }
+}
@@ -0,0 +1,253 @@
+//! # Parsed World fields
+//!
+//! Configuration values are often strings in the World (`"30s"`, `"8080"`, `"info"`) parsed in
+//! every `from_world` reading them. [`Parsed<T, Field>`] parses a string field of the World
+//! through [`FromStr`], and [`ParsedDuration<Field>`] parses a duration, both failing with a
+//! [`ParseError`] naming the field.
+//!
+//! # Field selection
+//!
+//! The field is selected by a type implementing [`WorldField`], naming the World, the field
+//! and reading it, as const generic string parameters (`Parsed<u16, "port">`) are not supported
+//! by stable Rust. [`world_field!`](crate::world_field) declares these types for fields (or
+//! nested fields) implementing `AsRef<str>`, e.g. `String` or `&'static str`:
+//!
+//! ```ignore
+//! world_field! {
+//!     Port: World => port;
+//!     Timeout: World => http.timeout;
+//! }
+//!
+//! let port: Parsed<u16, Port> = container.extract()?;
+//! ```
+//!
+//! The name of the field in errors is the path written in the macro, `http.timeout`.
+//!
+//! # Durations
+//!
+//! [`ParsedDuration`] parses a single integer followed by a unit: `ns`, `us`, `ms`, `s`, `m`,
+//! `h` or `d`, as in `30s` or `500ms`. With the `humantime` feature it uses
+//! [`humantime::parse_duration`] instead, accepting combined values such as `1h 30m`.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer,
+//!     parsed::{Parsed, ParsedDuration},
+//!     world_field,
+//! };
+//! use std::time::Duration;
+//!
+//! struct Http {
+//!     timeout: String,
+//! }
+//!
+//! struct World {
+//!     port: &'static str,
+//!     http: Http,
+//! }
+//!
+//! world_field! {
+//!     Port: World => port;
+//!     Timeout: World => http.timeout;
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     port: "8080",
+//!     http: Http { timeout: "30s".to_string() },
+//! });
+//!
+//! let (port, timeout): (Parsed<u16, Port>, ParsedDuration<Timeout>) =
+//!     container.extract().unwrap();
+//! assert_eq!((*port, *timeout), (8080, Duration::from_secs(30)));
+//!
+//! let container = DIContainer::new(World {
+//!     port: "http",
+//!     http: Http { timeout: "soon".to_string() },
+//! });
+//!
+//! let error = container.extract::<ParsedDuration<Timeout>>().err().unwrap();
+//! assert_eq!(error.field, "http.timeout");
+//! ```
+
+use crate::FromWorld;
+use std::{marker::PhantomData, ops::Deref, str::FromStr, time::Duration};
+
+/// # String field of a World
+///
+/// Usually declared with [`world_field!`](crate::world_field)
+pub trait WorldField {
+    type World;
+
+    /// Name of the field, used in errors
+    const NAME: &'static str;
+
+    fn get(world: &Self::World) -> &str;
+}
+
+/// # Error parsing a World field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid value for `{}`: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// # World field parsed through [`FromStr`]
+pub struct Parsed<T, Field, Error = ParseError> {
+    value: T,
+    _marker: PhantomData<fn() -> (Field, Error)>,
+}
+
+impl<T, Field, Error> Parsed<T, Field, Error> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, Field, Error> Deref for Parsed<T, Field, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, Field, Error> FromWorld for Parsed<T, Field, Error>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+    Field: WorldField,
+    Error: From<ParseError>,
+{
+    type World<'a> = Field::World;
+    type Error = Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let value = Field::get(world)
+            .parse()
+            .map_err(|error: T::Err| ParseError {
+                field: Field::NAME,
+                message: error.to_string(),
+            })?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # World field parsed as a [`Duration`]
+pub struct ParsedDuration<Field, Error = ParseError> {
+    value: Duration,
+    _marker: PhantomData<fn() -> (Field, Error)>,
+}
+
+impl<Field, Error> ParsedDuration<Field, Error> {
+    pub fn into_inner(self) -> Duration {
+        self.value
+    }
+}
+
+impl<Field, Error> Deref for ParsedDuration<Field, Error> {
+    type Target = Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<Field, Error> FromWorld for ParsedDuration<Field, Error>
+where
+    Field: WorldField,
+    Error: From<ParseError>,
+{
+    type World<'a> = Field::World;
+    type Error = Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let value = parse_duration(Field::get(world)).map_err(|message| ParseError {
+            field: Field::NAME,
+            message,
+        })?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "humantime")]
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    humantime::parse_duration(value).map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "humantime"))]
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("missing unit in `{value}`"))?;
+    let (amount, unit) = value.split_at(split);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration `{value}`"))?;
+
+    match unit.trim_start() {
+        "ns" => Some(Duration::from_nanos(amount)),
+        "us" => Some(Duration::from_micros(amount)),
+        "ms" => Some(Duration::from_millis(amount)),
+        "s" => Some(Duration::from_secs(amount)),
+        "m" => amount.checked_mul(60).map(Duration::from_secs),
+        "h" => amount.checked_mul(60 * 60).map(Duration::from_secs),
+        "d" => amount.checked_mul(24 * 60 * 60).map(Duration::from_secs),
+        unit => {
+            return Err(format!(
+                "unknown unit `{unit}`, expected ns, us, ms, s, m, h or d"
+            ));
+        }
+    }
+    .ok_or_else(|| format!("duration `{value}` is too large"))
+}
+
+/// # Declares [`WorldField`] types
+///
+/// ```ignore
+/// world_field! {
+///     /// The port the server listens on
+///     pub Port: World => port;
+///     Timeout: World => http.timeout;
+/// }
+/// ```
+#[macro_export]
+macro_rules! world_field {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis $name:ident: $world:ty => $($field:ident).+;
+    )+) => {
+        $(
+            $(#[$attr])*
+            $vis struct $name;
+
+            impl $crate::parsed::WorldField for $name {
+                type World = $world;
+
+                const NAME: &'static str = ::core::stringify!($($field).+);
+
+                fn get(world: &Self::World) -> &str {
+                    ::core::convert::AsRef::<str>::as_ref(&world.$($field).+)
+                }
+            }
+        )+
+    };
+}
@@ -0,0 +1,266 @@
+//! # Initialization phases
+//!
+//! Some dependencies must only be resolved once others have done their work: repositories after
+//! the migrations ran, workers after the caches are warm. [`OrderedContainer<World, Phase>`]
+//! encodes this order in its type. Every dependency is declared in a phase, the container is in
+//! one phase at a time and [`OrderedContainer::extract`] only accepts dependencies declared in the
+//! current phase or a prior one. Extracting a repository while the container is still in the
+//! migration phase is a compile error.
+//!
+//! # Phase markers
+//!
+//! A phase is a marker type, [`phases!`](crate::phases) declares them in order with the
+//! dependencies of each one:
+//!
+//! ```ignore
+//! phases! {
+//!     Migrations { RunMigrations };
+//!     Repositories { UserRepository, OrderRepository };
+//! }
+//! ```
+//!
+//! The order is expressed with three traits, implemented by the macro:
+//!
+//! - [`InitialPhase`], the first phase, the only one [`DIContainer::ordered`] starts in
+//! - [`Follows<P>`], implemented by the phase directly after `P`, the only phase
+//!   [`OrderedContainer::advance`] moves to: phases can't be skipped
+//! - [`Reached<P>`], implemented by `P` and every phase after it
+//!
+//! and [`Phased`] declares the phase of a dependency. Phases and dependencies can also implement
+//! these traits by hand, e.g. for generic dependencies. [`OrderedContainer::extract`] requires
+//! [`AvailableIn<P>`] of the dependency, implemented for every [`Phased`] dependency whose phase
+//! the phase `P` of the container has [`Reached`].
+//!
+//! The phase only exists in the type: advancing consumes the container and returns it in the next
+//! phase, nothing is stored or checked at runtime. Tuples have no phase, extract the dependencies
+//! of an ordered container one at a time.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, phases};
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//!
+//! struct World {
+//!     migrated: AtomicBool,
+//! }
+//!
+//! struct RunMigrations;
+//!
+//! impl FromWorld for RunMigrations {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         world.migrated.store(true, Ordering::SeqCst);
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! struct UserRepository;
+//!
+//! impl FromWorld for UserRepository {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         match world.migrated.load(Ordering::SeqCst) {
+//!             true => Ok(Self),
+//!             false => Err("the users table doesn't exist".to_string()),
+//!         }
+//!     }
+//! }
+//!
+//! phases! {
+//!     Migrations { RunMigrations };
+//!     Repositories { UserRepository };
+//! }
+//!
+//! let container = DIContainer::new(World { migrated: AtomicBool::new(false) })
+//!     .ordered::<Migrations>();
+//! let _: RunMigrations = container.extract().unwrap();
+//!
+//! let container = container.advance::<Repositories>();
+//! let _: UserRepository = container.extract().unwrap();
+//! // dependencies of prior phases stay available
+//! let _: RunMigrations = container.extract().unwrap();
+//! ```
+//!
+//! A dependency of a later phase can't be extracted:
+//!
+//! ```compile_fail,E0277
+//! # use je_di::{DIContainer, FromWorld, phases};
+//! # struct World;
+//! # struct RunMigrations;
+//! # impl FromWorld for RunMigrations {
+//! #     type World<'a> = World;
+//! #     type Error = String;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//! #         Ok(Self)
+//! #     }
+//! # }
+//! # struct UserRepository;
+//! # impl FromWorld for UserRepository {
+//! #     type World<'a> = World;
+//! #     type Error = String;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//! #         Ok(Self)
+//! #     }
+//! # }
+//! phases! {
+//!     Migrations { RunMigrations };
+//!     Repositories { UserRepository };
+//! }
+//!
+//! let container = DIContainer::new(World).ordered::<Migrations>();
+//! let _: UserRepository = container.extract().unwrap();
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use core::marker::PhantomData;
+
+/// # Initialization phase marker
+pub trait Phase {}
+
+/// # The phase an ordered container starts in
+pub trait InitialPhase: Phase {}
+
+/// # The phase directly after `P`
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not the phase after `{P}`",
+    label = "phases are advanced one at a time",
+    note = "phases are ordered by their declaration in `phases!`"
+)]
+pub trait Follows<P: Phase>: Phase {}
+
+/// # `P` or a phase after it
+#[diagnostic::on_unimplemented(
+    message = "the container is in phase `{Self}`, before phase `{P}`",
+    label = "dependency of a later phase",
+    note = "advance the container to `{P}` before extracting it"
+)]
+pub trait Reached<P: Phase>: Phase {}
+
+/// # Dependency declared in an initialization phase
+///
+/// Usually implemented by [`phases!`](crate::phases)
+pub trait Phased {
+    type Phase: Phase;
+}
+
+/// # Dependency extractable in the phase `P`
+///
+/// The bound of [`OrderedContainer::extract`]. Bounding on `P: Reached<T::Phase>` directly lets
+/// rustc unify the phase of `T` with the only phase `P` has reached when there is a single one,
+/// reporting a type mismatch instead of the unreached phase: behind this trait the phase of `T`
+/// is normalized first.
+pub trait AvailableIn<P: Phase> {}
+
+impl<T: Phased, P: Reached<T::Phase>> AvailableIn<P> for T {}
+
+/// # Container extracting the dependencies of its phase and the prior ones
+///
+/// See the [module documentation](crate::phase)
+pub struct OrderedContainer<World, P> {
+    container: DIContainer<World>,
+    _phase: PhantomData<fn() -> P>,
+}
+
+impl<World, P: Phase> OrderedContainer<World, P> {
+    pub fn extract<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + AvailableIn<P>,
+    {
+        resolve::<T>(&self.container.world)
+    }
+
+    /// Moves the container to the next phase
+    pub fn advance<Next: Follows<P>>(self) -> OrderedContainer<World, Next> {
+        OrderedContainer {
+            container: self.container,
+            _phase: PhantomData,
+        }
+    }
+
+    /// The underlying container, extracting any dependency regardless of the phases
+    pub fn into_inner(self) -> DIContainer<World> {
+        self.container
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World, P: Phase> OrderedContainer<World, P> {
+    pub async fn extract_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World> + AvailableIn<P>,
+    {
+        crate::resolve::resolve_async::<T>(&self.container.world).await
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Starts the ordered initialization in its first phase, see [`OrderedContainer`]
+    pub fn ordered<P: InitialPhase>(self) -> OrderedContainer<World, P> {
+        OrderedContainer {
+            container: self,
+            _phase: PhantomData,
+        }
+    }
+}
+
+/// # Declares initialization phases in order
+///
+/// ```ignore
+/// phases! {
+///     /// Schema migrations
+///     pub Migrations { RunMigrations };
+///     pub Repositories { UserRepository, OrderRepository };
+///     pub Services;
+/// }
+/// ```
+///
+/// See the [module documentation](crate::phase)
+#[macro_export]
+macro_rules! phases {
+    (@initial $first:ident $($rest:ident)*) => {
+        impl $crate::phase::InitialPhase for $first {}
+    };
+
+    (@reached [$($prior:ident)*]) => {};
+
+    (@reached [$($prior:ident)*] $current:ident $($rest:ident)*) => {
+        impl $crate::phase::Reached<$current> for $current {}
+        $(impl $crate::phase::Reached<$prior> for $current {})*
+        $crate::phases!(@reached [$($prior)* $current] $($rest)*);
+    };
+
+    (@follows $last:ident) => {};
+
+    (@follows $previous:ident $next:ident $($rest:ident)*) => {
+        impl $crate::phase::Follows<$previous> for $next {}
+        $crate::phases!(@follows $next $($rest)*);
+    };
+
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis $phase:ident $({ $($dependency:ty),* $(,)? })?;
+    )+) => {
+        $(
+            $(#[$attr])*
+            $vis struct $phase;
+
+            impl $crate::phase::Phase for $phase {}
+
+            $($(
+                impl $crate::phase::Phased for $dependency {
+                    type Phase = $phase;
+                }
+            )*)?
+        )+
+
+        $crate::phases!(@initial $($phase)+);
+        $crate::phases!(@follows $($phase)+);
+        $crate::phases!(@reached [] $($phase)+);
+    };
+
+}
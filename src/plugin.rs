@@ -0,0 +1,596 @@
+//! # Plugins
+//!
+//! A plugin is a dependency implementing a trait, registered from anywhere in the program with
+//! [`register_plugin!`](crate::register_plugin) instead of being listed by the code extracting
+//! it. [`DIContainer::extract_all`] resolves every plugin registered for a trait, e.g. the
+//! middlewares of a pipeline contributed by several modules:
+//!
+//! ```ignore
+//! register_plugin!(Auth as dyn Middleware, priority = -10);
+//! register_plugin!(Compression as dyn Middleware);
+//!
+//! let middlewares: Vec<Box<dyn Middleware>> = container.extract_all::<dyn Middleware>()?;
+//! ```
+//!
+//! # Registration
+//!
+//! `register_plugin!(Type as dyn Trait)` registers `Type` as a plugin of `Trait`, at module
+//! level, in any module of any crate linked into the program. The registrations are collected
+//! at startup with [`inventory`], nothing has to call them. A type registered for several traits
+//! is a plugin of each of them.
+//!
+//! The plugin resolves through [`FromWorld`], its error must convert into a [`BoxError`]. The
+//! registry is shared by every container: a container only resolves the plugins whose World is
+//! its own, the plugins of other Worlds are skipped.
+//!
+//! The macro expands to an [`inventory::submit!`] of a [`Plugin`] entry, a static holding the
+//! [`TypeId`] of the trait object, the [`TypeId`]s of the plugin and of its World, its priority,
+//! its requirements, its registration site and a function resolving it from a World passed as
+//! [`Any`]. [`inventory`] gathers the entries of every linked crate before `main`, the order of
+//! the entries is unspecified. An entry is only collected if the object file registering it is
+//! linked: a plugin registered in a crate nothing else refers to may be discarded by the linker.
+//!
+//! # Priority
+//!
+//! `register_plugin!(Type as dyn Trait, priority = N)` sets the priority, an `i32`, `0` when it
+//! is not given. [`DIContainer::extract_all`] returns the plugins by ascending priority: a
+//! plugin of priority `-10` comes before the plugins of the default priority, a plugin of
+//! priority `10` after them.
+//!
+//! The order of plugins sharing a priority is stable, the same from one run or build to the
+//! next. [`inventory`] doesn't keep the order in which registrations are linked, ties are broken
+//! by registration site instead: by module path, then by line. Plugins of equal priority
+//! registered in the same module keep the order in which they are registered, those of
+//! different modules are ordered by the path of their module.
+//!
+//! # Initialization order
+//!
+//! Plugins can need other plugins to be resolved first, e.g. the `Init` plugins of a program
+//! running migrations only once the database is set up.
+//! `register_plugin!(Type as dyn Trait, requires = [Other, ..])` declares the plugins `Type`
+//! requires, by their type: a requirement is the [`TypeId`] of a plugin type registered for
+//! the same trait and World, named in errors as written in the registration. The priority, when
+//! given, comes before the requirements.
+//!
+//! [`DIContainer::extract_all_ordered`] sorts the plugins topologically before resolving any of
+//! them, with Kahn's algorithm:
+//!
+//! 1. the plugins of the trait and World are ordered by priority then registration site, as for
+//!    [`DIContainer::extract_all`]
+//! 2. a requirement that isn't among them fails with [`PluginError::Missing`]
+//! 3. a plugin is ready once every plugin it requires is placed, the ready plugin coming first
+//!    in the order of step 1 is placed next: the priority only breaks the ties left by the
+//!    requirements
+//! 4. plugins left unplaced when none is ready require each other, the requirements among them
+//!    are followed until one repeats and the loop fails with [`PluginError::Cycle`], naming it
+//!
+//! The plugins are then resolved in that order, the requirements of a plugin being resolved
+//! before it. [`DIContainer::extract_all`] ignores the requirements.
+//!
+//! ```
+//! use je_di::{DIContainer, FromWorld, plugin::PluginError, register_plugin};
+//!
+//! struct World;
+//!
+//! trait Init {
+//!     fn name(&self) -> &'static str;
+//! }
+//!
+//! macro_rules! init {
+//!     ($($name:ident),+) => {
+//!         $(
+//!             struct $name;
+//!
+//!             impl FromWorld for $name {
+//!                 type World<'a> = World;
+//!                 type Error = String;
+//!
+//!                 fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!                     Ok(Self)
+//!                 }
+//!             }
+//!
+//!             impl Init for $name {
+//!                 fn name(&self) -> &'static str {
+//!                     stringify!($name)
+//!                 }
+//!             }
+//!         )+
+//!     };
+//! }
+//!
+//! init!(Database, Migrations, Metrics, Server);
+//!
+//! register_plugin!(Server as dyn Init, priority = -10, requires = [Migrations, Metrics]);
+//! register_plugin!(Migrations as dyn Init, requires = [Database]);
+//! register_plugin!(Metrics as dyn Init, priority = -5);
+//! register_plugin!(Database as dyn Init);
+//!
+//! let container = DIContainer::new(World);
+//! let inits = container.extract_all_ordered::<dyn Init>().unwrap();
+//!
+//! let names = inits.iter().map(|init| init.name()).collect::<Vec<_>>();
+//! // `Server` comes first by priority but requires two plugins, `Metrics` is the first ready
+//! assert_eq!(names, ["Metrics", "Database", "Migrations", "Server"]);
+//!
+//! trait Job {}
+//!
+//! struct Fetch;
+//! struct Parse;
+//! # impl FromWorld for Fetch {
+//! #     type World<'a> = World;
+//! #     type Error = String;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> { Ok(Self) }
+//! # }
+//! # impl FromWorld for Parse {
+//! #     type World<'a> = World;
+//! #     type Error = String;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> { Ok(Self) }
+//! # }
+//! impl Job for Fetch {}
+//! impl Job for Parse {}
+//!
+//! register_plugin!(Fetch as dyn Job, requires = [Parse]);
+//! register_plugin!(Parse as dyn Job, requires = [Fetch]);
+//!
+//! let error = container.extract_all_ordered::<dyn Job>().err().unwrap();
+//! assert!(matches!(error, PluginError::Cycle(_)));
+//! assert_eq!(error.to_string(), "plugins require each other: Fetch -> Parse -> Fetch");
+//!
+//! trait Report {}
+//!
+//! struct Weekly;
+//! # impl FromWorld for Weekly {
+//! #     type World<'a> = World;
+//! #     type Error = String;
+//! #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> { Ok(Self) }
+//! # }
+//! impl Report for Weekly {}
+//!
+//! // `Database` is an `Init` plugin, not a `Report` one
+//! register_plugin!(Weekly as dyn Report, requires = [Database]);
+//!
+//! let error = container.extract_all_ordered::<dyn Report>().err().unwrap();
+//! assert!(matches!(error, PluginError::Missing { plugin: "Weekly", required: "Database" }));
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, register_plugin};
+//!
+//! struct World {
+//!     compression_level: u32,
+//! }
+//!
+//! trait Middleware {
+//!     fn name(&self) -> String;
+//! }
+//!
+//! struct Auth;
+//!
+//! impl FromWorld for Auth {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl Middleware for Auth {
+//!     fn name(&self) -> String {
+//!         "auth".to_string()
+//!     }
+//! }
+//!
+//! struct Compression(u32);
+//!
+//! impl FromWorld for Compression {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.compression_level))
+//!     }
+//! }
+//!
+//! impl Middleware for Compression {
+//!     fn name(&self) -> String {
+//!         format!("compression {}", self.0)
+//!     }
+//! }
+//!
+//! struct Logging;
+//!
+//! impl FromWorld for Logging {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl Middleware for Logging {
+//!     fn name(&self) -> String {
+//!         "logging".to_string()
+//!     }
+//! }
+//!
+//! register_plugin!(Compression as dyn Middleware);
+//! register_plugin!(Logging as dyn Middleware);
+//! register_plugin!(Auth as dyn Middleware, priority = -10);
+//!
+//! let container = DIContainer::new(World { compression_level: 6 });
+//! let middlewares = container.extract_all::<dyn Middleware>().unwrap();
+//!
+//! let names = middlewares.iter().map(|middleware| middleware.name()).collect::<Vec<_>>();
+//! // by priority, then in registration order
+//! assert_eq!(names, ["auth", "compression 6", "logging"]);
+//! ```
+
+use crate::{DIContainer, FromWorld, boxed::BoxError, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+type Build = fn(&dyn Any) -> Option<Result<Box<dyn Any>, BoxError>>;
+
+/// A plugin required by another, by the [`TypeId`] and the name of its type
+type Requirement = (fn() -> TypeId, &'static str);
+
+/// # Plugin registered with [`register_plugin!`](crate::register_plugin)
+pub struct Plugin {
+    interface: fn() -> TypeId,
+    id: fn() -> TypeId,
+    world: fn() -> TypeId,
+    name: &'static str,
+    priority: i32,
+    requires: &'static [Requirement],
+    module: &'static str,
+    line: u32,
+    build: Build,
+}
+
+inventory::collect!(Plugin);
+
+impl Plugin {
+    #[doc(hidden)]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        interface: fn() -> TypeId,
+        id: fn() -> TypeId,
+        world: fn() -> TypeId,
+        name: &'static str,
+        priority: i32,
+        requires: &'static [Requirement],
+        module: &'static str,
+        line: u32,
+        build: Build,
+    ) -> Self {
+        Self {
+            interface,
+            id,
+            world,
+            name,
+            priority,
+            requires,
+            module,
+            line,
+            build,
+        }
+    }
+
+    /// Name of the plugin type, as written in the registration
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Names of the plugins required by this one, as written in the registration
+    pub fn requires(&self) -> impl Iterator<Item = &'static str> {
+        self.requires.iter().map(|(_, name)| *name)
+    }
+
+    /// Position of the plugin among those of equal priority
+    fn site(&self) -> (&'static str, u32) {
+        (self.module, self.line)
+    }
+}
+
+/// Resolves the plugin `P` from `world` when it is its World, as a `Box<Interface>`
+#[doc(hidden)]
+pub fn build<P, World, Interface>(
+    world: &dyn Any,
+    upcast: fn(P) -> Box<Interface>,
+) -> Option<Result<Box<dyn Any>, BoxError>>
+where
+    P: for<'a> FromWorld<World<'a> = World>,
+    P::Error: Into<BoxError>,
+    World: 'static,
+    Interface: ?Sized + 'static,
+{
+    let world = world.downcast_ref::<World>()?;
+
+    Some(
+        resolve::<P>(world)
+            .map(|plugin| Box::new(upcast(plugin)) as Box<dyn Any>)
+            .map_err(Into::into),
+    )
+}
+
+/// [`TypeId`] of the World of the plugin `P`
+#[doc(hidden)]
+pub fn world<P, World>() -> TypeId
+where
+    P: for<'a> FromWorld<World<'a> = World>,
+    World: 'static,
+{
+    TypeId::of::<World>()
+}
+
+/// The plugins registered for `Interface` over `World`, by priority then registration site
+fn registered<Interface: ?Sized + 'static, World: 'static>() -> Vec<&'static Plugin> {
+    let mut plugins = inventory::iter::<Plugin>
+        .into_iter()
+        .filter(|plugin| (plugin.interface)() == TypeId::of::<Interface>())
+        .filter(|plugin| (plugin.world)() == TypeId::of::<World>())
+        .collect::<Vec<_>>();
+    plugins.sort_by_key(|plugin| (plugin.priority, plugin.site()));
+
+    plugins
+}
+
+/// Orders `plugins` so that every plugin comes after the plugins it requires, keeping the
+/// given order among the plugins ready at the same time
+fn sort_by_requirements(
+    plugins: Vec<&'static Plugin>,
+) -> Result<Vec<&'static Plugin>, PluginError> {
+    let mut positions = HashMap::new();
+    for (position, plugin) in plugins.iter().enumerate().rev() {
+        // a plugin registered twice is required through its first registration
+        positions.insert((plugin.id)(), position);
+    }
+
+    // positions of the plugins required by each plugin
+    let mut requirements = Vec::with_capacity(plugins.len());
+    for plugin in &plugins {
+        let required = plugin
+            .requires
+            .iter()
+            .map(|(id, name)| {
+                positions.get(&id()).copied().ok_or(PluginError::Missing {
+                    plugin: plugin.name,
+                    required: name,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        requirements.push(required);
+    }
+
+    let mut pending = requirements.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut dependents = vec![Vec::new(); plugins.len()];
+    for (position, required) in requirements.iter().enumerate() {
+        for &required in required {
+            dependents[required].push(position);
+        }
+    }
+
+    let mut ready = (0..plugins.len())
+        .filter(|&position| pending[position] == 0)
+        .collect::<BTreeSet<_>>();
+    let mut sorted = Vec::with_capacity(plugins.len());
+
+    while let Some(position) = ready.pop_first() {
+        sorted.push(plugins[position]);
+        for &dependent in &dependents[position] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    if sorted.len() < plugins.len() {
+        return Err(PluginError::Cycle(find_cycle(
+            &plugins,
+            &requirements,
+            &pending,
+        )));
+    }
+
+    Ok(sorted)
+}
+
+/// Follows the unplaced requirements from the first unplaced plugin until one repeats
+///
+/// Every unplaced plugin requires at least one other unplaced plugin, the walk never ends
+/// before finding a cycle.
+fn find_cycle(
+    plugins: &[&'static Plugin],
+    requirements: &[Vec<usize>],
+    pending: &[usize],
+) -> Vec<&'static str> {
+    let unplaced = |position: &usize| pending[*position] > 0;
+
+    let mut path: Vec<usize> = Vec::new();
+    let mut current = (0..plugins.len())
+        .find(unplaced)
+        .expect("a cycle leaves plugins unplaced");
+
+    loop {
+        if let Some(start) = path.iter().position(|&position| position == current) {
+            return path[start..]
+                .iter()
+                .map(|&position| plugins[position].name)
+                .collect();
+        }
+
+        path.push(current);
+        current = *requirements[current]
+            .iter()
+            .find(|position| unplaced(position))
+            .expect("an unplaced plugin requires an unplaced plugin");
+    }
+}
+
+/// # Error resolving the plugins of a trait
+#[derive(Debug)]
+pub enum PluginError {
+    /// The plugin failed to resolve
+    Resolve {
+        plugin: &'static str,
+        source: BoxError,
+    },
+    /// The plugin requires a plugin that isn't registered for the same trait and World
+    Missing {
+        plugin: &'static str,
+        required: &'static str,
+    },
+    /// The plugins require each other, each requiring the next and the last the first
+    Cycle(Vec<&'static str>),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Resolve { plugin, source } => {
+                write!(f, "plugin {plugin} failed to resolve: {source}")
+            }
+            PluginError::Missing { plugin, required } => {
+                write!(
+                    f,
+                    "plugin {plugin} requires {required}, which isn't registered"
+                )
+            }
+            PluginError::Cycle(cycle) => {
+                write!(f, "plugins require each other: ")?;
+                for plugin in cycle {
+                    write!(f, "{plugin} -> ")?;
+                }
+                write!(f, "{}", cycle.first().copied().unwrap_or_default())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PluginError::Resolve { source, .. } => Some(&**source),
+            PluginError::Missing { .. } | PluginError::Cycle(_) => None,
+        }
+    }
+}
+
+/// Resolves `plugins` in order
+fn build_all<Interface: ?Sized + 'static>(
+    world: &dyn Any,
+    plugins: Vec<&'static Plugin>,
+) -> Result<Vec<Box<Interface>>, PluginError> {
+    let mut built = Vec::with_capacity(plugins.len());
+
+    for plugin in plugins {
+        let Some(resolved) = (plugin.build)(world) else {
+            continue;
+        };
+        let resolved = resolved.map_err(|source| PluginError::Resolve {
+            plugin: plugin.name,
+            source,
+        })?;
+
+        let resolved = resolved
+            .downcast::<Box<Interface>>()
+            .expect("plugins are registered with their interface");
+        built.push(*resolved);
+    }
+
+    Ok(built)
+}
+
+impl<World: 'static> DIContainer<World> {
+    /// Resolves every plugin registered for `Interface` over this World, by priority
+    pub fn extract_all<Interface: ?Sized + 'static>(
+        &self,
+    ) -> Result<Vec<Box<Interface>>, PluginError> {
+        build_all(&self.world, registered::<Interface, World>())
+    }
+
+    /// Resolves every plugin registered for `Interface` over this World, each after the plugins
+    /// it requires
+    ///
+    /// See the [module documentation](crate::plugin#initialization-order)
+    pub fn extract_all_ordered<Interface: ?Sized + 'static>(
+        &self,
+    ) -> Result<Vec<Box<Interface>>, PluginError> {
+        let plugins = sort_by_requirements(registered::<Interface, World>())?;
+
+        build_all(&self.world, plugins)
+    }
+}
+
+/// # Registers a plugin of a trait
+///
+/// ```ignore
+/// register_plugin!(Type as dyn Trait);
+/// register_plugin!(Type as dyn Trait, priority = 10);
+/// register_plugin!(Type as dyn Trait, requires = [Other]);
+/// register_plugin!(Type as dyn Trait, priority = 10, requires = [Other, Another]);
+/// ```
+///
+/// See the [module documentation](crate::plugin)
+#[macro_export]
+macro_rules! register_plugin {
+    (
+        $plugin:ty as dyn $interface:path
+        $(, priority = $priority:expr)?
+        $(, requires = [$($required:ty),* $(,)?])?
+        $(,)?
+    ) => {
+        const _: () = {
+            const REQUIRES: &[(fn() -> ::core::any::TypeId, &str)] = &[
+                $($((::core::any::TypeId::of::<$required>, ::core::stringify!($required))),*)?
+            ];
+
+            fn build(
+                world: &dyn ::core::any::Any,
+            ) -> ::core::option::Option<
+                ::core::result::Result<
+                    $crate::__private::Box<dyn ::core::any::Any>,
+                    $crate::boxed::BoxError,
+                >,
+            > {
+                $crate::plugin::build::<$plugin, _, dyn $interface>(world, |plugin| {
+                    $crate::__private::Box::new(plugin)
+                })
+            }
+
+            $crate::__private::inventory::submit! {
+                $crate::plugin::Plugin::new(
+                    ::core::any::TypeId::of::<dyn $interface>,
+                    ::core::any::TypeId::of::<$plugin>,
+                    $crate::plugin::world::<$plugin, _>,
+                    ::core::stringify!($plugin),
+                    $crate::register_plugin!(@priority $($priority)?),
+                    REQUIRES,
+                    ::core::module_path!(),
+                    ::core::line!(),
+                    build,
+                )
+            }
+        };
+    };
+
+    (@priority) => {
+        0
+    };
+
+    (@priority $priority:expr) => {
+        $priority
+    };
+}
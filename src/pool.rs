@@ -0,0 +1,162 @@
+//! # Pooled dependencies
+//!
+//! Dependencies are constructed from `&World`, construction that needs to mutate the World (pop
+//! from a pool, increment a counter) goes through interior mutability. [`Pool<T>`] is a World
+//! field handing out items that return to the pool when dropped.
+//!
+//! The World exposes the pool through [`AsRef<Pool<T>>`], [`Pooled<T, World>`] then resolves by
+//! checking out an item. A hand written [`FromWorld`] implementation can call
+//! [`Pool::checkout`] as well, wrapping the guard in its own type.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, pool::{Pool, Pooled}};
+//!
+//! struct Connection(u32);
+//!
+//! struct World {
+//!     connections: Pool<Connection>,
+//! }
+//!
+//! impl AsRef<Pool<Connection>> for World {
+//!     fn as_ref(&self) -> &Pool<Connection> {
+//!         &self.connections
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     connections: Pool::new([Connection(1)]),
+//! });
+//!
+//! {
+//!     let connection: Pooled<Connection, World> = container.extract().unwrap();
+//!     assert_eq!(connection.0, 1);
+//!     assert!(container.extract::<Pooled<Connection, World>>().is_err());
+//! }
+//!
+//! // returned on drop
+//! assert!(container.extract::<Pooled<Connection, World>>().is_ok());
+//! ```
+
+use crate::FromWorld;
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// # Pool of items shared through the World
+///
+/// Clones share the same items
+pub struct Pool<T> {
+    items: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl<T> Pool<T> {
+    pub fn new(items: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            items: Arc::new(Mutex::new(items.into_iter().collect())),
+        }
+    }
+
+    /// Takes an item out of the pool, the item returns to the pool when the guard is dropped
+    pub fn checkout<World, Error>(&self) -> Result<Pooled<T, World, Error>, PoolExhausted> {
+        let item = self.lock().pop().ok_or(PoolExhausted {
+            type_name: std::any::type_name::<T>(),
+        })?;
+
+        Ok(Pooled {
+            item: Some(item),
+            pool: self.clone(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Adds an item to the pool
+    pub fn checkin(&self, item: T) {
+        self.lock().push(item);
+    }
+
+    /// Number of items currently available
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.items.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// # Item checked out of a [`Pool`]
+///
+/// Returns the item to the pool on drop.
+///
+/// See the [error of the dependency](crate::FromDependency#error-of-the-dependency)
+pub struct Pooled<T, World = (), Error = PoolExhausted> {
+    item: Option<T>,
+    pool: Pool<T>,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> Deref for Pooled<T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.item.as_ref().expect("item is present until drop")
+    }
+}
+
+impl<T, World, Error> DerefMut for Pooled<T, World, Error> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.item.as_mut().expect("item is present until drop")
+    }
+}
+
+impl<T, World, Error> Drop for Pooled<T, World, Error> {
+    fn drop(&mut self) {
+        if let Some(item) = self.item.take() {
+            self.pool.checkin(item);
+        }
+    }
+}
+
+impl<T, W, E> FromWorld for Pooled<T, W, E>
+where
+    W: AsRef<Pool<T>>,
+    E: From<PoolExhausted>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(world.as_ref().checkout()?)
+    }
+}
+
+/// # Error returned when a [`Pool`] has no available item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolExhausted {
+    pub type_name: &'static str,
+}
+
+impl std::fmt::Display for PoolExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no {} available in the pool", self.type_name)
+    }
+}
+
+impl std::error::Error for PoolExhausted {}
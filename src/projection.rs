@@ -0,0 +1,176 @@
+//! # World projection
+//!
+//! A container over a large World can serve dependencies declared over a smaller one, as long as
+//! the smaller World can be built from the larger one through [`FromRef`], like axum substates.
+//!
+//! - [`DIContainer::project`] builds a container over the smaller World
+//! - [`Projected<T, World>`] resolves `T` from the projection of `World`, it can be extracted from
+//!   the large container and depended upon by dependencies of the large World
+//!
+//! With the `derive` feature, `#[derive(SubWorld)]` on the large World generates the
+//! [`FromRef`] implementations of the types of its fields.
+//!
+//! # Coherence
+//!
+//! A blanket `impl<T> FromWorld for T` bridging every World to its projections would overlap
+//! with the [`FromDependency`](crate::FromDependency) blanket implementation, and a type can only
+//! have one World: [`Projected`] carries the large World type instead.
+//!
+//! # Cost
+//!
+//! The projection is built on every resolution: [`FromRef`] usually clones a part of the World,
+//! keep the projected Worlds cheap to clone (`Arc`s, small configuration structs) or
+//! project once with [`DIContainer::project`] and extract from the projected container.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, projection::{FromRef, Projected}};
+//!
+//! struct App {
+//!     database: DatabaseConfig,
+//!     port: u16,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct DatabaseConfig {
+//!     url: String,
+//! }
+//!
+//! impl FromRef<App> for DatabaseConfig {
+//!     fn from_ref(app: &App) -> Self {
+//!         app.database.clone()
+//!     }
+//! }
+//!
+//! struct Database(String);
+//!
+//! impl FromWorld for Database {
+//!     type World<'a> = DatabaseConfig;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.url.clone()))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(App {
+//!     database: DatabaseConfig { url: "postgres://localhost".to_string() },
+//!     port: 8080,
+//! });
+//!
+//! let database: Projected<Database, App> = container.extract().unwrap();
+//! assert_eq!(database.0, "postgres://localhost");
+//!
+//! let database: Database = container.project::<DatabaseConfig>().extract().unwrap();
+//! assert_eq!(database.0, "postgres://localhost");
+//! ```
+//!
+//! Deriving the projections of the fields:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use je_di::{
+//!     DIContainer, FromWorld, SubWorld,
+//!     projection::{FromRef, Projected},
+//! };
+//!
+//! #[derive(Clone)]
+//! struct DatabaseConfig {
+//!     url: String,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct MailerConfig {
+//!     sender: String,
+//! }
+//!
+//! #[derive(SubWorld)]
+//! struct App {
+//!     database: DatabaseConfig,
+//!     mailer: MailerConfig,
+//!     #[subworld(skip)]
+//!     port: u16,
+//! }
+//!
+//! struct Mailer(String);
+//!
+//! impl FromWorld for Mailer {
+//!     type World<'a> = MailerConfig;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.sender.clone()))
+//!     }
+//! }
+//!
+//! let app = App {
+//!     database: DatabaseConfig { url: "postgres://localhost".to_string() },
+//!     mailer: MailerConfig { sender: "noreply@example.com".to_string() },
+//!     port: 8080,
+//! };
+//! assert_eq!(DatabaseConfig::from_ref(&app).url, "postgres://localhost");
+//!
+//! let container = DIContainer::new(app);
+//! let mailer: Projected<Mailer, App> = container.extract().unwrap();
+//! assert_eq!(mailer.0, "noreply@example.com");
+//! # }
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use core::{marker::PhantomData, ops::Deref};
+
+/// # Builds `Self` from a reference to `T`
+pub trait FromRef<T> {
+    fn from_ref(input: &T) -> Self;
+}
+
+impl<T: Clone> FromRef<T> for T {
+    fn from_ref(input: &T) -> Self {
+        input.clone()
+    }
+}
+
+/// # `T` resolved from the projection of `World` into the World of `T`
+pub struct Projected<T, World> {
+    pub value: T,
+    _marker: PhantomData<fn() -> World>,
+}
+
+impl<T, World> Projected<T, World> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, World> Deref for Projected<T, World> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, W> FromWorld for Projected<T, W>
+where
+    T: FromWorld,
+    for<'a> T::World<'a>: FromRef<W>,
+{
+    type World<'a> = W;
+    type Error = T::Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let projection = <T::World<'_> as FromRef<W>>::from_ref(world);
+
+        Ok(Self {
+            value: resolve::<T>(&projection)?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Builds a container over the projection of the World into `Small`
+    pub fn project<Small: FromRef<World>>(&self) -> DIContainer<Small> {
+        DIContainer::new(Small::from_ref(&self.world))
+    }
+}
@@ -0,0 +1,101 @@
+//! # World providers
+//!
+//! For dependencies that are a plain field access, the relationship can be inverted: instead of
+//! a [`FromWorld`] implementation per dependency, the World implements [`Provider<T>`] and
+//! dependencies are resolved through [`Provided<T, World>`].
+//!
+//! # Coherence
+//!
+//! A blanket `impl<T> FromWorld for T where World: Provider<T>` would overlap with the
+//! [`FromDependency`](crate::FromDependency) blanket implementation, and [`FromWorld::World`]
+//! must be determined by the dependency type, not by the World. The [`Provided`] wrapper
+//! carries the World type and never conflicts with user [`FromWorld`] implementations: a type
+//! can both be provided by the World and implement [`FromWorld`] itself.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromDependency, Provided, Provider};
+//!
+//! struct World {
+//!     username: String,
+//! }
+//!
+//! impl Provider<String> for World {
+//!     fn provide(&self) -> String {
+//!         self.username.clone()
+//!     }
+//! }
+//!
+//! struct Greeter(String);
+//!
+//! impl FromDependency for Greeter {
+//!     type World<'a> = World;
+//!     type Error = std::convert::Infallible;
+//!     type Dependency = Provided<String, World>;
+//!
+//!     fn from_dependency(
+//!         _world: &Self::World<'_>,
+//!         username: &Self::Dependency,
+//!     ) -> Result<Self, Self::Error> {
+//!         Ok(Self(format!("hello {}", username.value)))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { username: "je-di".to_string() });
+//!
+//! let greeter: Greeter = container.extract().unwrap();
+//! assert_eq!(greeter.0, "hello je-di");
+//! assert_eq!(container.provide::<String>(), "je-di");
+//! ```
+
+use crate::{DIContainer, FromWorld};
+use core::{convert::Infallible, marker::PhantomData};
+
+/// # Provides a value from the World
+pub trait Provider<T> {
+    fn provide(&self) -> T;
+}
+
+/// # Dependency resolved through [`Provider`]
+pub struct Provided<T, World, Error = Infallible> {
+    pub value: T,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> Provided<T, World, Error> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, World, Error> core::ops::Deref for Provided<T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, W, E> FromWorld for Provided<T, W, E>
+where
+    W: Provider<T>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: world.provide(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<World> DIContainer<World> {
+    pub fn provide<T>(&self) -> T
+    where
+        World: Provider<T>,
+    {
+        self.world.provide()
+    }
+}
@@ -0,0 +1,182 @@
+//! # Racing providers
+//!
+//! For fallback chains where any provider will do, [`DIContainer::extract_race`] resolves every
+//! element of a tuple concurrently and returns the first one that succeeds, wrapped in the
+//! matching `EitherN` variant.
+//!
+//! # Cancellation
+//!
+//! As soon as a provider succeeds the others are dropped: their resolution stops at the await
+//! point they were suspended on, and anything they started but didn't finish is abandoned.
+//! Providers taking part in a race should be cancel safe.
+//!
+//! When every provider fails, the errors are returned in declaration order.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromAsyncWorld, async_trait, race::Either3};
+//! use std::{
+//!     sync::{
+//!         Arc,
+//!         atomic::{AtomicBool, Ordering},
+//!     },
+//!     time::Duration,
+//! };
+//!
+//! struct World {
+//!     primary_dropped: Arc<AtomicBool>,
+//! }
+//!
+//! struct LocalCache;
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for LocalCache {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         Err("cache miss".to_string())
+//!     }
+//! }
+//!
+//! struct Replica(&'static str);
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Replica {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         tokio::time::sleep(Duration::from_millis(10)).await;
+//!         Ok(Self("replica"))
+//!     }
+//! }
+//!
+//! /// Flags the resolution of the primary as dropped
+//! struct Abandoned(Arc<AtomicBool>);
+//!
+//! impl Drop for Abandoned {
+//!     fn drop(&mut self) {
+//!         self.0.store(true, Ordering::SeqCst);
+//!     }
+//! }
+//!
+//! struct Primary;
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Primary {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         let _abandoned = Abandoned(world.primary_dropped.clone());
+//!         tokio::time::sleep(Duration::from_secs(60)).await;
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let primary_dropped = Arc::new(AtomicBool::new(false));
+//! let container = DIContainer::new(World { primary_dropped: primary_dropped.clone() });
+//!
+//! // the cache fails, the replica is the first to succeed
+//! match container.extract_race::<(LocalCache, Replica, Primary)>().await {
+//!     Ok(Either3::Second(replica)) => assert_eq!(replica.0, "replica"),
+//!     _ => unreachable!("the replica resolves first"),
+//! }
+//!
+//! // the primary still in flight was dropped, without waiting for it
+//! assert!(primary_dropped.load(Ordering::SeqCst));
+//!
+//! // when every provider fails, the errors are in declaration order
+//! let errors = container.extract_race::<(LocalCache, LocalCache)>().await.err();
+//! assert_eq!(errors, Some(vec!["cache miss".to_string(), "cache miss".to_string()]));
+//! # });
+//! ```
+
+use crate::{DIContainer, FromAsyncWorld, resolve::resolve_async};
+use futures::{StreamExt, stream::FuturesUnordered};
+use std::{future::Future, pin::Pin};
+
+type RaceFuture<'a, T, E> = Pin<Box<dyn Future<Output = (usize, Result<T, E>)> + Send + 'a>>;
+
+/// # Tuple of providers resolved concurrently
+pub trait Race {
+    type Output;
+    type Error;
+    type World<'a>;
+
+    fn race<'a>(
+        world: &'a Self::World<'a>,
+    ) -> impl Future<Output = Result<Self::Output, Vec<Self::Error>>> + Send + 'a;
+}
+
+async fn first_ok<'a, T, E>(
+    pending: FuturesUnordered<RaceFuture<'a, T, E>>,
+) -> Result<T, Vec<E>> {
+    let mut pending = pending;
+    let mut errors = Vec::new();
+
+    while let Some((index, result)) = pending.next().await {
+        match result {
+            Ok(value) => return Ok(value),
+            Err(error) => errors.push((index, error)),
+        }
+    }
+
+    errors.sort_by_key(|(index, _)| *index);
+    Err(errors.into_iter().map(|(_, error)| error).collect())
+}
+
+macro_rules! impl_race {
+    ($either:ident, $first_n:tt:$first_name:ident:$first_variant:ident, $($n:tt:$name:ident:$variant:ident),+) => {
+        /// Output of a race between providers, holding the one that resolved first
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $either<$first_name, $($name),+> {
+            $first_variant($first_name),
+            $($variant($name)),+
+        }
+
+        impl<$first_name, $($name),+> Race for ($first_name, $($name),+)
+        where
+            $first_name: FromAsyncWorld + Send,
+            $($name: Send + for<'a> FromAsyncWorld<World<'a> = $first_name::World<'a>, Error = $first_name::Error>),+
+        {
+            type Output = $either<$first_name, $($name),+>;
+            type Error = $first_name::Error;
+            type World<'a> = $first_name::World<'a>;
+
+            fn race<'a>(
+                world: &'a Self::World<'a>,
+            ) -> impl Future<Output = Result<Self::Output, Vec<Self::Error>>> + Send + 'a {
+                let pending: FuturesUnordered<RaceFuture<'a, Self::Output, Self::Error>> =
+                    FuturesUnordered::new();
+                pending.push(Box::pin(async move {
+                    ($first_n, resolve_async::<$first_name>(world).await.map($either::$first_variant))
+                }));
+                $(
+                    pending.push(Box::pin(async move {
+                        ($n, resolve_async::<$name>(world).await.map($either::$variant))
+                    }));
+                )+
+
+                first_ok(pending)
+            }
+        }
+    };
+}
+
+impl_race!(Either5, 0:Dep0:First, 1:Dep1:Second, 2:Dep2:Third, 3:Dep3:Fourth, 4:Dep4:Fifth);
+impl_race!(Either4, 0:Dep0:First, 1:Dep1:Second, 2:Dep2:Third, 3:Dep3:Fourth);
+impl_race!(Either3, 0:Dep0:First, 1:Dep1:Second, 2:Dep2:Third);
+impl_race!(Either2, 0:Dep0:First, 1:Dep1:Second);
+
+impl<World> DIContainer<World> {
+    /// Resolves every provider of `T` concurrently, returning the first success or every error
+    pub async fn extract_race<T>(&self) -> Result<T::Output, Vec<T::Error>>
+    where
+        T: for<'a> Race<World<'a> = World>,
+    {
+        T::race(&self.world).await
+    }
+}
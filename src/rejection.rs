@@ -0,0 +1,231 @@
+//! # Ready made axum rejections
+//!
+//! [`Dependency<T>`](crate::axum::Dependency) rejects the request with `T::Error`, which must
+//! implement [`IntoResponse`]. The errors of this module already do, set one as the Error of a
+//! dependency instead of implementing [`IntoResponse`] for an error type:
+//!
+//! - [`StatusError`]: the status code, with its canonical reason as a `text/plain` body
+//!   (`401 Unauthorized` responds `Unauthorized`)
+//! - [`JsonError<E>`]: a status code, with `E` serialized as an `application/json` body
+//! - [`ProblemDetails`]: an [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem, as an
+//!   `application/problem+json` body
+//!
+//! # Status mapping
+//!
+//! The status is chosen where the error is built: [`StatusError`] wraps it, [`JsonError::new`]
+//! and [`ProblemDetails::new`] take it. Errors converted with [`From`], e.g. by `?` on the error of
+//! a dependency, don't carry one: `JsonError<E>` is `From<E>` with `500 Internal Server Error`.
+//!
+//! [`ProblemDetails`] sets its `status` member to the status of the response, and its `title` to
+//! the canonical reason of the status until set with [`ProblemDetails::with_title`].
+//!
+//! # Usage
+//! ```
+//! use axum::{
+//!     http::{StatusCode, header::CONTENT_TYPE, request::Parts},
+//!     response::IntoResponse,
+//! };
+//! use je_di::{
+//!     axum_world,
+//!     rejection::{JsonError, ProblemDetails, StatusError},
+//! };
+//! use serde::Serialize;
+//!
+//! struct AppState;
+//!
+//! #[derive(Serialize)]
+//! struct AuthError {
+//!     reason: &'static str,
+//! }
+//!
+//! struct User;
+//!
+//! axum_world! {
+//!     async fn from_world(parts: &Parts, _state: &AppState) -> Result<User, JsonError<AuthError>> {
+//!         match parts.headers.contains_key("authorization") {
+//!             true => Ok(User),
+//!             false => Err(JsonError::new(StatusCode::UNAUTHORIZED, AuthError { reason: "missing token" })),
+//!         }
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! async fn body(response: axum::response::Response) -> String {
+//!     let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+//!     String::from_utf8(bytes.to_vec()).unwrap()
+//! }
+//!
+//! let response = StatusError(StatusCode::FORBIDDEN).into_response();
+//! assert_eq!(response.status(), StatusCode::FORBIDDEN);
+//! assert_eq!(body(response).await, "Forbidden");
+//!
+//! let response = JsonError::new(StatusCode::UNAUTHORIZED, AuthError { reason: "missing token" }).into_response();
+//! assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+//! assert_eq!(body(response).await, r#"{"reason":"missing token"}"#);
+//!
+//! let response = ProblemDetails::new(StatusCode::NOT_FOUND)
+//!     .with_detail("no user with id 42")
+//!     .into_response();
+//! assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+//!
+//! let problem: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+//! assert_eq!(
+//!     problem,
+//!     serde_json::json!({
+//!         "type": "about:blank",
+//!         "title": "Not Found",
+//!         "status": 404,
+//!         "detail": "no user with id 42",
+//!     })
+//! );
+//! # });
+//! ```
+
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// # Rejection with a status code and its canonical reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusError(pub StatusCode);
+
+impl From<StatusCode> for StatusError {
+    fn from(status: StatusCode) -> Self {
+        Self(status)
+    }
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+impl IntoResponse for StatusError {
+    fn into_response(self) -> Response {
+        (self.0, self.0.canonical_reason().unwrap_or_default()).into_response()
+    }
+}
+
+/// # Rejection with a status code and a JSON body
+#[derive(Debug, Clone)]
+pub struct JsonError<E> {
+    pub status: StatusCode,
+    pub error: E,
+}
+
+impl<E> JsonError<E> {
+    pub fn new(status: StatusCode, error: E) -> Self {
+        Self { status, error }
+    }
+}
+
+impl<E> From<E> for JsonError<E> {
+    fn from(error: E) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, error)
+    }
+}
+
+impl<E: Serialize> IntoResponse for JsonError<E> {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.error)).into_response()
+    }
+}
+
+/// # RFC 7807 problem details rejection
+///
+/// Responds with the status of the problem and an `application/problem+json` body holding the
+/// `type`, `title` and `status` members, and the `detail` and `instance` members when set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemDetails {
+    status: StatusCode,
+    type_uri: String,
+    title: Option<String>,
+    detail: Option<String>,
+    instance: Option<String>,
+}
+
+impl ProblemDetails {
+    /// A problem with the `about:blank` type, titled after the canonical reason of `status`
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            type_uri: "about:blank".to_string(),
+            title: None,
+            detail: None,
+            instance: None,
+        }
+    }
+
+    /// Sets the URI identifying the problem type
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets the short summary of the problem type
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the explanation specific to this occurrence of the problem
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the URI identifying this occurrence of the problem
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl From<StatusCode> for ProblemDetails {
+    fn from(status: StatusCode) -> Self {
+        Self::new(status)
+    }
+}
+
+impl From<StatusError> for ProblemDetails {
+    fn from(StatusError(status): StatusError) -> Self {
+        Self::new(status)
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let title = self
+            .title
+            .or_else(|| self.status.canonical_reason().map(str::to_string));
+
+        let mut body = serde_json::json!({
+            "type": self.type_uri,
+            "title": title,
+            "status": self.status.as_u16(),
+        });
+        if let Some(detail) = self.detail {
+            body["detail"] = detail.into();
+        }
+        if let Some(instance) = self.instance {
+            body["instance"] = instance.into();
+        }
+
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            body.to_string(),
+        )
+            .into_response()
+    }
+}
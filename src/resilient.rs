@@ -0,0 +1,407 @@
+//! # Resilient dependencies
+//!
+//! [`Resilient<T, World>`] resolves a flaky async dependency, e.g. a client of a remote service,
+//! behind the retries, circuit breaker and fallback configured by a [`ResiliencePolicy`]:
+//!
+//! - a failed resolution is retried up to [`ResiliencePolicy::max_retries`] times, waiting
+//!   [`ResiliencePolicy::backoff`] before the first retry and twice as long before each next one
+//! - after [`ResiliencePolicy::failure_threshold`] consecutive failed attempts the circuit opens:
+//!   the resolutions fail with [`ResilientError::Open`] without calling `from_world`, sparing the
+//!   service and the caller the wait
+//! - instead of failing, the resolution falls back to the value of the [`Fallback`] of the
+//!   wrapper, if any
+//!
+//! The timer is the one of the [`DefaultRuntime`], set the last type parameter of the wrapper,
+//! `Resilient<T, World, F, R>`, to use the timer of another [`Runtime`], see
+//! [runtimes](crate::runtime). The `resilient` feature enables `runtime-tokio`, the default is
+//! always a runtime.
+//!
+//! # State sharing
+//!
+//! The state of the breaker is shared by every resolution of `T`, it lives in the World: a
+//! [`CircuitBreaker<T>`] holding the policy, exposed through [`AsRef<CircuitBreaker<T>>`]. It
+//! lives as long as the World, and with it the container, whichever wrapper or container resolves
+//! `T` from it, like a singleton. Each dependency has a breaker of its own, the failures of one
+//! service don't open the circuit of another.
+//!
+//! Failures are counted per attempt, retries included, and a success resets the count. The
+//! state is locked briefly around each attempt, never while `from_world` runs: concurrent
+//! resolutions run their attempts concurrently, and a resolution already retrying when the circuit
+//! opens fails at its next failed attempt.
+//!
+//! # Half-open transition
+//!
+//! Once opened, the circuit stays open for [`ResiliencePolicy::reset_timeout`]. The first
+//! resolution after that is a trial: the circuit is half-open, the trial makes a single attempt,
+//! without retries, and the concurrent resolutions fail with [`ResilientError::Open`] meanwhile.
+//!
+//! - a successful trial closes the circuit, the failure count starts over
+//! - a failed trial opens the circuit again for another `reset_timeout`
+//! - a trial that never completes, e.g. cancelled by a timeout, is replaced by a new trial after
+//!   `reset_timeout`
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromAsyncWorld, async_trait,
+//!     resilient::{
+//!         CircuitBreaker, CircuitState, DefaultFallback, ResiliencePolicy, Resilient,
+//!         ResilientError,
+//!     },
+//! };
+//! use std::{
+//!     sync::{
+//!         Arc,
+//!         atomic::{AtomicUsize, Ordering},
+//!     },
+//!     time::Duration,
+//! };
+//!
+//! static CALLS: AtomicUsize = AtomicUsize::new(0);
+//! // the attempts failing before the rates service is back
+//! static FAILING: AtomicUsize = AtomicUsize::new(0);
+//!
+//! #[derive(Default)]
+//! struct Rates(f64);
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Rates {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         CALLS.fetch_add(1, Ordering::SeqCst);
+//!         match FAILING.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)) {
+//!             Ok(_) => Err("rates service unavailable".to_string()),
+//!             Err(_) => Ok(Self(1.08)),
+//!         }
+//!     }
+//! }
+//!
+//! struct World {
+//!     breaker: Arc<CircuitBreaker<Rates>>,
+//! }
+//!
+//! impl AsRef<CircuitBreaker<Rates>> for World {
+//!     fn as_ref(&self) -> &CircuitBreaker<Rates> {
+//!         &self.breaker
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let breaker = Arc::new(CircuitBreaker::new(ResiliencePolicy {
+//!     max_retries: 2,
+//!     backoff: Duration::from_millis(1),
+//!     failure_threshold: 3,
+//!     reset_timeout: Duration::from_millis(50),
+//! }));
+//! let container = DIContainer::new(World { breaker: breaker.clone() });
+//!
+//! // the second retry succeeds
+//! FAILING.store(2, Ordering::SeqCst);
+//! let rates = container.extract_async::<Resilient<Rates, World>>().await.unwrap();
+//! assert_eq!((rates.0, CALLS.load(Ordering::SeqCst)), (1.08, 3));
+//!
+//! // the service is down, three consecutive failed attempts open the circuit
+//! FAILING.store(usize::MAX, Ordering::SeqCst);
+//! let error = container.extract_async::<Resilient<Rates, World>>().await.err().unwrap();
+//! assert_eq!(error, ResilientError::Failed("rates service unavailable".to_string()));
+//! assert_eq!(breaker.state(), CircuitState::Open);
+//!
+//! // the open circuit fails fast, or falls back
+//! let error = container.extract_async::<Resilient<Rates, World>>().await.err().unwrap();
+//! assert_eq!(error, ResilientError::Open);
+//! let rates = container
+//!     .extract_async::<Resilient<Rates, World, DefaultFallback>>()
+//!     .await
+//!     .unwrap();
+//! assert!(rates.is_fallback());
+//! assert_eq!(CALLS.load(Ordering::SeqCst), 6);
+//!
+//! // the service is back, the trial after the reset timeout closes the circuit
+//! FAILING.store(0, Ordering::SeqCst);
+//! tokio::time::sleep(Duration::from_millis(60)).await;
+//! let rates = container.extract_async::<Resilient<Rates, World>>().await.unwrap();
+//! assert!(!rates.is_fallback());
+//! assert_eq!(breaker.state(), CircuitState::Closed);
+//! # });
+//! ```
+
+use crate::{
+    FromAsyncWorld, async_trait,
+    resolve::resolve_async,
+    runtime::{DefaultRuntime, Runtime},
+};
+use std::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// # Retries and circuit breaker configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResiliencePolicy {
+    /// Retries of a failed resolution, `0` to only attempt once
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled before each next one
+    pub backoff: Duration,
+    /// Consecutive failed attempts opening the circuit
+    pub failure_threshold: u32,
+    /// Time the circuit stays open before a trial resolution
+    pub reset_timeout: Duration,
+}
+
+impl Default for ResiliencePolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff: Duration::from_millis(100),
+            failure_threshold: 5,
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// # State of a [`CircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Resolutions are attempted
+    Closed,
+    /// Resolutions fail without being attempted
+    Open,
+    /// A trial resolution is in flight
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { since: Instant },
+    HalfOpen { since: Instant },
+}
+
+/// # Circuit breaker of the resolutions of `T`
+///
+/// Stored in the World, see the [module documentation](crate::resilient#state-sharing)
+pub struct CircuitBreaker<T> {
+    policy: ResiliencePolicy,
+    state: Mutex<State>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> CircuitBreaker<T> {
+    pub fn new(policy: ResiliencePolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(State::Closed { failures: 0 }),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn policy(&self) -> &ResiliencePolicy {
+        &self.policy
+    }
+
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { .. } => CircuitState::Open,
+            State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Runs `attempt` with the retries of the policy, unless the circuit is open
+    async fn call<R, Fut, E>(
+        &self,
+        mut attempt: impl FnMut() -> Fut,
+    ) -> Result<T, ResilientError<E>>
+    where
+        R: Runtime,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let trial = self.acquire()?;
+        let max_retries = if trial { 0 } else { self.policy.max_retries };
+        let mut backoff = self.policy.backoff;
+        let mut retries = 0;
+
+        loop {
+            match attempt().await {
+                Ok(value) => {
+                    *self.lock() = State::Closed { failures: 0 };
+                    return Ok(value);
+                }
+                Err(error) => {
+                    if self.failed() || retries == max_retries {
+                        return Err(ResilientError::Failed(error));
+                    }
+                }
+            }
+
+            R::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+            retries += 1;
+        }
+    }
+
+    /// Whether the resolution may start, and if it is a trial
+    fn acquire<E>(&self) -> Result<bool, ResilientError<E>> {
+        let mut state = self.lock();
+        match *state {
+            State::Closed { .. } => Ok(false),
+            State::Open { since } | State::HalfOpen { since }
+                if since.elapsed() >= self.policy.reset_timeout =>
+            {
+                *state = State::HalfOpen {
+                    since: Instant::now(),
+                };
+                Ok(true)
+            }
+            State::Open { .. } | State::HalfOpen { .. } => Err(ResilientError::Open),
+        }
+    }
+
+    /// Records a failed attempt, returns whether the circuit is open
+    fn failed(&self) -> bool {
+        let mut state = self.lock();
+        match *state {
+            State::Closed { failures } if failures + 1 < self.policy.failure_threshold => {
+                *state = State::Closed {
+                    failures: failures + 1,
+                };
+                false
+            }
+            State::Closed { .. } | State::HalfOpen { .. } => {
+                *state = State::Open {
+                    since: Instant::now(),
+                };
+                true
+            }
+            State::Open { .. } => true,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> fmt::Debug for CircuitBreaker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("policy", &self.policy)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+/// # Value used when a resilient resolution fails
+pub trait Fallback<T> {
+    fn fallback() -> Option<T>;
+}
+
+/// # No fallback, the resolution fails
+pub struct NoFallback;
+
+impl<T> Fallback<T> for NoFallback {
+    fn fallback() -> Option<T> {
+        None
+    }
+}
+
+/// # Falls back to [`Default::default`]
+pub struct DefaultFallback;
+
+impl<T: Default> Fallback<T> for DefaultFallback {
+    fn fallback() -> Option<T> {
+        Some(T::default())
+    }
+}
+
+/// # Error of a resilient resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResilientError<E> {
+    /// The circuit is open, the resolution wasn't attempted
+    Open,
+    /// The error of the last attempt
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ResilientError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResilientError::Open => write!(f, "circuit open, resolution not attempted"),
+            ResilientError::Failed(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ResilientError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ResilientError::Open => None,
+            ResilientError::Failed(error) => Some(error),
+        }
+    }
+}
+
+type Marker<World, F, R> = PhantomData<fn() -> (World, F, R)>;
+
+/// # `T` resolved with the retries, circuit breaker and fallback `F`
+///
+/// The retries wait on the timer of `R`. See the [module documentation](crate::resilient)
+pub struct Resilient<T, World, F = NoFallback, R = DefaultRuntime> {
+    value: T,
+    fallback: bool,
+    _marker: Marker<World, F, R>,
+}
+
+impl<T, World, F, R> Resilient<T, World, F, R> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Whether the value is the fallback
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+}
+
+impl<T, World, F, R> Deref for Resilient<T, World, F, R> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[async_trait]
+impl<T, World, F, R> FromAsyncWorld for Resilient<T, World, F, R>
+where
+    T: for<'a> FromAsyncWorld<World<'a> = World> + Send,
+    World: AsRef<CircuitBreaker<T>> + Send + Sync + 'static,
+    F: Fallback<T> + 'static,
+    R: Runtime,
+{
+    type World<'a> = World;
+    type Error = ResilientError<T::Error>;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        let (value, fallback) = match world
+            .as_ref()
+            .call::<R, _, _>(|| resolve_async::<T>(world))
+            .await
+        {
+            Ok(value) => (value, false),
+            Err(error) => (F::fallback().ok_or(error)?, true),
+        };
+
+        Ok(Self {
+            value,
+            fallback,
+            _marker: PhantomData,
+        })
+    }
+}
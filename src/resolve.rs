@@ -0,0 +1,180 @@
+//! Single entry point for the resolution of a node
+//!
+//! The container, the blanket implementations and the tuple implementations resolve their
+//! dependencies through these functions, cross-cutting concerns (logging, tracing) hook in here.
+//!
+//! With the `log` feature every node emits, with target `je_di`:
+//!
+//! - `debug` when its resolution starts
+//! - `debug` with the elapsed time when it resolves
+//! - `error` with the elapsed time when it fails
+//!
+//! With the `debug-async` feature, in debug builds, every poll of an async `from_world` is timed
+//! and a `warn` is emitted, with target `je_di`, when it blocks the executor for longer than
+//! [`BLOCKING_THRESHOLD`]. This is best-effort: a blocking call in a nested dependency also makes
+//! every dependent poll exceed the threshold, look for the innermost warning.
+//!
+//! With the `trace-export` feature every sync node is recorded into the trace being recorded on
+//! the current thread by [`extract_traced`](crate::DIContainer::extract_traced), if any.
+//!
+//! With the `observe` feature every node is notified to the
+//! [extraction observers](crate::observe), the global one and, for sync nodes, those of the
+//! [`extract_observed`](crate::DIContainer::extract_observed) calls running on the current thread.
+//!
+//! Without the features these are plain calls to `from_world`.
+
+use crate::FromWorld;
+
+#[inline]
+pub fn resolve<T: FromWorld>(world: &T::World<'_>) -> Result<T, T::Error> {
+    #[cfg(feature = "log")]
+    let start = log_start::<T>();
+    #[cfg(feature = "trace-export")]
+    let entered = crate::trace::enter::<T>();
+    #[cfg(feature = "observe")]
+    let observed = crate::observe::start::<T>();
+
+    let result = T::from_world(world);
+
+    #[cfg(feature = "observe")]
+    crate::observe::end::<T>(observed, result.is_ok());
+    #[cfg(feature = "trace-export")]
+    crate::trace::exit(entered, result.is_ok());
+    #[cfg(feature = "log")]
+    log_end::<T>(start, result.is_ok());
+
+    result
+}
+
+#[cfg(feature = "async")]
+#[inline]
+pub async fn resolve_async<'a, T: crate::FromAsyncWorld>(
+    world: &'a T::World<'a>,
+) -> Result<T, T::Error> {
+    #[cfg(feature = "log")]
+    let start = log_start::<T>();
+    #[cfg(feature = "observe")]
+    let observed = crate::observe::start_global::<T>();
+
+    #[cfg(all(feature = "debug-async", debug_assertions))]
+    let result = blocking::Timed::<_, T>::new(T::from_world(world)).await;
+    #[cfg(not(all(feature = "debug-async", debug_assertions)))]
+    let result = T::from_world(world).await;
+
+    #[cfg(feature = "observe")]
+    crate::observe::end_global::<T>(observed, result.is_ok());
+    #[cfg(feature = "log")]
+    log_end::<T>(start, result.is_ok());
+
+    result
+}
+
+#[cfg(feature = "local")]
+#[inline]
+pub async fn resolve_local_async<'a, T: crate::local::FromLocalAsyncWorld>(
+    world: &'a T::World<'a>,
+) -> Result<T, T::Error> {
+    #[cfg(feature = "log")]
+    let start = log_start::<T>();
+    #[cfg(feature = "observe")]
+    let observed = crate::observe::start_global::<T>();
+
+    #[cfg(all(feature = "debug-async", debug_assertions))]
+    let result = blocking::Timed::<_, T>::new(T::from_world(world)).await;
+    #[cfg(not(all(feature = "debug-async", debug_assertions)))]
+    let result = T::from_world(world).await;
+
+    #[cfg(feature = "observe")]
+    crate::observe::end_global::<T>(observed, result.is_ok());
+    #[cfg(feature = "log")]
+    log_end::<T>(start, result.is_ok());
+
+    result
+}
+
+/// Unwraps the result of the extraction of `T`, logging the error before panicking
+#[track_caller]
+pub fn unwrap_or_log<T, E: core::fmt::Debug>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => {
+            let name = core::any::type_name::<T>();
+
+            #[cfg(feature = "log")]
+            log::error!(target: "je_di", "failed to extract {name}: {error:?}");
+
+            panic!("failed to extract {name}: {error:?}")
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+fn log_start<T>() -> std::time::Instant {
+    log::debug!(target: "je_di", "resolving {}", std::any::type_name::<T>());
+    std::time::Instant::now()
+}
+
+#[cfg(feature = "log")]
+fn log_end<T>(start: std::time::Instant, resolved: bool) {
+    let elapsed = start.elapsed();
+    if resolved {
+        log::debug!(target: "je_di", "resolved {} in {elapsed:?}", std::any::type_name::<T>());
+    } else {
+        log::error!(
+            target: "je_di",
+            "failed to resolve {} after {elapsed:?}",
+            std::any::type_name::<T>()
+        );
+    }
+}
+
+/// Longest poll of an async `from_world` not reported as blocking
+#[cfg(all(feature = "debug-async", debug_assertions))]
+pub const BLOCKING_THRESHOLD: std::time::Duration = std::time::Duration::from_millis(10);
+
+#[cfg(all(feature = "debug-async", debug_assertions))]
+mod blocking {
+    use std::{
+        future::Future,
+        marker::PhantomData,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Instant,
+    };
+
+    /// Warns when a single poll of the wrapped future exceeds [`super::BLOCKING_THRESHOLD`]
+    pub struct Timed<F, T> {
+        future: F,
+        _marker: PhantomData<fn() -> T>,
+    }
+
+    impl<F, T> Timed<F, T> {
+        pub fn new(future: F) -> Self {
+            Self {
+                future,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<F: Future + Unpin, T> Future for Timed<F, T> {
+        type Output = F::Output;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let start = Instant::now();
+            let poll = Pin::new(&mut self.future).poll(cx);
+            let elapsed = start.elapsed();
+
+            if elapsed > super::BLOCKING_THRESHOLD {
+                log::warn!(
+                    target: "je_di",
+                    "resolving {} blocked the executor for {elapsed:?}, move blocking calls to \
+                     `spawn_blocking` or `block_in_place`",
+                    std::any::type_name::<T>()
+                );
+            }
+
+            poll
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! # Re-resolvable dependencies
+//!
+//! [`DIContainer::extract_with_resolver`] returns a `T` along with a [`Resolver<T, World>`] able
+//! to resolve it again later without the container, for values refreshed from time to time
+//! (access tokens, configuration snapshots) by code not holding the container.
+//!
+//! # Captured state
+//!
+//! The resolver captures a clone of the World, taken when extracting: the World must be
+//! [`Clone`], keep it cheap to clone by sharing it behind an [`Arc`](alloc::sync::Arc). Changes to
+//! the state shared through the World (`Arc`s, interior mutability) are seen by the later
+//! resolutions, replacing the World of the container isn't.
+//!
+//! # Cost
+//!
+//! [`Resolver::resolve`] resolves `T` from scratch, running `from_world` for `T` and its whole
+//! dependency tree, nothing is cached between resolutions. Wrap the dependencies expensive to
+//! build in [`Singleton`](crate::singleton) or [`Expiring`](crate::expiring) fields of the World
+//! if only `T` needs to be refreshed.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//! use std::sync::{
+//!     Arc,
+//!     atomic::{AtomicU32, Ordering},
+//! };
+//!
+//! struct Issuer {
+//!     issued: AtomicU32,
+//! }
+//!
+//! type World = Arc<Issuer>;
+//!
+//! struct Token(u32);
+//!
+//! impl FromWorld for Token {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.issued.fetch_add(1, Ordering::SeqCst)))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(Arc::new(Issuer { issued: AtomicU32::new(0) }));
+//!
+//! let (token, resolver) = container.extract_with_resolver::<Token>().unwrap();
+//! assert_eq!(token.0, 0);
+//!
+//! drop(container);
+//! assert_eq!(resolver.resolve().unwrap().0, 1);
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use core::marker::PhantomData;
+
+/// # Resolves `T` again from a clone of the World
+pub struct Resolver<T, World> {
+    world: World,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, World> Resolver<T, World>
+where
+    T: for<'a> FromWorld<World<'a> = World>,
+{
+    /// Resolves a new `T`
+    pub fn resolve(&self) -> Result<T, T::Error> {
+        resolve::<T>(&self.world)
+    }
+}
+
+impl<T, World: Clone> Clone for Resolver<T, World> {
+    fn clone(&self) -> Self {
+        Self {
+            world: self.world.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<World: Clone> DIContainer<World> {
+    /// Extracts `T` along with a [`Resolver`] to resolve it again later
+    pub fn extract_with_resolver<T>(&self) -> Result<(T, Resolver<T, World>), T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        let resolver = Resolver {
+            world: self.world.clone(),
+            _marker: PhantomData,
+        };
+
+        Ok((resolver.resolve()?, resolver))
+    }
+}
@@ -0,0 +1,184 @@
+//! # Async runtimes
+//!
+//! The combinators waiting on time, the [timeouts](crate::timeout) and the retries of
+//! [`Resilient`](crate::resilient::Resilient), use the timer of the async runtime they run on
+//! through the [`Runtime`] trait, implemented for each runtime behind a feature:
+//!
+//! - [`Tokio`] with `runtime-tokio`, requiring the time driver of the tokio runtime
+//! - [`AsyncStd`] with `runtime-async-std`
+//! - [`Smol`] with `runtime-smol`
+//!
+//! A future waiting on the timer of one runtime must be polled by that runtime: tokio timers
+//! panic outside of a tokio runtime, the others run their own timer thread and work anywhere but
+//! add that thread to a tokio application.
+//!
+//! # Default runtime
+//!
+//! [`DefaultRuntime`] is the runtime used when none is named, the first enabled of [`Tokio`],
+//! [`AsyncStd`] and [`Smol`], in that order. The `timeout` and `resilient` features enable
+//! `runtime-tokio`, their API doesn't depend on picking a runtime feature: with them the default
+//! is [`Tokio`], the other runtimes are named explicitly. Without any runtime feature it is
+//! [`NoRuntime`], which isn't a runtime and implements no [`Runtime`].
+//!
+//! # Overriding the runtime
+//!
+//! Libraries should leave the choice to the application and not enable a runtime feature
+//! themselves: features are additive, a library enabling `runtime-tokio` makes tokio the default
+//! of every crate of the build. Where the default is not the right runtime, e.g. an application
+//! running on smol with `timeout` enabled, or running tokio and smol side by side, name it:
+//!
+//! - [`DIContainer::extract_async_timeout_on`](crate::DIContainer::extract_async_timeout_on) and
+//!   the other `_on` variants of the timeouts take the runtime as a type parameter
+//! - [`Resilient<T, World, F, R>`](crate::resilient::Resilient) takes the runtime as its last
+//!   type parameter
+//!
+//! A custom executor implements [`Runtime`] with its own timer.
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "runtime-smol")] {
+//! use je_di::{DIContainer, FromAsyncWorld, async_trait, runtime::{Runtime, Smol}};
+//! use std::time::Duration;
+//!
+//! struct World;
+//!
+//! struct Rates(f64);
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Rates {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         Smol::sleep(Duration::from_secs(60)).await;
+//!         Ok(Self(1.08))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let rates = smol::block_on(
+//!     container.extract_async_or_after_on::<Smol, _>(Duration::from_millis(10), Rates(1.0)),
+//! );
+//! assert_eq!(rates.unwrap().0, 1.0);
+//! # }
+//! ```
+
+use core::{future::Future, time::Duration};
+use std::time::Instant;
+
+/// # Timer of an async runtime
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an async runtime",
+    label = "not a runtime",
+    note = "enable the `runtime-tokio`, `runtime-async-std` or `runtime-smol` feature of je-di, \
+            or name a type implementing `Runtime`"
+)]
+pub trait Runtime: 'static {
+    /// Completes after `duration`
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send;
+
+    /// Completes at `deadline`, immediately when it is past
+    fn sleep_until(deadline: Instant) -> impl Future<Output = ()> + Send {
+        Self::sleep(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// # The tokio runtime
+#[cfg(feature = "runtime-tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "runtime-tokio")))]
+pub struct Tokio;
+
+#[cfg(feature = "runtime-tokio")]
+impl Runtime for Tokio {
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+
+    fn sleep_until(deadline: Instant) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline))
+    }
+}
+
+/// # The async-std runtime
+#[cfg(feature = "runtime-async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "runtime-async-std")))]
+pub struct AsyncStd;
+
+#[cfg(feature = "runtime-async-std")]
+impl Runtime for AsyncStd {
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send {
+        async_std::task::sleep(duration)
+    }
+}
+
+/// # The smol runtime
+#[cfg(feature = "runtime-smol")]
+#[cfg_attr(docsrs, doc(cfg(feature = "runtime-smol")))]
+pub struct Smol;
+
+#[cfg(feature = "runtime-smol")]
+impl Runtime for Smol {
+    async fn sleep(duration: Duration) {
+        smol::Timer::after(duration).await;
+    }
+
+    async fn sleep_until(deadline: Instant) {
+        smol::Timer::at(deadline).await;
+    }
+}
+
+/// # Runtime used when none is named
+///
+/// See the [module documentation](crate::runtime#default-runtime)
+#[cfg(feature = "runtime-tokio")]
+pub type DefaultRuntime = Tokio;
+
+/// # Runtime used when none is named
+///
+/// See the [module documentation](crate::runtime#default-runtime)
+#[cfg(all(not(feature = "runtime-tokio"), feature = "runtime-async-std"))]
+pub type DefaultRuntime = AsyncStd;
+
+/// # Runtime used when none is named
+///
+/// See the [module documentation](crate::runtime#default-runtime)
+#[cfg(all(
+    not(feature = "runtime-tokio"),
+    not(feature = "runtime-async-std"),
+    feature = "runtime-smol"
+))]
+pub type DefaultRuntime = Smol;
+
+/// # Not a runtime
+///
+/// The [`DefaultRuntime`] without runtime feature, it implements no [`Runtime`]
+pub enum NoRuntime {}
+
+/// # Runtime used when none is named
+///
+/// See the [module documentation](crate::runtime#default-runtime)
+#[cfg(not(any(
+    feature = "runtime-tokio",
+    feature = "runtime-async-std",
+    feature = "runtime-smol"
+)))]
+pub type DefaultRuntime = NoRuntime;
+
+/// Polls `future` until `timer` completes, `None` if it completes first
+#[cfg(feature = "timeout")]
+pub(crate) async fn race_timer<F: Future>(
+    future: F,
+    timer: impl Future<Output = ()>,
+) -> Option<F::Output> {
+    use core::{future::poll_fn, pin::pin, task::Poll};
+
+    let mut future = pin!(future);
+    let mut timer = pin!(timer);
+
+    poll_fn(|cx| match future.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => timer.as_mut().poll(cx).map(|()| None),
+    })
+    .await
+}
@@ -0,0 +1,174 @@
+//! # Scoped resolution
+//!
+//! Request-scoped dependencies are declared over a [`Scoped`] World pairing the container World
+//! with a scope built per request or per job, e.g. the authenticated user and the request id.
+//! [`DIContainer::extract_scoped_all`] resolves them against the container World and a scope:
+//! requesting a tuple resolves all its elements against the same scope, built once by the
+//! caller, which is what a handler needing several request-scoped dependencies does.
+//!
+//! The tuple implementations cover 2 to 9 elements, a single dependency can be requested as well.
+//!
+//! # Borrowing
+//!
+//! [`DIContainer::extract_borrowing_scoped`] extracts a [`BorrowFromWorld`] view over the
+//! [`Scoped`] World, borrowing from both the container World and the scope for the same `'a`
+//! instead of cloning: a handler can hold a header of the request next to a client of the World.
+//! The view keeps both the container and the scope borrowed as long as it lives.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, scope::Scoped};
+//!
+//! struct World {
+//!     tenant: &'static str,
+//! }
+//!
+//! struct Request {
+//!     user: &'static str,
+//!     id: u64,
+//! }
+//!
+//! struct Audit(String);
+//!
+//! impl FromWorld for Audit {
+//!     type World<'a> = Scoped<'a, World, Request>;
+//!     type Error = String;
+//!
+//!     fn from_world((world, request): &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(format!("{}/{}", world.tenant, request.user)))
+//!     }
+//! }
+//!
+//! struct RequestId(u64);
+//!
+//! impl FromWorld for RequestId {
+//!     type World<'a> = Scoped<'a, World, Request>;
+//!     type Error = String;
+//!
+//!     fn from_world((_, request): &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(request.id))
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { tenant: "acme" });
+//! let request = Request { user: "alice", id: 7 };
+//!
+//! let (audit, id): (Audit, RequestId) = container.extract_scoped_all(&request).unwrap();
+//! assert_eq!(audit.0, "acme/alice");
+//! assert_eq!(id.0, 7);
+//! ```
+
+use crate::{BorrowFromWorld, DIContainer, FromWorld, resolve::resolve};
+
+/// World of the dependencies scoped by `S`, the container World and the scope
+pub type Scoped<'a, World, S> = (&'a World, &'a S);
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, usually a tuple, against the World and `scope`
+    pub fn extract_scoped_all<T, S>(&self, scope: &S) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = Scoped<'a, World, S>>,
+    {
+        resolve::<T>(&(&self.world, scope))
+    }
+
+    /// Extracts a `T` borrowing from both the World and `scope`
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{BorrowFromWorld, DIContainer, scope::Scoped};
+    ///
+    /// struct HttpClient {
+    ///     base_url: String,
+    /// }
+    ///
+    /// struct World {
+    ///     client: HttpClient,
+    /// }
+    ///
+    /// struct Request {
+    ///     headers: Vec<(String, String)>,
+    /// }
+    ///
+    /// struct Upstream<'a> {
+    ///     client: &'a HttpClient,
+    ///     authorization: &'a str,
+    /// }
+    ///
+    /// impl<'a> BorrowFromWorld<'a> for Upstream<'a> {
+    ///     type World = Scoped<'a, World, Request>;
+    ///     type Error = &'static str;
+    ///
+    ///     fn borrow_from_world((world, request): Self::World) -> Result<Self, Self::Error> {
+    ///         let authorization = request
+    ///             .headers
+    ///             .iter()
+    ///             .find(|(name, _)| name == "authorization")
+    ///             .map(|(_, value)| value.as_str())
+    ///             .ok_or("missing authorization header")?;
+    ///
+    ///         Ok(Self { client: &world.client, authorization })
+    ///     }
+    /// }
+    ///
+    /// let container = DIContainer::new(World {
+    ///     client: HttpClient { base_url: "https://api.example.com".to_string() },
+    /// });
+    /// let request = Request {
+    ///     headers: vec![("authorization".to_string(), "Bearer token".to_string())],
+    /// };
+    ///
+    /// let upstream: Upstream = container.extract_borrowing_scoped(&request).unwrap();
+    /// assert_eq!(upstream.client.base_url, "https://api.example.com");
+    /// assert_eq!(upstream.authorization, "Bearer token");
+    /// ```
+    pub fn extract_borrowing_scoped<'a, T, S>(
+        &'a self,
+        scope: &'a S,
+    ) -> Result<T, <T as BorrowFromWorld<'a>>::Error>
+    where
+        T: BorrowFromWorld<'a, World = Scoped<'a, World, S>>,
+    {
+        T::borrow_from_world((&self.world, scope))
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World> DIContainer<World> {
+    /// Async variant of [`extract_scoped_all`](DIContainer::extract_scoped_all)
+    ///
+    /// # Usage
+    /// ```
+    /// use je_di::{DIContainer, FromAsyncWorld, async_trait, scope::Scoped};
+    ///
+    /// struct World;
+    ///
+    /// struct User(&'static str);
+    ///
+    /// #[async_trait]
+    /// impl FromAsyncWorld for User {
+    ///     type World<'a> = Scoped<'a, World, &'static str>;
+    ///     type Error = String;
+    ///
+    ///     async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+    ///         Ok(Self(world.1))
+    ///     }
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let container = DIContainer::new(World);
+    /// let (first, second): (User, User) =
+    ///     container.extract_scoped_all_async(&"alice").await.unwrap();
+    /// assert_eq!((first.0, second.0), ("alice", "alice"));
+    /// # });
+    /// ```
+    pub async fn extract_scoped_all_async<T, S>(&self, scope: &S) -> Result<T, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = Scoped<'a, World, S>>,
+    {
+        let world = (&self.world, scope);
+
+        crate::resolve::resolve_async::<T>(&world).await
+    }
+}
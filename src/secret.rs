@@ -0,0 +1,310 @@
+//! # File-mounted secrets
+//!
+//! Orchestrators mount secrets and downward-API values as files (Kubernetes mounts them under
+//! `/var/run/secrets/...`) and rewrite them in place when they rotate. [`SecretFile<T>`] is a
+//! World field reading such a file and parsing it through [`FromStr`], [`Secret<T, World>`] then
+//! resolves the current value from a World exposing the file through [`AsRef<SecretFile<T>>`].
+//!
+//! The content is parsed without its trailing whitespace, mounted files usually end with a
+//! newline that isn't part of the secret.
+//!
+//! # Time to live
+//!
+//! The parsed value is cached for a time to live, one minute by default, set with
+//! [`SecretFile::with_ttl`]. Resolutions within the time to live share the cached value without
+//! touching the file, the first resolution after it re-reads and re-parses the file, picking up a
+//! rotated secret: a rotation is seen at most one time to live late. A zero time to live reads
+//! the file on every resolution, [`SecretFile::invalidate`] forces the next one to read it.
+//!
+//! A failed refresh returns the error and leaves the stale value in place, the next resolution
+//! tries again: a secret caught mid-rotation (missing, or partially written) is retried rather
+//! than cached. The cache is an [`Expiring`], a time to live too large for the clock never
+//! expires.
+//!
+//! # Async
+//!
+//! With the `fs` feature, [`SecretFile::get_async`] reads the file through `tokio::fs` and
+//! [`Secret`] implements [`FromAsyncWorld`](crate::FromAsyncWorld), not blocking the executor
+//! when the value is refreshed. Sync refreshes are single-flight, concurrent callers wait for the
+//! refreshing one, async refreshes aren't: concurrent callers finding a stale value each read the
+//! file.
+//!
+//! ```
+//! # #[cfg(feature = "fs")] {
+//! use je_di::{DIContainer, secret::{Secret, SecretFile}};
+//! use std::time::Duration;
+//!
+//! struct World {
+//!     token: SecretFile<String>,
+//! }
+//!
+//! impl AsRef<SecretFile<String>> for World {
+//!     fn as_ref(&self) -> &SecretFile<String> {
+//!         &self.token
+//!     }
+//! }
+//!
+//! let path = std::env::temp_dir().join(format!("je-di-secret-async-{}", std::process::id()));
+//! std::fs::write(&path, "first\n").unwrap();
+//!
+//! let container = DIContainer::new(World {
+//!     token: SecretFile::new(&path).with_ttl(Duration::ZERO),
+//! });
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let token = container.extract_async::<Secret<String, World>>().await.unwrap();
+//! assert_eq!(*token, "first");
+//!
+//! // rotated
+//! tokio::fs::write(&path, "second\n").await.unwrap();
+//! let token = container.extract_async::<Secret<String, World>>().await.unwrap();
+//! assert_eq!(*token, "second");
+//! # });
+//!
+//! std::fs::remove_file(&path).unwrap();
+//! # }
+//! ```
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, secret::{Secret, SecretError, SecretFile}};
+//! use std::{str::FromStr, time::Duration};
+//!
+//! struct Token(String);
+//!
+//! impl FromStr for Token {
+//!     type Err = String;
+//!
+//!     fn from_str(token: &str) -> Result<Self, Self::Err> {
+//!         match token.is_empty() {
+//!             true => Err("empty token".to_string()),
+//!             false => Ok(Self(token.to_string())),
+//!         }
+//!     }
+//! }
+//!
+//! struct World {
+//!     token: SecretFile<Token>,
+//! }
+//!
+//! impl AsRef<SecretFile<Token>> for World {
+//!     fn as_ref(&self) -> &SecretFile<Token> {
+//!         &self.token
+//!     }
+//! }
+//!
+//! let path = std::env::temp_dir().join(format!("je-di-secret-{}", std::process::id()));
+//! std::fs::write(&path, "first\n").unwrap();
+//!
+//! let container = DIContainer::new(World {
+//!     token: SecretFile::new(&path).with_ttl(Duration::ZERO),
+//! });
+//!
+//! let token: Secret<Token, World> = container.extract().unwrap();
+//! assert_eq!(token.0, "first");
+//!
+//! // rotated
+//! std::fs::write(&path, "second\n").unwrap();
+//! let token: Secret<Token, World> = container.extract().unwrap();
+//! assert_eq!(token.0, "second");
+//!
+//! // a time to live overflowing the clock keeps the first value read
+//! let pinned = SecretFile::<Token>::new(&path).with_ttl(Duration::MAX);
+//! assert_eq!(pinned.get().unwrap().0, "second");
+//! std::fs::write(&path, "third\n").unwrap();
+//! assert_eq!(pinned.get().unwrap().0, "second");
+//!
+//! std::fs::write(&path, "").unwrap();
+//! let error = container.extract::<Secret<Token, World>>().err().unwrap();
+//! assert!(matches!(error, SecretError::Parse { .. }));
+//!
+//! std::fs::remove_file(&path).unwrap();
+//! let error = container.extract::<Secret<Token, World>>().err().unwrap();
+//! assert!(matches!(error, SecretError::Io { .. }));
+//! ```
+
+use crate::{FromWorld, expiring::Expiring};
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+/// # Secret read from a file, cached for a time to live
+pub struct SecretFile<T> {
+    path: PathBuf,
+    cache: Expiring<T>,
+}
+
+impl<T> SecretFile<T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cache: Expiring::new(Duration::from_secs(60)),
+        }
+    }
+
+    /// Sets how long the parsed value is used before the file is read again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.cache = Expiring::new(ttl);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.cache.ttl()
+    }
+
+    /// Forces the next read of the secret to read the file
+    pub fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+}
+
+impl<T> SecretFile<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    /// Returns the current secret, reading the file if the cached value is missing or stale
+    pub fn get(&self) -> Result<Arc<T>, SecretError> {
+        self.cache.get_or_build(|| {
+            let content = std::fs::read_to_string(&self.path).map_err(|error| SecretError::Io {
+                path: self.path.clone(),
+                error,
+            })?;
+
+            self.parse(&content)
+        })
+    }
+
+    /// Async variant of [`SecretFile::get`], reading the file through `tokio::fs`
+    #[cfg(feature = "fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub async fn get_async(&self) -> Result<Arc<T>, SecretError> {
+        if let Some(value) = self.cache.cached() {
+            return Ok(value);
+        }
+
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|error| SecretError::Io {
+                path: self.path.clone(),
+                error,
+            })?;
+        let value = self.parse(&content)?;
+
+        Ok(self.cache.insert(value))
+    }
+
+    fn parse(&self, content: &str) -> Result<T, SecretError> {
+        content
+            .trim_end()
+            .parse()
+            .map_err(|error: T::Err| SecretError::Parse {
+                path: self.path.clone(),
+                message: error.to_string(),
+            })
+    }
+}
+
+/// # Current value of the [`SecretFile<T>`] of the World
+pub struct Secret<T, World, Error = SecretError> {
+    value: Arc<T>,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> Secret<T, World, Error> {
+    pub fn into_inner(self) -> Arc<T> {
+        self.value
+    }
+}
+
+impl<T, World, Error> Deref for Secret<T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, World, Error> FromWorld for Secret<T, World, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    World: AsRef<SecretFile<T>>,
+    Error: From<SecretError>,
+{
+    type World<'a> = World;
+    type Error = Error;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: world.as_ref().get()?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+#[crate::async_trait]
+impl<T, World, Error> crate::FromAsyncWorld for Secret<T, World, Error>
+where
+    T: FromStr + Send + Sync + 'static,
+    T::Err: Display,
+    World: AsRef<SecretFile<T>> + Send + Sync + 'static,
+    Error: From<SecretError> + Send + Sync + 'static,
+{
+    type World<'a> = World;
+    type Error = Error;
+
+    async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: world.as_ref().get_async().await?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// # Error reading a [`SecretFile`]
+#[derive(Debug)]
+pub enum SecretError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for SecretError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretError::Io { path, error } => {
+                write!(f, "failed to read secret `{}`: {error}", path.display())
+            }
+            SecretError::Parse { path, message } => {
+                write!(f, "invalid secret `{}`: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SecretError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecretError::Io { error, .. } => Some(error),
+            SecretError::Parse { .. } => None,
+        }
+    }
+}
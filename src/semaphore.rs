@@ -0,0 +1,127 @@
+//! # Concurrency limits
+//!
+//! When many requests each resolve an expensive async dependency (opening a connection, a TLS
+//! handshake), [`SemaphoreContainer`] caps the number of resolutions in flight and queues the
+//! others instead of letting them all hit the backend at once.
+//!
+//! - [`SemaphoreContainer::limit`] caps the concurrent resolutions of one type
+//! - [`SemaphoreContainer::limit_global`] caps the concurrent resolutions of every type together
+//!
+//! A resolution holds its permits until `from_world` returns, the resolved value doesn't. Like
+//! interceptors, limits apply to the extracted type, not to the graph below it. The semaphores
+//! are tokio's, the container requires the `semaphore` feature.
+//!
+//! # Queuing
+//!
+//! The semaphores are fair: queued resolutions acquire their permits in the order they started
+//! waiting. A resolution waits for the permit of its type first and for a global permit second,
+//! waiting for its type doesn't hold a global permit.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromAsyncWorld, async_trait};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+//! static PEAK: AtomicUsize = AtomicUsize::new(0);
+//!
+//! struct World;
+//!
+//! struct Connection;
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Connection {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         let in_flight = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+//!         PEAK.fetch_max(in_flight, Ordering::SeqCst);
+//!         tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+//!         IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let container = DIContainer::new(World)
+//!     .into_limited()
+//!     .limit::<Connection>(2)
+//!     .limit_global(16);
+//!
+//! let connections = tokio::join!(
+//!     container.extract_async::<Connection>(),
+//!     container.extract_async::<Connection>(),
+//!     container.extract_async::<Connection>(),
+//!     container.extract_async::<Connection>(),
+//! );
+//! assert!(connections.3.is_ok());
+//! assert_eq!(PEAK.load(Ordering::SeqCst), 2);
+//! # });
+//! ```
+
+use crate::{DIContainer, FromAsyncWorld, resolve::resolve_async};
+use std::{any::TypeId, collections::HashMap, sync::Arc};
+use tokio::sync::Semaphore;
+
+/// # Container limiting the concurrent resolutions
+pub struct SemaphoreContainer<World> {
+    container: DIContainer<World>,
+    global: Option<Arc<Semaphore>>,
+    per_type: HashMap<TypeId, Arc<Semaphore>>,
+}
+
+impl<World> SemaphoreContainer<World> {
+    /// The underlying container, extractions through it are not limited
+    pub fn container(&self) -> &DIContainer<World> {
+        &self.container
+    }
+
+    /// Allows at most `permits` concurrent resolutions of `T`, replacing its previous limit
+    pub fn limit<T: 'static>(mut self, permits: usize) -> Self {
+        self.per_type
+            .insert(TypeId::of::<T>(), Arc::new(Semaphore::new(permits)));
+        self
+    }
+
+    /// Allows at most `permits` concurrent resolutions of all types, replacing the previous limit
+    pub fn limit_global(mut self, permits: usize) -> Self {
+        self.global = Some(Arc::new(Semaphore::new(permits)));
+        self
+    }
+
+    /// Extracts `T` once a permit of its type and a global permit are available
+    pub async fn extract_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        let _type_permit = match self.per_type.get(&TypeId::of::<T>()) {
+            Some(semaphore) => Some(acquire(semaphore).await),
+            None => None,
+        };
+        let _global_permit = match &self.global {
+            Some(semaphore) => Some(acquire(semaphore).await),
+            None => None,
+        };
+
+        resolve_async::<T>(&self.container.world).await
+    }
+}
+
+async fn acquire(semaphore: &Semaphore) -> tokio::sync::SemaphorePermit<'_> {
+    semaphore
+        .acquire()
+        .await
+        .unwrap_or_else(|_| unreachable!("the semaphores are never closed"))
+}
+
+impl<World> DIContainer<World> {
+    /// Wraps the container into a [`SemaphoreContainer`] without limits
+    pub fn into_limited(self) -> SemaphoreContainer<World> {
+        SemaphoreContainer {
+            container: self,
+            global: None,
+            per_type: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! # Singleton scope
+//!
+//! [`SingletonContainer`] builds each extracted type once and keeps it for the lifetime of the
+//! container.
+//!
+//! - [`SingletonContainer::extract_arc`] returns a clone of the stored [`Arc`], the idiomatic way
+//!   to share heavy services
+//! - [`SingletonContainer::extract`] returns an owned clone of the stored value
+//!
+//! # Teardown
+//!
+//! [`SingletonContainer::on_drop`] registers a closure run with the cached `T` when the container
+//! is dropped, in reverse registration order, e.g. to flush a writer before closing the pool it
+//! writes to.
+//!
+//! - teardowns only fire for cached instances, a type that was never extracted is not built on
+//!   drop
+//! - a panicking teardown doesn't prevent the next ones from running: the panic is caught and
+//!   discarded, dropping the container never panics
+//!
+//! # Concurrency
+//!
+//! The instances are not locked while being built: two threads extracting the same missing type
+//! concurrently both build it, the first build to be stored wins and is returned to both.
+//!
+//! # Seeding
+//!
+//! [`SingletonContainer::extract_or_insert_with`] returns the cached `T` if present, otherwise
+//! builds it with a closure instead of `from_world` and caches it, mixing manually built
+//! instances with resolved ones. The same race applies: concurrent callers may all run their
+//! closure, the first instance stored wins, whether it was built by a closure or resolved, and
+//! the other ones are dropped.
+//!
+//! # Usage
+//! ```
+//! use je_di::{FromWorld, singleton::SingletonContainer};
+//! use std::sync::Arc;
+//!
+//! struct World;
+//!
+//! struct Service;
+//!
+//! impl FromWorld for Service {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! let container = SingletonContainer::new(World);
+//!
+//! let first = container.extract_arc::<Service>().unwrap();
+//! let second = container.extract_arc::<Service>().unwrap();
+//! assert!(Arc::ptr_eq(&first, &second));
+//!
+//! let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+//! let first = log.clone();
+//! container.on_drop(move |_: &Service| first.lock().unwrap().push("service"));
+//! let second = log.clone();
+//! container.on_drop(move |_: &String| second.lock().unwrap().push("never extracted"));
+//! let third = log.clone();
+//! container.on_drop(move |_: &Service| third.lock().unwrap().push("service, registered last"));
+//!
+//! drop(container);
+//! assert_eq!(*log.lock().unwrap(), ["service, registered last", "service"]);
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+type Instances = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+type Teardown = Box<dyn FnOnce(&Instances) + Send>;
+
+pub struct SingletonContainer<World> {
+    container: DIContainer<World>,
+    instances: Mutex<Instances>,
+    teardowns: Mutex<Vec<Teardown>>,
+}
+
+impl<World> SingletonContainer<World> {
+    pub fn new(world: World) -> Self {
+        DIContainer::new(world).into_singleton()
+    }
+
+    /// The underlying container, extractions through it are not cached
+    pub fn container(&self) -> &DIContainer<World> {
+        &self.container
+    }
+
+    /// Returns the cached `T`, building it on first use
+    pub fn extract_arc<T>(&self) -> Result<Arc<T>, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + Send + Sync + 'static,
+    {
+        if let Some(instance) = self.get::<T>() {
+            return Ok(instance);
+        }
+
+        let instance = resolve::<T>(&self.container.world)?;
+
+        Ok(self.insert(instance))
+    }
+
+    /// Returns an owned clone of the cached `T`, building it on first use
+    pub fn extract<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + Clone + Send + Sync + 'static,
+    {
+        self.extract_arc::<T>().map(|instance| T::clone(&instance))
+    }
+
+    /// Returns the cached `T`, building it with `f` if it was never built
+    ///
+    /// # Usage
+    /// ```
+    /// # use je_di::{FromWorld, singleton::SingletonContainer};
+    /// # struct World;
+    /// #[derive(Clone)]
+    /// struct Seed(u64);
+    ///
+    /// # impl FromWorld for Seed {
+    /// #     type World<'a> = World;
+    /// #     type Error = String;
+    /// #     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+    /// #         Ok(Self(0))
+    /// #     }
+    /// # }
+    /// let container = SingletonContainer::new(World);
+    ///
+    /// assert_eq!(container.extract_or_insert_with(|| Seed(42)).0, 42);
+    /// assert_eq!(container.extract_or_insert_with(|| Seed(7)).0, 42);
+    /// assert_eq!(container.extract::<Seed>().unwrap().0, 42);
+    /// ```
+    pub fn extract_or_insert_with<T>(&self, f: impl FnOnce() -> T) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        T::clone(&self.extract_arc_or_insert_with(f))
+    }
+
+    /// Returns the cached `T` as an [`Arc`], building it with `f` if it was never built
+    pub fn extract_arc_or_insert_with<T>(&self, f: impl FnOnce() -> T) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        match self.get::<T>() {
+            Some(instance) => instance,
+            None => self.insert(f()),
+        }
+    }
+
+    /// Registers `teardown`, run with the cached `T` when the container is dropped
+    pub fn on_drop<T: Send + Sync + 'static>(&self, teardown: impl FnOnce(&T) + Send + 'static) {
+        let teardown: Teardown = Box::new(move |instances| {
+            if let Some(instance) = instances
+                .get(&TypeId::of::<T>())
+                .and_then(|instance| instance.downcast_ref::<T>())
+            {
+                teardown(instance);
+            }
+        });
+
+        self.teardowns
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(teardown);
+    }
+
+    pub(crate) fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        let instance = self.lock().get(&TypeId::of::<T>())?.clone();
+
+        instance.downcast::<T>().ok()
+    }
+
+    /// Stores `instance` unless another one was stored first, returns the stored instance
+    pub(crate) fn insert<T: Send + Sync + 'static>(&self, instance: T) -> Arc<T> {
+        let stored = self
+            .lock()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Arc::new(instance))
+            .clone();
+
+        stored
+            .downcast::<T>()
+            .unwrap_or_else(|_| unreachable!("instances are keyed by their TypeId"))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Instances> {
+        self.instances.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<World> Drop for SingletonContainer<World> {
+    fn drop(&mut self) {
+        let teardowns = std::mem::take(
+            self.teardowns
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        let instances = self
+            .instances
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        for teardown in teardowns.into_iter().rev() {
+            let _ = catch_unwind(AssertUnwindSafe(|| teardown(instances)));
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World> SingletonContainer<World> {
+    /// Async variant of [`SingletonContainer::extract_arc`]
+    pub async fn extract_arc_async<T>(&self) -> Result<Arc<T>, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World> + Send + Sync,
+    {
+        if let Some(instance) = self.get::<T>() {
+            return Ok(instance);
+        }
+
+        let instance = crate::resolve::resolve_async::<T>(&self.container.world).await?;
+
+        Ok(self.insert(instance))
+    }
+
+    /// Async variant of [`SingletonContainer::extract`]
+    pub async fn extract_async<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World> + Clone + Send + Sync,
+    {
+        self.extract_arc_async::<T>()
+            .await
+            .map(|instance| T::clone(&instance))
+    }
+}
+
+impl<World> DIContainer<World> {
+    pub fn into_singleton(self) -> SingletonContainer<World> {
+        SingletonContainer {
+            container: self,
+            instances: Mutex::new(HashMap::new()),
+            teardowns: Mutex::new(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! # Snapshots
+//!
+//! For golden tests of a dependency graph, [`DIContainer::extract_and_serialize`] resolves a
+//! dependency and serializes it to pretty printed JSON, to be compared against a fixture.
+//!
+//! Only [`Serialize`] dependencies can be snapshotted. Dependencies holding resources
+//! (connections, file handles, clients) can:
+//!
+//! - skip the resource fields with `#[serde(skip)]`, keeping the configuration they were built
+//!   from
+//! - be snapshotted through a serializable projection, a dependency of the same World built from
+//!   the same inputs as the resource
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld};
+//! use serde::Serialize;
+//!
+//! struct World {
+//!     host: String,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Client {
+//!     base_url: String,
+//!     #[serde(skip)]
+//!     _connection: (),
+//! }
+//!
+//! impl FromWorld for Client {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self {
+//!             base_url: format!("https://{}", world.host),
+//!             _connection: (),
+//!         })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { host: "example.com".to_string() });
+//!
+//! let snapshot = container.extract_and_serialize::<Client>().unwrap();
+//! assert_eq!(snapshot, "{\n  \"base_url\": \"https://example.com\"\n}");
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use serde::Serialize;
+
+/// # Error snapshotting a dependency
+#[derive(Debug)]
+pub enum SnapshotError<E> {
+    Resolve(E),
+    Serialize(serde_json::Error),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for SnapshotError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Resolve(error) => error.fmt(f),
+            SnapshotError::Serialize(error) => write!(f, "failed to serialize: {error}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SnapshotError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Resolve(error) => Some(error),
+            SnapshotError::Serialize(error) => Some(error),
+        }
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T` and serializes it to pretty printed JSON
+    pub fn extract_and_serialize<T>(&self) -> Result<String, SnapshotError<T::Error>>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + Serialize,
+    {
+        let value = resolve::<T>(&self.world).map_err(SnapshotError::Resolve)?;
+
+        serde_json::to_string_pretty(&value).map_err(SnapshotError::Serialize)
+    }
+}
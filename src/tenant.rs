@@ -0,0 +1,187 @@
+//! # Tenant-typed dependencies
+//!
+//! For multi-tenant applications known at compile time, the tenant can be part of the type of a
+//! dependency: `Billing<1>` and `Billing<2>` are distinct types resolved by a single
+//! `impl<const ID: u32> FromWorld for Billing<ID>`, reading the configuration of the tenant `ID`
+//! from the World. The tenant costs nothing at runtime, every `ID` gets its own monomorphized
+//! implementation, and mixing up two tenants is a type error.
+//!
+//! # Per-tenant configuration
+//!
+//! The World holds the configuration of every tenant in a [`Tenants<C>`] map, a lookup by
+//! `ID` inside `from_world`, and exposes it through [`AsRef<Tenants<C>>`]. [`Tenant<ID, C, World>`]
+//! resolves the configuration of the tenant `ID` itself, failing with [`TenantNotFound`] when the
+//! World has none.
+//!
+//! # Caching
+//!
+//! Containers caching by type, like
+//! [`SingletonContainer`](crate::singleton::SingletonContainer), key their entries by
+//! [`TypeId`](core::any::TypeId): `Billing<1>` and `Billing<2>` are cached separately.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, tenant::{Tenant, TenantNotFound, Tenants}};
+//!
+//! #[derive(Clone)]
+//! struct TenantConfig {
+//!     currency: &'static str,
+//! }
+//!
+//! struct World {
+//!     tenants: Tenants<TenantConfig>,
+//! }
+//!
+//! impl AsRef<Tenants<TenantConfig>> for World {
+//!     fn as_ref(&self) -> &Tenants<TenantConfig> {
+//!         &self.tenants
+//!     }
+//! }
+//!
+//! struct Billing<const ID: u32> {
+//!     currency: &'static str,
+//! }
+//!
+//! impl<const ID: u32> FromWorld for Billing<ID> {
+//!     type World<'a> = World;
+//!     type Error = TenantNotFound;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         let config = world.tenants.get(ID).ok_or(TenantNotFound { id: ID })?;
+//!         Ok(Self { currency: config.currency })
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World {
+//!     tenants: Tenants::from_iter([
+//!         (1, TenantConfig { currency: "EUR" }),
+//!         (2, TenantConfig { currency: "USD" }),
+//!     ]),
+//! });
+//!
+//! let europe: Billing<1> = container.extract().unwrap();
+//! let america: Billing<2> = container.extract().unwrap();
+//! assert_eq!((europe.currency, america.currency), ("EUR", "USD"));
+//!
+//! let missing = container.extract::<Billing<3>>();
+//! assert_eq!(missing.err(), Some(TenantNotFound { id: 3 }));
+//!
+//! let config: Tenant<2, TenantConfig, World> = container.extract().unwrap();
+//! assert_eq!((config.id(), config.currency), (2, "USD"));
+//!
+//! // cached separately per tenant
+//! let singletons = container.into_singleton();
+//! let europe = singletons.extract_arc::<Billing<1>>().unwrap();
+//! let america = singletons.extract_arc::<Billing<2>>().unwrap();
+//! assert_eq!((europe.currency, america.currency), ("EUR", "USD"));
+//! ```
+
+use crate::FromWorld;
+use alloc::collections::BTreeMap;
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+/// # Configuration of every tenant, by tenant ID
+#[derive(Debug, Clone)]
+pub struct Tenants<C> {
+    configs: BTreeMap<u32, C>,
+}
+
+impl<C> Default for Tenants<C> {
+    fn default() -> Self {
+        Self {
+            configs: BTreeMap::new(),
+        }
+    }
+}
+
+impl<C> Tenants<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the configuration of the tenant `id`, returning the previous one
+    pub fn insert(&mut self, id: u32, config: C) -> Option<C> {
+        self.configs.insert(id, config)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&C> {
+        self.configs.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+}
+
+impl<C> FromIterator<(u32, C)> for Tenants<C> {
+    fn from_iter<I: IntoIterator<Item = (u32, C)>>(iter: I) -> Self {
+        Self {
+            configs: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// # The World has no configuration for the tenant `id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TenantNotFound {
+    pub id: u32,
+}
+
+impl fmt::Display for TenantNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no configuration for the tenant {}", self.id)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TenantNotFound {}
+
+/// # Configuration of the tenant `ID`, cloned from the [`Tenants`] of the World
+///
+/// `Error` defaults to [`TenantNotFound`], any error implementing `From<TenantNotFound>` can be
+/// used instead
+pub struct Tenant<const ID: u32, C, World, Error = TenantNotFound> {
+    config: C,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<const ID: u32, C, World, Error> Tenant<ID, C, World, Error> {
+    pub fn id(&self) -> u32 {
+        ID
+    }
+
+    pub fn into_inner(self) -> C {
+        self.config
+    }
+}
+
+impl<const ID: u32, C, World, Error> Deref for Tenant<ID, C, World, Error> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+impl<const ID: u32, C, W, E> FromWorld for Tenant<ID, C, W, E>
+where
+    C: Clone,
+    W: AsRef<Tenants<C>>,
+    E: From<TenantNotFound>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let config = world.as_ref().get(ID).ok_or(TenantNotFound { id: ID })?;
+
+        Ok(Self {
+            config: config.clone(),
+            _marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,239 @@
+//! # Timeouts
+//!
+//! [`DIContainer::extract_async_timeout`] bounds the time an async extraction may take,
+//! [`DIContainer::extract_async_or_after`] falls back to a default value instead: use live data,
+//! but don't block the request forever.
+//!
+//! # Deadlines
+//!
+//! A duration restarts the clock on every extraction, for a budget shared by all the operations
+//! of a request [`DIContainer::extract_async_until`] takes an absolute [`Instant`] instead,
+//! usually propagated with the request. It is equivalent to
+//! [`extract_async_timeout`](DIContainer::extract_async_timeout) with the duration left until the
+//! deadline, except that a deadline already past fails with [`DeadlineError::DeadlineExceeded`]
+//! without starting the resolution, where a zero duration would still poll it once.
+//!
+//! The timer is the one of the [`DefaultRuntime`](crate::runtime::DefaultRuntime), the `_on`
+//! variants take the [`Runtime`] instead, see [runtimes](crate::runtime). The `timeout` feature
+//! enables `runtime-tokio`, the variants without `_on` are always available.
+//!
+//! # Cancellation
+//!
+//! On timeout the resolution future is dropped: the resolution stops at the await point it was
+//! suspended on, the values already resolved are dropped and anything started but not finished
+//! (a half-open connection, a request in flight) is abandoned. Dependencies resolved under a
+//! timeout should be cancel safe, a resolution must not be left half applied to shared state.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromAsyncWorld, async_trait};
+//! use std::time::Duration;
+//!
+//! struct World;
+//!
+//! struct Rates(f64);
+//!
+//! #[async_trait]
+//! impl FromAsyncWorld for Rates {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+//!         tokio::time::sleep(Duration::from_secs(60)).await;
+//!         Ok(Self(1.08))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let container = DIContainer::new(World);
+//!
+//! let rates = container
+//!     .extract_async_or_after(Duration::from_millis(10), Rates(1.0))
+//!     .await
+//!     .unwrap();
+//! assert_eq!(rates.0, 1.0);
+//! # });
+//! ```
+
+use crate::{
+    DIContainer, FromAsyncWorld,
+    resolve::resolve_async,
+    runtime::{Runtime, race_timer},
+};
+use std::time::{Duration, Instant};
+
+/// # Error of an extraction bounded in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    Elapsed(Duration),
+    Resolve(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeoutError::Elapsed(duration) => {
+                write!(f, "resolution timed out after {duration:?}")
+            }
+            TimeoutError::Resolve(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for TimeoutError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TimeoutError::Elapsed(_) => None,
+            TimeoutError::Resolve(error) => Some(error),
+        }
+    }
+}
+
+/// # Error of an extraction bounded by a deadline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadlineError<E> {
+    /// The deadline passed before or during the resolution
+    DeadlineExceeded,
+    Resolve(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for DeadlineError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeadlineError::DeadlineExceeded => write!(f, "resolution deadline exceeded"),
+            DeadlineError::Resolve(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for DeadlineError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeadlineError::DeadlineExceeded => None,
+            DeadlineError::Resolve(error) => Some(error),
+        }
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// [`extract_async_timeout`](DIContainer::extract_async_timeout) on the timer of `R`
+    pub async fn extract_async_timeout_on<R, T>(
+        &self,
+        duration: Duration,
+    ) -> Result<T, TimeoutError<T::Error>>
+    where
+        R: Runtime,
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        match race_timer(resolve_async::<T>(&self.world), R::sleep(duration)).await {
+            Some(resolved) => resolved.map_err(TimeoutError::Resolve),
+            None => Err(TimeoutError::Elapsed(duration)),
+        }
+    }
+
+    /// [`extract_async_or_after`](DIContainer::extract_async_or_after) on the timer of `R`
+    pub async fn extract_async_or_after_on<R, T>(
+        &self,
+        duration: Duration,
+        default: T,
+    ) -> Result<T, T::Error>
+    where
+        R: Runtime,
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        match self.extract_async_timeout_on::<R, T>(duration).await {
+            Ok(value) => Ok(value),
+            Err(TimeoutError::Elapsed(_)) => Ok(default),
+            Err(TimeoutError::Resolve(error)) => Err(error),
+        }
+    }
+
+    /// [`extract_async_until`](DIContainer::extract_async_until) on the timer of `R`
+    pub async fn extract_async_until_on<R, T>(
+        &self,
+        deadline: Instant,
+    ) -> Result<T, DeadlineError<T::Error>>
+    where
+        R: Runtime,
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        if Instant::now() >= deadline {
+            return Err(DeadlineError::DeadlineExceeded);
+        }
+
+        match race_timer(resolve_async::<T>(&self.world), R::sleep_until(deadline)).await {
+            Some(resolved) => resolved.map_err(DeadlineError::Resolve),
+            None => Err(DeadlineError::DeadlineExceeded),
+        }
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, failing with [`TimeoutError::Elapsed`] if it takes longer than `duration`
+    pub async fn extract_async_timeout<T>(
+        &self,
+        duration: Duration,
+    ) -> Result<T, TimeoutError<T::Error>>
+    where
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        self.extract_async_timeout_on::<crate::runtime::DefaultRuntime, T>(duration)
+            .await
+    }
+
+    /// Extracts `T`, returning `default` if it takes longer than `duration`
+    ///
+    /// Resolution errors occurring within `duration` are returned as is
+    pub async fn extract_async_or_after<T>(
+        &self,
+        duration: Duration,
+        default: T,
+    ) -> Result<T, T::Error>
+    where
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        self.extract_async_or_after_on::<crate::runtime::DefaultRuntime, T>(duration, default)
+            .await
+    }
+
+    /// Extracts `T`, failing with [`DeadlineError::DeadlineExceeded`] if `deadline` passes first
+    ///
+    /// A deadline already past fails without starting the resolution
+    ///
+    /// # Usage
+    /// ```
+    /// # use je_di::{DIContainer, FromAsyncWorld, async_trait, timeout::DeadlineError};
+    /// # use std::time::{Duration, Instant};
+    /// # struct World;
+    /// # struct Rates(f64);
+    /// # #[async_trait]
+    /// # impl FromAsyncWorld for Rates {
+    /// #     type World<'a> = World;
+    /// #     type Error = String;
+    /// #     async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+    /// #         Ok(Self(1.08))
+    /// #     }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let container = DIContainer::new(World);
+    /// let deadline = Instant::now() + Duration::from_secs(1);
+    ///
+    /// let rates: Rates = container.extract_async_until(deadline).await.unwrap();
+    /// assert_eq!(rates.0, 1.08);
+    ///
+    /// let past = Instant::now() - Duration::from_millis(1);
+    /// let rates = container.extract_async_until::<Rates>(past).await;
+    /// assert!(matches!(rates, Err(DeadlineError::DeadlineExceeded)));
+    /// # });
+    /// ```
+    pub async fn extract_async_until<T>(
+        &self,
+        deadline: Instant,
+    ) -> Result<T, DeadlineError<T::Error>>
+    where
+        T: for<'a> FromAsyncWorld<World<'a> = World>,
+    {
+        self.extract_async_until_on::<crate::runtime::DefaultRuntime, T>(deadline)
+            .await
+    }
+}
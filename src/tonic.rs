@@ -0,0 +1,186 @@
+//! # tonic integration
+//!
+//! Dependencies of a gRPC request are declared over [`TonicRequestWorld<'a, World, T>`], the
+//! [`Scoped`] World pairing the container World with the [`tonic::Request`], and resolved with
+//! [`DIContainer::extract_scoped_all`](crate::DIContainer::extract_scoped_all).
+//!
+//! # Dynamic messages
+//!
+//! With the `prost-reflect` feature a generic gRPC gateway, proxying or transcoding methods it
+//! has no generated code for, resolves the request message as a
+//! [`DynamicMessage`](prost_reflect::DynamicMessage): the raw request is decoded with the input
+//! message of the called method, found in a [`DescriptorPool`](prost_reflect::DescriptorPool),
+//! the World of the container.
+//!
+//! The errors are [`tonic::Status`], returned to the client as is:
+//!
+//! - [`Code::Internal`](tonic::Code::Internal) when the request has no [`MethodPath`]
+//! - [`Code::Unimplemented`](tonic::Code::Unimplemented) when the pool has no such method
+//! - [`Code::InvalidArgument`](tonic::Code::InvalidArgument) when the body doesn't decode as the
+//!   input message
+//!
+//! # Descriptor pool plumbing
+//!
+//! The pool holds the descriptors of every service the gateway serves, usually decoded once at
+//! startup from a file descriptor set, generated at build time (`file_descriptor_set_path` of
+//! `tonic-prost-build`, `protoc --descriptor_set_out`) or fetched from the server reflection
+//! service of the upstream:
+//!
+//! ```ignore
+//! let pool = DescriptorPool::decode(include_bytes!("descriptors.bin").as_ref())?;
+//! let container = DIContainer::new(pool);
+//! ```
+//!
+//! The pool is reference counted, cloning it into the container of every connection is cheap.
+//!
+//! A generic gateway receives the requests as raw bytes, e.g. through a pass-through codec, and
+//! the called method from the path of the HTTP request, `/{package}.{Service}/{Method}`, which the
+//! [`tonic::Request`] doesn't keep. The gateway inserts it as a [`MethodPath`] extension when
+//! converting the HTTP request:
+//!
+//! ```ignore
+//! let path = MethodPath::new(http_request.uri().path());
+//! let mut request = tonic::Request::new(body);
+//! request.extensions_mut().insert(path);
+//!
+//! let message: DynamicMessage = container.extract_scoped_all(&request)?;
+//! ```
+//!
+//! [`MethodPath::descriptor`] finds the method in the pool, e.g. for the output message of the
+//! response.
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "prost-reflect")] {
+//! use je_di::{DIContainer, tonic::MethodPath};
+//! use prost_reflect::{
+//!     DescriptorPool, DynamicMessage,
+//!     bytes::Bytes,
+//!     prost_types::{
+//!         DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+//!         MethodDescriptorProto, ServiceDescriptorProto, field_descriptor_proto::Type,
+//!     },
+//! };
+//! use tonic::Code;
+//!
+//! // echo.proto:
+//! //   message EchoRequest { string text = 1; }
+//! //   service Echo { rpc Say(EchoRequest) returns (EchoRequest); }
+//! let pool = DescriptorPool::from_file_descriptor_set(FileDescriptorSet {
+//!     file: vec![FileDescriptorProto {
+//!         name: Some("echo.proto".into()),
+//!         package: Some("echo".into()),
+//!         message_type: vec![DescriptorProto {
+//!             name: Some("EchoRequest".into()),
+//!             field: vec![FieldDescriptorProto {
+//!                 name: Some("text".into()),
+//!                 number: Some(1),
+//!                 r#type: Some(Type::String.into()),
+//!                 ..Default::default()
+//!             }],
+//!             ..Default::default()
+//!         }],
+//!         service: vec![ServiceDescriptorProto {
+//!             name: Some("Echo".into()),
+//!             method: vec![MethodDescriptorProto {
+//!                 name: Some("Say".into()),
+//!                 input_type: Some(".echo.EchoRequest".into()),
+//!                 output_type: Some(".echo.EchoRequest".into()),
+//!                 ..Default::default()
+//!             }],
+//!             ..Default::default()
+//!         }],
+//!         ..Default::default()
+//!     }],
+//! })
+//! .unwrap();
+//!
+//! let container = DIContainer::new(pool);
+//! let request = |path: &str, body: &'static [u8]| {
+//!     let mut request = tonic::Request::new(Bytes::from_static(body));
+//!     request.extensions_mut().insert(MethodPath::new(path));
+//!     request
+//! };
+//!
+//! let message: DynamicMessage = container
+//!     .extract_scoped_all(&request("/echo.Echo/Say", b"\x0a\x02hi"))
+//!     .unwrap();
+//! assert_eq!(message.get_field_by_name("text").unwrap().as_str(), Some("hi"));
+//!
+//! let status = container
+//!     .extract_scoped_all::<DynamicMessage, _>(&request("/echo.Echo/Shout", b""))
+//!     .unwrap_err();
+//! assert_eq!(status.code(), Code::Unimplemented);
+//!
+//! let status = container
+//!     .extract_scoped_all::<DynamicMessage, _>(&request("/echo.Echo/Say", b"\x0a\x05hi"))
+//!     .unwrap_err();
+//! assert_eq!(status.code(), Code::InvalidArgument);
+//! # }
+//! ```
+
+use crate::scope::Scoped;
+
+#[cfg(feature = "prost-reflect")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prost-reflect")))]
+pub use self::reflection::MethodPath;
+
+/// World of the dependencies of a gRPC request, the container World and the request
+pub type TonicRequestWorld<'a, World, T> = Scoped<'a, World, tonic::Request<T>>;
+
+#[cfg(feature = "prost-reflect")]
+mod reflection {
+    use super::TonicRequestWorld;
+    use crate::FromWorld;
+    use prost_reflect::{DescriptorPool, DynamicMessage, MethodDescriptor, bytes::Bytes};
+    use tonic::Status;
+
+    /// # Path of the called gRPC method, `/{package}.{Service}/{Method}`
+    ///
+    /// Inserted in the extensions of the request by the gateway
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MethodPath(String);
+
+    impl MethodPath {
+        pub fn new(path: impl Into<String>) -> Self {
+            Self(path.into())
+        }
+
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+
+        /// The descriptor of the method in `pool`
+        pub fn descriptor(&self, pool: &DescriptorPool) -> Result<MethodDescriptor, Status> {
+            let (service, method) = self
+                .0
+                .strip_prefix('/')
+                .and_then(|path| path.split_once('/'))
+                .ok_or_else(|| {
+                    Status::unimplemented(format!("invalid method path `{}`", self.0))
+                })?;
+
+            pool.get_service_by_name(service)
+                .and_then(|service| service.methods().find(|m| m.name() == method))
+                .ok_or_else(|| Status::unimplemented(format!("unknown method `{}`", self.0)))
+        }
+    }
+
+    impl FromWorld for DynamicMessage {
+        type World<'a> = TonicRequestWorld<'a, DescriptorPool, Bytes>;
+        type Error = Status;
+
+        fn from_world((pool, request): &Self::World<'_>) -> Result<Self, Self::Error> {
+            let method = request
+                .extensions()
+                .get::<MethodPath>()
+                .ok_or_else(|| Status::internal("the request has no method path extension"))?
+                .descriptor(pool)?;
+
+            let input = method.input();
+            DynamicMessage::decode(input.clone(), request.get_ref().clone()).map_err(|error| {
+                Status::invalid_argument(format!("invalid `{}`: {error}", input.full_name()))
+            })
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! # Chrome trace export
+//!
+//! [`DIContainer::extract_traced`] records the resolution of every node of the dependency tree
+//! of `T`, timed, into a [`ChromeTrace`]. [`ChromeTrace::to_json`] serializes it in the
+//! [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+//! load the file in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev) to see the
+//! resolution timeline, nested dependencies below their dependents.
+//!
+//! Each node is a complete event (`"ph": "X"`) named after its type, with its start and
+//! duration in microseconds, and whether it resolved in `args.result`.
+//!
+//! # Scope
+//!
+//! The resolutions are recorded by the resolution hooks of the crate, on the thread calling
+//! `extract_traced`, while it runs: sync resolutions only, nodes resolved on other threads are
+//! not recorded. Overridden nodes are not resolved and not recorded. Recording costs a
+//! thread-local lookup per node when no trace is being recorded.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromDependency, FromWorld};
+//!
+//! struct World;
+//!
+//! struct Config;
+//!
+//! impl FromWorld for Config {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! struct Server;
+//!
+//! impl FromDependency for Server {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!     type Dependency = Config;
+//!
+//!     fn from_dependency(_world: &Self::World<'_>, _config: &Config) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let (server, trace) = container.extract_traced::<Server>();
+//! assert!(server.is_ok());
+//!
+//! let names: Vec<_> = trace.events().iter().map(|event| event.name).collect();
+//! assert!(names[0].ends_with("Server"));
+//! assert!(names[1].ends_with("Config"));
+//!
+//! let json = trace.to_json();
+//! assert!(json.contains(r#""ph":"X""#));
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+/// # Resolution of a node recorded in a [`ChromeTrace`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    /// Type name of the node
+    pub name: &'static str,
+    /// Start of the resolution, relative to the start of the trace
+    pub start: Duration,
+    pub duration: Duration,
+    pub resolved: bool,
+}
+
+/// # Timed resolutions of a dependency tree, in the order they started
+#[derive(Debug, Clone, Default)]
+pub struct ChromeTrace {
+    events: Vec<TraceEvent>,
+}
+
+impl ChromeTrace {
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Serializes the trace as a Chrome trace event JSON array
+    pub fn to_json(&self) -> String {
+        let events = self
+            .events
+            .iter()
+            .map(|event| {
+                serde_json::json!({
+                    "name": event.name,
+                    "cat": "je_di",
+                    "ph": "X",
+                    "ts": event.start.as_secs_f64() * 1e6,
+                    "dur": event.duration.as_secs_f64() * 1e6,
+                    "pid": 1,
+                    "tid": 1,
+                    "args": { "result": if event.resolved { "ok" } else { "error" } },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::Value::Array(events).to_string()
+    }
+}
+
+struct Recording {
+    start: Instant,
+    events: Vec<TraceEvent>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Starts recording the resolution of `T`, when a trace is being recorded on this thread
+pub(crate) fn enter<T>() -> Option<(usize, Instant)> {
+    RECORDING.with(|recording| {
+        let mut recording = recording.borrow_mut();
+        let recording = recording.as_mut()?;
+
+        let now = Instant::now();
+        recording.events.push(TraceEvent {
+            name: std::any::type_name::<T>(),
+            start: now - recording.start,
+            duration: Duration::ZERO,
+            resolved: false,
+        });
+
+        Some((recording.events.len() - 1, now))
+    })
+}
+
+/// Completes the event started by [`enter`]
+pub(crate) fn exit(entered: Option<(usize, Instant)>, resolved: bool) {
+    let Some((index, start)) = entered else {
+        return;
+    };
+
+    RECORDING.with(|recording| {
+        if let Some(event) = recording
+            .borrow_mut()
+            .as_mut()
+            .and_then(|recording| recording.events.get_mut(index))
+        {
+            event.duration = start.elapsed();
+            event.resolved = resolved;
+        }
+    });
+}
+
+/// Restores the recording in progress before `extract_traced`, even if the resolution panics
+struct RecordingGuard {
+    previous: Option<Recording>,
+}
+
+impl RecordingGuard {
+    fn start() -> Self {
+        let recording = Recording {
+            start: Instant::now(),
+            events: Vec::new(),
+        };
+
+        Self {
+            previous: RECORDING.with(|current| current.replace(Some(recording))),
+        }
+    }
+
+    fn finish(self) -> ChromeTrace {
+        let recording = RECORDING.with(|current| current.borrow_mut().take());
+
+        ChromeTrace {
+            events: recording
+                .map(|recording| recording.events)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Drop for RecordingGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RECORDING.with(|current| current.replace(previous));
+    }
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, recording the resolution of its dependency tree
+    pub fn extract_traced<T>(&self) -> (Result<T, T::Error>, ChromeTrace)
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        let guard = RecordingGuard::start();
+        let result = resolve::<T>(&self.world);
+
+        (result, guard.finish())
+    }
+}
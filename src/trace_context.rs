@@ -0,0 +1,310 @@
+//! # Trace context
+//!
+//! Dependencies calling other services propagate the trace they are resolved in.
+//! [`TraceContext<World>`] captures the current [`tracing::Span`] when resolved, from any World,
+//! sync or async. With the `axum` feature, [`RequestTraceContext<State>`] also reads the W3C
+//! [`traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header) header of the request
+//! into a [`TraceParent`].
+//!
+//! With the `opentelemetry` feature both also carry an [`opentelemetry::Context`]: the current
+//! context for [`TraceContext`], the context extracted from the request headers by the global
+//! text map propagator for [`RequestTraceContext`].
+//!
+//! # Propagation
+//!
+//! The context is captured once, when the dependency is resolved, not when it is used: a client
+//! resolved inside the span of a request keeps that span, even when used from a task the span
+//! isn't entered in. Instrument spawned work with a clone of [`TraceContext::span`] to keep it in
+//! the trace.
+//!
+//! The [`TraceParent`] of a request identifies the caller's span. A call to a downstream service
+//! continues the same trace as a child of the span making the call: send the `traceparent` from
+//! [`TraceParent::with_parent_id`] with the id of that span, the trace id and the flags are kept.
+//! Forwarding the incoming header unchanged makes the downstream call a sibling of the current
+//! service in the trace. A request without a valid header starts a new trace, the parent is
+//! `None`.
+//!
+//! The OpenTelemetry context is extracted by the propagator installed with
+//! [`opentelemetry::global::set_text_map_propagator`], without one it is the empty context.
+//! Inject it into outgoing requests with the same propagator.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, trace_context::{TraceContext, TraceParent}};
+//!
+//! struct World;
+//!
+//! let container = DIContainer::new(World);
+//!
+//! let span = tracing::info_span!("request");
+//! let context: TraceContext<World> = span.in_scope(|| container.extract().unwrap());
+//! assert_eq!(context.span().id(), span.id());
+//!
+//! let parent: TraceParent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse().unwrap();
+//! assert_eq!(parent.trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+//! assert!(parent.is_sampled());
+//!
+//! let child = parent.with_parent_id(0x53995c3f42cd8ad8);
+//! assert_eq!(child.to_string(), "00-4bf92f3577b34da6a3ce929d0e0e4736-53995c3f42cd8ad8-01");
+//!
+//! assert!("00-00000000000000000000000000000000-00f067aa0ba902b7-01".parse::<TraceParent>().is_err());
+//!
+//! # #[cfg(feature = "axum")] {
+//! use axum::http::Request;
+//! use je_di::{FromAsyncWorld, trace_context::RequestTraceContext};
+//!
+//! let (parts, ()) = Request::builder()
+//!     .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+//!     .body(())
+//!     .unwrap()
+//!     .into_parts();
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let context = RequestTraceContext::<()>::from_world(&(&parts, &())).await.unwrap();
+//! assert_eq!(context.parent(), Some(parent));
+//! # });
+//! # }
+//! ```
+
+use crate::{FromAsyncWorld, FromWorld, async_trait};
+use std::{convert::Infallible, fmt, marker::PhantomData, str::FromStr};
+use tracing::Span;
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub use self::request::RequestTraceContext;
+
+/// # Span current when the dependency was resolved
+///
+/// The World is not read, set it to the World of the dependency tree
+pub struct TraceContext<World, Error = Infallible> {
+    span: Span,
+    #[cfg(feature = "opentelemetry")]
+    otel: opentelemetry::Context,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<World, Error> TraceContext<World, Error> {
+    fn current() -> Self {
+        Self {
+            span: Span::current(),
+            #[cfg(feature = "opentelemetry")]
+            otel: opentelemetry::Context::current(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.otel
+    }
+}
+
+impl<World, Error> FromWorld for TraceContext<World, Error> {
+    type World<'a> = World;
+    type Error = Error;
+
+    fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        Ok(Self::current())
+    }
+}
+
+#[async_trait]
+impl<World, Error> FromAsyncWorld for TraceContext<World, Error>
+where
+    World: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    type World<'a> = World;
+    type Error = Error;
+
+    async fn from_world<'a>(_world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+        Ok(Self::current())
+    }
+}
+
+/// # W3C `traceparent` header
+///
+/// Parsed from and formatted as `{version}-{trace-id}-{parent-id}-{flags}`, always formatted with
+/// version `00`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceParent {
+    trace_id: u128,
+    parent_id: u64,
+    flags: u8,
+}
+
+impl TraceParent {
+    /// Name of the header
+    pub const HEADER: &'static str = "traceparent";
+
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// Id of the caller's span
+    pub fn parent_id(&self) -> u64 {
+        self.parent_id
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.flags & 1 == 1
+    }
+
+    /// The same trace, with `parent_id` as the caller's span, to propagate downstream
+    pub fn with_parent_id(self, parent_id: u64) -> Self {
+        Self { parent_id, ..self }
+    }
+}
+
+impl FromStr for TraceParent {
+    type Err = InvalidTraceParent;
+
+    fn from_str(header: &str) -> Result<Self, Self::Err> {
+        let mut parts = header.trim().split('-');
+        let (Some(version), Some(trace_id), Some(parent_id), Some(flags)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(InvalidTraceParent);
+        };
+
+        // later versions may append fields, version 00 has exactly 4
+        let version = hex::<u8>(version, 2)?;
+        if version == 0xff || (version == 0 && parts.next().is_some()) {
+            return Err(InvalidTraceParent);
+        }
+
+        let trace_parent = Self {
+            trace_id: hex(trace_id, 32)?,
+            parent_id: hex(parent_id, 16)?,
+            flags: hex(flags, 2)?,
+        };
+
+        match trace_parent.trace_id != 0 && trace_parent.parent_id != 0 {
+            true => Ok(trace_parent),
+            false => Err(InvalidTraceParent),
+        }
+    }
+}
+
+fn hex<T: TryFrom<u128>>(field: &str, len: usize) -> Result<T, InvalidTraceParent> {
+    let valid = field.len() == len
+        && field
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte));
+    if !valid {
+        return Err(InvalidTraceParent);
+    }
+
+    u128::from_str_radix(field, 16)
+        .ok()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or(InvalidTraceParent)
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id, self.parent_id, self.flags
+        )
+    }
+}
+
+/// # Error parsing a [`TraceParent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTraceParent;
+
+impl fmt::Display for InvalidTraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid traceparent header")
+    }
+}
+
+impl std::error::Error for InvalidTraceParent {}
+
+#[cfg(feature = "axum")]
+mod request {
+    use super::TraceParent;
+    use crate::{FromAsyncWorld, async_trait, axum::AxumRequestPartsWorld};
+    use std::{convert::Infallible, marker::PhantomData};
+    use tracing::Span;
+
+    /// # Trace context of an axum request
+    ///
+    /// The span current when the dependency was resolved, and the [`TraceParent`] of the request
+    pub struct RequestTraceContext<State> {
+        span: Span,
+        parent: Option<TraceParent>,
+        #[cfg(feature = "opentelemetry")]
+        otel: opentelemetry::Context,
+        _marker: PhantomData<fn() -> State>,
+    }
+
+    impl<State> RequestTraceContext<State> {
+        pub fn span(&self) -> &Span {
+            &self.span
+        }
+
+        /// The `traceparent` of the request, `None` when missing or invalid
+        pub fn parent(&self) -> Option<TraceParent> {
+            self.parent
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+        pub fn otel_context(&self) -> &opentelemetry::Context {
+            &self.otel
+        }
+    }
+
+    #[async_trait]
+    impl<State> FromAsyncWorld for RequestTraceContext<State>
+    where
+        State: Send + Sync + 'static,
+    {
+        type World<'a> = AxumRequestPartsWorld<'a, State>;
+        type Error = Infallible;
+
+        async fn from_world<'a>(world: &'a Self::World<'a>) -> Result<Self, Self::Error> {
+            let headers = &world.0.headers;
+
+            Ok(Self {
+                span: Span::current(),
+                parent: headers
+                    .get(TraceParent::HEADER)
+                    .and_then(|header| header.to_str().ok())
+                    .and_then(|header| header.parse().ok()),
+                #[cfg(feature = "opentelemetry")]
+                otel: opentelemetry::global::get_text_map_propagator(|propagator| {
+                    propagator.extract(&HeaderExtractor(headers))
+                }),
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    struct HeaderExtractor<'a>(&'a axum::http::HeaderMap);
+
+    #[cfg(feature = "opentelemetry")]
+    impl opentelemetry::propagation::Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|key| key.as_str()).collect()
+        }
+    }
+}
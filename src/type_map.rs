@@ -0,0 +1,176 @@
+//! # Runtime registered instances
+//!
+//! Alongside the static fields of the World, a [`TypeMap`] holds instances registered at runtime
+//! by type, e.g. the configurations of the plugins loaded at startup. [`Registered<T, World>`]
+//! resolves by cloning the instance of type `T`, failing with [`TypeNotRegistered`] when none was
+//! registered.
+//!
+//! The World exposes the map through [`AsRef<TypeMap>`].
+//!
+//! # Coherence
+//!
+//! A blanket `impl<T: Clone> FromWorld for T` would overlap with the
+//! [`FromDependency`](crate::FromDependency) blanket implementation, [`Registered`] carries the
+//! World type instead.
+//!
+//! # Cloning
+//!
+//! Dependencies are owned values while the instances stay in the map for the next resolutions:
+//! `T` must be [`Clone`]. Register an `Arc<T>` for instances expensive to clone or that must be
+//! shared.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer,
+//!     type_map::{Registered, TypeMap, TypeNotRegistered},
+//! };
+//!
+//! #[derive(Clone)]
+//! struct S3Config {
+//!     bucket: &'static str,
+//! }
+//!
+//! struct World {
+//!     plugins: TypeMap,
+//! }
+//!
+//! impl AsRef<TypeMap> for World {
+//!     fn as_ref(&self) -> &TypeMap {
+//!         &self.plugins
+//!     }
+//! }
+//!
+//! let mut plugins = TypeMap::new();
+//! plugins.insert(S3Config { bucket: "assets" });
+//!
+//! let container = DIContainer::new(World { plugins });
+//!
+//! let config: Registered<S3Config, World> = container.extract().unwrap();
+//! assert_eq!(config.bucket, "assets");
+//!
+//! let missing = container.extract::<Registered<u32, World>>();
+//! assert!(matches!(missing, Err(TypeNotRegistered { .. })));
+//! ```
+
+use crate::FromWorld;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    ops::Deref,
+};
+
+/// # Instances stored by type
+#[derive(Default)]
+pub struct TypeMap {
+    instances: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl TypeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `value`, returning the instance of the same type it replaces
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.instances
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.instances.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.instances.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.instances
+            .remove(&TypeId::of::<T>())?
+            .downcast()
+            .ok()
+            .map(|value| *value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+impl fmt::Debug for TypeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeMap")
+            .field("len", &self.instances.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// # No instance of the type was registered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeNotRegistered {
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for TypeNotRegistered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no instance of {} is registered", self.type_name)
+    }
+}
+
+impl std::error::Error for TypeNotRegistered {}
+
+/// # Clone of the instance of `T` registered in the [`TypeMap`] of the World
+///
+/// See the [error of the dependency](crate::FromDependency#error-of-the-dependency)
+pub struct Registered<T, World, Error = TypeNotRegistered> {
+    pub value: T,
+    _marker: PhantomData<fn() -> (World, Error)>,
+}
+
+impl<T, World, Error> Registered<T, World, Error> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, World, Error> Deref for Registered<T, World, Error> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, W, E> FromWorld for Registered<T, W, E>
+where
+    T: Clone + 'static,
+    W: AsRef<TypeMap>,
+    E: From<TypeNotRegistered>,
+{
+    type World<'a> = W;
+    type Error = E;
+
+    fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+        let value = world
+            .as_ref()
+            .get::<T>()
+            .cloned()
+            .ok_or(TypeNotRegistered {
+                type_name: std::any::type_name::<T>(),
+            })?;
+
+        Ok(Self {
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
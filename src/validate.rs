@@ -0,0 +1,104 @@
+//! # Validated dependencies
+//!
+//! Some dependencies are constructible but semantically invalid, e.g. a client with an empty
+//! URL or a pool with a maximum size of zero. [`DIContainer::extract_validated`] resolves `T`,
+//! then runs [`Validate::validate`] on it and fails the extraction when it is invalid, instead
+//! of failing at the first use of the dependency.
+//!
+//! Only the extracted root is validated: its dependencies are resolved as usual, validate them
+//! from the [`Validate`] implementation of the root, or extract them validated themselves.
+//!
+//! # Errors
+//!
+//! The extraction returns the error of the construction, [`FromWorld::Error`], the error of the
+//! validation is converted into it with `From`. A dependency whose construction and validation
+//! fail alike uses the same error type for both, nothing is converted. With distinct types,
+//! implement `From<ValidationError> for ConstructionError`, e.g. with an enum wrapping both
+//! causes, the validation errors then stay distinguishable from construction errors.
+//!
+//! # Usage
+//! ```
+//! use je_di::{DIContainer, FromWorld, validate::Validate};
+//!
+//! struct World {
+//!     url: &'static str,
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum ClientError {
+//!     Invalid(&'static str),
+//! }
+//!
+//! struct Client {
+//!     url: &'static str,
+//! }
+//!
+//! impl FromWorld for Client {
+//!     type World<'a> = World;
+//!     type Error = ClientError;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { url: world.url })
+//!     }
+//! }
+//!
+//! impl Validate for Client {
+//!     type Error = ClientError;
+//!
+//!     fn validate(&self) -> Result<(), Self::Error> {
+//!         match self.url {
+//!             "" => Err(ClientError::Invalid("empty url")),
+//!             _ => Ok(()),
+//!         }
+//!     }
+//! }
+//!
+//! let container = DIContainer::new(World { url: "https://example.com" });
+//! assert!(container.extract_validated::<Client>().is_ok());
+//!
+//! let container = DIContainer::new(World { url: "" });
+//! let error = container.extract_validated::<Client>().err();
+//! assert_eq!(error, Some(ClientError::Invalid("empty url")));
+//!
+//! // the unvalidated extraction still succeeds
+//! assert!(container.extract::<Client>().is_ok());
+//! ```
+
+use crate::{DIContainer, FromWorld, dependency::ErrorFrom, resolve::resolve};
+
+/// # Validation of a constructed dependency
+pub trait Validate {
+    type Error;
+
+    fn validate(&self) -> Result<(), Self::Error>;
+}
+
+impl<World> DIContainer<World> {
+    /// Extracts `T`, then fails if it is not valid
+    pub fn extract_validated<T>(&self) -> Result<T, <T as FromWorld>::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + Validate,
+        <T as FromWorld>::Error: ErrorFrom<<T as Validate>::Error>,
+    {
+        let value = resolve::<T>(&self.world)?;
+        value.validate().map_err(ErrorFrom::error_from)?;
+
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World> DIContainer<World> {
+    /// Async variant of [`extract_validated`](DIContainer::extract_validated)
+    pub async fn extract_validated_async<T>(&self) -> Result<T, <T as crate::FromAsyncWorld>::Error>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World> + Validate,
+        <T as crate::FromAsyncWorld>::Error: ErrorFrom<<T as Validate>::Error>,
+    {
+        let value = crate::resolve::resolve_async::<T>(&self.world).await?;
+        value.validate().map_err(ErrorFrom::error_from)?;
+
+        Ok(value)
+    }
+}
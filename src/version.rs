@@ -0,0 +1,145 @@
+//! # Schema versioned extraction
+//!
+//! Guards against wiring a dependency against a World it was not written for: the World reports
+//! its schema version through [`SchemaVersion`], dependencies declare the version they expect
+//! through [`RequiresVersion`] and [`DIContainer::extract_versioned`] fails fast on a mismatch,
+//! before calling `from_world`.
+//!
+//! # Usage
+//! ```
+//! use je_di::{
+//!     DIContainer, FromWorld,
+//!     version::{RequiresVersion, SchemaVersion, VersionedError},
+//! };
+//!
+//! struct World {
+//!     version: u32,
+//! }
+//!
+//! impl SchemaVersion for World {
+//!     fn schema_version(&self) -> u32 {
+//!         self.version
+//!     }
+//! }
+//!
+//! struct Printer;
+//!
+//! impl FromWorld for Printer {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(_world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self)
+//!     }
+//! }
+//!
+//! impl RequiresVersion for Printer {
+//!     const VERSION: u32 = 2;
+//! }
+//!
+//! let container = DIContainer::new(World { version: 1 });
+//!
+//! let Err(VersionedError::Mismatch(mismatch)) = container.extract_versioned::<Printer>() else {
+//!     panic!("expected a version mismatch");
+//! };
+//! assert_eq!((mismatch.expected, mismatch.found), (2, 1));
+//! ```
+
+use crate::{DIContainer, FromWorld, resolve::resolve};
+
+/// # Accessor for the schema version of a World
+pub trait SchemaVersion {
+    fn schema_version(&self) -> u32;
+}
+
+/// # Schema version a dependency was written for
+pub trait RequiresVersion {
+    const VERSION: u32;
+}
+
+/// # The World schema version doesn't match the one required by a dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionMismatch {
+    pub type_name: &'static str,
+    pub expected: u32,
+    pub found: u32,
+}
+
+impl core::fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} requires schema version {}, found {}",
+            self.type_name, self.expected, self.found
+        )
+    }
+}
+
+impl core::error::Error for VersionMismatch {}
+
+/// # Error returned by versioned extraction
+#[derive(Debug)]
+pub enum VersionedError<E> {
+    Mismatch(VersionMismatch),
+    Resolve(E),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for VersionedError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VersionedError::Mismatch(mismatch) => mismatch.fmt(f),
+            VersionedError::Resolve(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for VersionedError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            VersionedError::Mismatch(mismatch) => Some(mismatch),
+            VersionedError::Resolve(error) => Some(error),
+        }
+    }
+}
+
+fn check_version<T: RequiresVersion>(world: &impl SchemaVersion) -> Result<(), VersionMismatch> {
+    let found = world.schema_version();
+    if found == T::VERSION {
+        Ok(())
+    } else {
+        Err(VersionMismatch {
+            type_name: core::any::type_name::<T>(),
+            expected: T::VERSION,
+            found,
+        })
+    }
+}
+
+impl<World: SchemaVersion> DIContainer<World> {
+    /// Extracts `T` after checking that the World schema version matches
+    /// [`RequiresVersion::VERSION`]
+    pub fn extract_versioned<T>(&self) -> Result<T, VersionedError<T::Error>>
+    where
+        T: for<'a> FromWorld<World<'a> = World> + RequiresVersion,
+    {
+        check_version::<T>(&self.world).map_err(VersionedError::Mismatch)?;
+
+        resolve::<T>(&self.world).map_err(VersionedError::Resolve)
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World: SchemaVersion> DIContainer<World> {
+    /// Async variant of [`DIContainer::extract_versioned`]
+    pub async fn extract_versioned_async<T>(&self) -> Result<T, VersionedError<T::Error>>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World> + RequiresVersion,
+    {
+        check_version::<T>(&self.world).map_err(VersionedError::Mismatch)?;
+
+        crate::resolve::resolve_async::<T>(&self.world)
+            .await
+            .map_err(VersionedError::Resolve)
+    }
+}
@@ -0,0 +1,120 @@
+//! # Watched Worlds
+//!
+//! [`WatchedContainer`] resolves from a World published through a
+//! [`tokio::sync::watch`] channel, [`WatchedContainer::extract_watched`] returns a stream
+//! yielding a freshly resolved `T` for the current World and on every change, e.g. services
+//! rebuilt when feature flags are reconfigured live.
+//!
+//! # Latest only
+//!
+//! The channel only holds the latest World, the sender is never slowed down by the streams: a
+//! stream polled slower than the World changes skips the intermediate Worlds and resolves from the
+//! latest one. Changes published while a resolution is in flight are coalesced into the next item.
+//!
+//! The stream ends when the sender is dropped.
+//!
+//! # Usage
+//! ```
+//! use futures::StreamExt;
+//! use je_di::{FromWorld, watch::WatchedContainer};
+//!
+//! struct Flags {
+//!     beta: bool,
+//! }
+//!
+//! struct Checkout(&'static str);
+//!
+//! impl FromWorld for Checkout {
+//!     type World<'a> = Flags;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(if world.beta { "beta" } else { "stable" }))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let (flags, receiver) = tokio::sync::watch::channel(Flags { beta: false });
+//! let container = WatchedContainer::new(receiver);
+//!
+//! let mut checkouts = std::pin::pin!(container.extract_watched::<Checkout>());
+//! assert_eq!(checkouts.next().await.unwrap().unwrap().0, "stable");
+//!
+//! flags.send_replace(Flags { beta: true });
+//! assert_eq!(checkouts.next().await.unwrap().unwrap().0, "beta");
+//!
+//! drop(flags);
+//! assert!(checkouts.next().await.is_none());
+//! # });
+//! ```
+
+use crate::{FromWorld, resolve::resolve};
+use futures::Stream;
+use tokio::sync::watch::Receiver;
+
+/// # Container resolving from the latest World of a watch channel
+pub struct WatchedContainer<World> {
+    receiver: Receiver<World>,
+}
+
+impl<World> WatchedContainer<World> {
+    pub fn new(receiver: Receiver<World>) -> Self {
+        Self { receiver }
+    }
+
+    pub fn receiver(&self) -> &Receiver<World> {
+        &self.receiver
+    }
+
+    /// Resolves `T` from the current World
+    pub fn extract<T>(&self) -> Result<T, T::Error>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        resolve::<T>(&self.receiver.borrow())
+    }
+
+    /// Resolves `T` from the current World, then again on every change of the World
+    ///
+    /// The World is borrowed while `T` resolves, blocking the sender: keep the resolution short
+    pub fn extract_watched<T>(&self) -> impl Stream<Item = Result<T, T::Error>> + use<T, World>
+    where
+        T: for<'a> FromWorld<World<'a> = World>,
+    {
+        let mut receiver = self.receiver.clone();
+        receiver.mark_changed();
+
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.changed().await.ok()?;
+            let resolved = resolve::<T>(&receiver.borrow_and_update());
+
+            Some((resolved, receiver))
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<World: Clone> WatchedContainer<World> {
+    /// Async variant of [`WatchedContainer::extract_watched`]
+    ///
+    /// The World is cloned out of the channel before resolving, the sender is never blocked by
+    /// the resolution
+    pub fn extract_watched_async<T>(
+        &self,
+    ) -> impl Stream<Item = Result<T, T::Error>> + use<T, World>
+    where
+        T: for<'a> crate::FromAsyncWorld<World<'a> = World>,
+    {
+        let mut receiver = self.receiver.clone();
+        receiver.mark_changed();
+
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.changed().await.ok()?;
+            let world = receiver.borrow_and_update().clone();
+            let resolved = crate::resolve::resolve_async::<T>(&world).await;
+
+            Some((resolved, receiver))
+        })
+    }
+}
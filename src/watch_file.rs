@@ -0,0 +1,226 @@
+//! # Watched configuration files
+//!
+//! [`WatchedFileContainer`] deserializes the World from a configuration file, TOML or YAML
+//! depending on its extension, and reloads it when the file changes: the reloaded World is
+//! published to the underlying [`WatchedContainer`], whose
+//! [`extract_watched`](WatchedContainer::extract_watched) streams re-resolve the watched
+//! dependencies, e.g. for long-running services with an editable configuration.
+//!
+//! The file is watched with [`notify`], through the directory holding it: editors saving by
+//! writing a new file and renaming it over the old one are followed as well.
+//!
+//! # Debounce
+//!
+//! Saving a file usually emits several events, truncation, writes, renames. The file is reloaded
+//! once the events have stopped for the debounce delay, [`DEFAULT_DEBOUNCE`] unless set with
+//! [`WatchedFileContainer::open_with_debounce`]: a burst of changes is a single reload, of the
+//! final content. The reloads run on a thread of their own, stopped when the container drops.
+//!
+//! # Error recovery
+//!
+//! The file must be valid when opening the container, the error is returned otherwise. On a
+//! reload, a file that doesn't deserialize, e.g. saved halfway through an edit, keeps the last
+//! good World published: the watched dependencies are not re-resolved and keep running on it.
+//! The error is kept until [taken](WatchedFileContainer::take_reload_error) or the next
+//! successful reload, watching continues and the next valid save is picked up.
+//!
+//! # Usage
+//! ```
+//! use futures::StreamExt;
+//! use je_di::{FromWorld, watch_file::WatchedFileContainer};
+//! use std::time::Duration;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct World {
+//!     greeting: String,
+//! }
+//!
+//! struct Greeter(String);
+//!
+//! impl FromWorld for Greeter {
+//!     type World<'a> = World;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self(world.greeting.clone()))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let path = std::env::temp_dir().join(format!("je-di-watch-{}.toml", std::process::id()));
+//! std::fs::write(&path, "greeting = 'hello'").unwrap();
+//!
+//! let container =
+//!     WatchedFileContainer::<World>::open_with_debounce(&path, Duration::from_millis(20)).unwrap();
+//! let mut greeters = std::pin::pin!(container.extract_watched::<Greeter>());
+//! assert_eq!(greeters.next().await.unwrap().unwrap().0, "hello");
+//!
+//! std::fs::write(&path, "greeting = 'bonjour'").unwrap();
+//! assert_eq!(greeters.next().await.unwrap().unwrap().0, "bonjour");
+//!
+//! // an invalid save keeps the last good World
+//! std::fs::write(&path, "greeting = ").unwrap();
+//! while container.take_reload_error().is_none() {
+//!     tokio::time::sleep(Duration::from_millis(10)).await;
+//! }
+//! assert_eq!(container.extract::<Greeter>().unwrap().0, "bonjour");
+//! # std::fs::remove_file(&path).unwrap();
+//! # });
+//! ```
+
+use crate::{config::WorldConfigError, watch::WatchedContainer};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, PoisonError,
+        mpsc::{self, RecvTimeoutError},
+    },
+    time::Duration,
+};
+
+/// Quiet period after the last change of the file before reloading it
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// # Error opening a [`WatchedFileContainer`]
+#[derive(Debug)]
+pub enum WatchedFileError {
+    /// The file doesn't deserialize as the World
+    Load(WorldConfigError),
+    /// The file can't be watched
+    Watch(notify::Error),
+}
+
+impl fmt::Display for WatchedFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchedFileError::Load(error) => error.fmt(f),
+            WatchedFileError::Watch(error) => write!(f, "failed to watch the file: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for WatchedFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WatchedFileError::Load(error) => Some(error),
+            WatchedFileError::Watch(error) => Some(error),
+        }
+    }
+}
+
+fn load<World: DeserializeOwned>(path: &Path) -> Result<World, WorldConfigError> {
+    config::Config::builder()
+        .add_source(config::File::from(path))
+        .build()
+        .and_then(config::Config::try_deserialize)
+        .map_err(|source| WorldConfigError {
+            world: std::any::type_name::<World>(),
+            source,
+        })
+}
+
+/// # Container over a World loaded from a file, reloaded when it changes
+///
+/// Dereferences to the [`WatchedContainer`] the World is published to. See the
+/// [module documentation](crate::watch_file)
+pub struct WatchedFileContainer<World> {
+    container: WatchedContainer<World>,
+    reload_error: Arc<Mutex<Option<WorldConfigError>>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl<World> WatchedFileContainer<World>
+where
+    World: DeserializeOwned + Send + Sync + 'static,
+{
+    /// Loads the World from `path` and watches it, with the [`DEFAULT_DEBOUNCE`]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WatchedFileError> {
+        Self::open_with_debounce(path, DEFAULT_DEBOUNCE)
+    }
+
+    /// Loads the World from `path` and watches it, reloading `debounce` after the last change
+    pub fn open_with_debounce(
+        path: impl AsRef<Path>,
+        debounce: Duration,
+    ) -> Result<Self, WatchedFileError> {
+        let path = std::path::absolute(path.as_ref())
+            .map_err(|error| WatchedFileError::Watch(error.into()))?;
+        let world = load::<World>(&path).map_err(WatchedFileError::Load)?;
+        let (sender, receiver) = tokio::sync::watch::channel(world);
+
+        let (changes, changed) = mpsc::channel();
+        let file_name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let is_file = |changed: &PathBuf| changed.file_name() == file_name.as_deref();
+
+                if (event.kind.is_create() || event.kind.is_modify())
+                    && event.paths.iter().any(is_file)
+                {
+                    let _ = changes.send(());
+                }
+            })
+            .map_err(WatchedFileError::Watch)?;
+
+        let directory = path.parent().unwrap_or(&path);
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(WatchedFileError::Watch)?;
+
+        let reload_error = Arc::new(Mutex::new(None));
+        let errors = reload_error.clone();
+        std::thread::spawn(move || {
+            // the watcher owns the sender of the changes, dropping the container stops the thread
+            while changed.recv().is_ok() {
+                loop {
+                    match changed.recv_timeout(debounce) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+
+                let reloaded = load::<World>(&path);
+                let mut error = errors.lock().unwrap_or_else(PoisonError::into_inner);
+                match reloaded {
+                    Ok(world) => {
+                        sender.send_replace(world);
+                        *error = None;
+                    }
+                    Err(reload_error) => *error = Some(reload_error),
+                }
+            }
+        });
+
+        Ok(Self {
+            container: WatchedContainer::new(receiver),
+            reload_error,
+            _watcher: watcher,
+        })
+    }
+}
+
+impl<World> WatchedFileContainer<World> {
+    /// Takes the error of the last reload, `None` if it succeeded or was already taken
+    pub fn take_reload_error(&self) -> Option<WorldConfigError> {
+        self.reload_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+impl<World> Deref for WatchedFileContainer<World> {
+    type Target = WatchedContainer<World>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.container
+    }
+}
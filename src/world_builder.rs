@@ -0,0 +1,90 @@
+//! # World builders
+//!
+//! Building a large World positionally makes it easy to swap two fields of the same type.
+//! With the `derive` feature, `#[derive(WorldBuilder)]` generates a fluent builder for it,
+//! checking at compile time that every required field is set.
+//!
+//! # Generated API
+//!
+//! For a World `AppWorld`:
+//!
+//! - `AppWorld::builder()` returns an `AppWorldBuilder` with no field set
+//! - a setter per field, named after it, taking `impl Into<FieldType>`
+//! - `build()` returns the World and `build_container()` a [`DIContainer`](crate::DIContainer)
+//!   owning it, both are only available once every required field is set
+//!
+//! Fields are required unless they are annotated with `#[builder(default)]`, falling back to
+//! [`Default::default`], or `#[builder(default = expr)]`, or are an `Option<T>`, whose setter
+//! takes `impl Into<T>` and which falls back to `None`.
+//!
+//! The builder tracks each required field with a type parameter, [`Unset`] until the setter is
+//! called and [`Set`] afterwards: forgetting a field leaves `build` undefined on the builder,
+//! setting it twice leaves its setter undefined.
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use je_di::{FromWorld, WorldBuilder};
+//!
+//! #[derive(WorldBuilder)]
+//! struct AppWorld {
+//!     /// Connection string of the database
+//!     database_url: String,
+//!     #[builder(default = 8080)]
+//!     port: u16,
+//!     #[builder(default)]
+//!     verbose: bool,
+//!     api_key: Option<String>,
+//! }
+//!
+//! struct Server {
+//!     address: String,
+//! }
+//!
+//! impl FromWorld for Server {
+//!     type World<'a> = AppWorld;
+//!     type Error = String;
+//!
+//!     fn from_world(world: &Self::World<'_>) -> Result<Self, Self::Error> {
+//!         Ok(Self { address: format!("0.0.0.0:{}", world.port) })
+//!     }
+//! }
+//!
+//! let world = AppWorld::builder()
+//!     .database_url("postgres://localhost")
+//!     .api_key("secret")
+//!     .build();
+//! assert_eq!(world.port, 8080);
+//! assert!(!world.verbose);
+//! assert_eq!(world.api_key.as_deref(), Some("secret"));
+//!
+//! let container = AppWorld::builder()
+//!     .port(3000u16)
+//!     .database_url("postgres://localhost")
+//!     .build_container();
+//! let server: Server = container.extract().unwrap();
+//! assert_eq!(server.address, "0.0.0.0:3000");
+//! # }
+//! ```
+//!
+//! A missing required field doesn't compile:
+//!
+//! ```compile_fail
+//! use je_di::WorldBuilder;
+//!
+//! #[derive(WorldBuilder)]
+//! struct AppWorld {
+//!     database_url: String,
+//!     port: u16,
+//! }
+//!
+//! let world = AppWorld::builder().port(8080u16).build();
+//! ```
+
+/// Marks a required field as set on a generated World builder
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Set;
+
+/// Marks a required field as not set yet on a generated World builder
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unset;